@@ -2,6 +2,10 @@
 //!
 //! clapのderiveパターンを使用して、サブコマンドを持つCLIインターフェースを定義します。
 
+use crate::config::{ConfigFormat, GroupMode, Selector};
+use crate::scanner::{ProjectType, SortCriterion};
+use crate::shell_init::Shell;
+use crate::ui::{ColorMode, ListFormat};
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
@@ -9,7 +13,7 @@ use std::path::PathBuf;
 ///
 /// 登録したディレクトリからプロジェクトを検索し、
 /// あいまい検索で選択してエディタで開きます。
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(
     name = "quick-proj",
     version,
@@ -33,38 +37,538 @@ pub struct Args {
     /// 検索の最大深度
     #[arg(short = 'd', long, global = true, help = "検索の最大深度")]
     pub max_depth: Option<usize>,
+
+    /// プロジェクト種別のアイコンを表示しない
+    #[arg(long, global = true, help = "プロジェクト種別のアイコンを表示しない")]
+    pub no_icons: bool,
+
+    /// 一覧表示で最終更新からの経過時間を表示する
+    #[arg(
+        long,
+        global = true,
+        help = "一覧表示で最終更新からの経過時間（例: 3d ago）を表示する"
+    )]
+    pub show_age: bool,
+
+    /// 選択UIでREADMEのプレビューを表示しない
+    #[arg(long, global = true, help = "選択UIでREADMEのプレビューを表示しない")]
+    pub no_preview: bool,
+
+    /// 詳細を表示する（スキャン中に読み込めなかったパスなど）
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "詳細を表示する（スキャン中に読み込めなかったパスなど）"
+    )]
+    pub verbose: bool,
+
+    /// 選択UIを完全一致（部分文字列）検索で開始する
+    #[arg(
+        long,
+        global = true,
+        help = "選択UIを完全一致（部分文字列）検索で開始する（Ctrl+Eで切り替え）"
+    )]
+    pub exact: bool,
+
+    /// 選択UIの検索で大文字と小文字を区別する
+    #[arg(
+        long,
+        global = true,
+        help = "選択UIの検索で大文字と小文字を区別する（Ctrl+Sで切り替え）"
+    )]
+    pub case_sensitive: bool,
+
+    /// .gitignore を無視してスキャンする（この実行のみ）
+    #[arg(
+        long,
+        global = true,
+        help = ".gitignore で除外されたディレクトリもスキャンする"
+    )]
+    pub no_gitignore: bool,
+
+    /// Cargo / npm / pnpm / Yarn ワークスペースのメンバーも個別に表示する（この実行のみ）
+    #[arg(
+        long,
+        global = true,
+        help = "Cargo / npm / pnpm / Yarn ワークスペースのメンバーも個別に表示する"
+    )]
+    pub monorepo: bool,
+
+    /// エディタを起動せず、実行するコマンドを表示する
+    #[arg(
+        long,
+        global = true,
+        help = "エディタを起動せず、実行するコマンドを表示する"
+    )]
+    pub dry_run: bool,
+
+    /// エディタを開かず、選択したプロジェクトへの cd コマンドを出力する
+    #[arg(
+        long,
+        global = true,
+        help = "エディタを開かず cd コマンドを出力する（eval \"$(quick-proj --cd)\" で使用）"
+    )]
+    pub cd: bool,
+
+    /// エディタを開かず、選択したプロジェクトのパスをクリップボードにコピーして出力する
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "cd",
+        help = "エディタを開かず、選択したプロジェクトのパスをクリップボードにコピーして出力する"
+    )]
+    pub copy: bool,
+
+    /// プロジェクトごとのtmuxセッションを作成・切り替える
+    #[arg(
+        long,
+        global = true,
+        help = "プロジェクトごとのtmuxセッションを作成・切り替える"
+    )]
+    pub tmux: bool,
+
+    /// 複数のプロジェクトを選択してまとめて開く
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["cd", "tmux"],
+        help = "複数のプロジェクトを選択してまとめて開く"
+    )]
+    pub multi: bool,
+
+    /// 色付き出力（auto, always, never）。auto は NO_COLOR と端末かどうかに従う
+    #[arg(
+        long,
+        global = true,
+        value_name = "WHEN",
+        default_value = "auto",
+        help = "色付き出力（auto, always, never）"
+    )]
+    pub color: ColorMode,
+
+    /// 使用する設定プロファイル（指定は次回以降も引き継がれる。環境変数 QUICK_PROJ_PROFILE でも指定可）
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "使用する設定プロファイル"
+    )]
+    pub profile: Option<String>,
+
+    /// 使用する設定ファイルのパス（環境変数 QUICK_PROJ_CONFIG より優先）
+    #[arg(
+        long = "config",
+        id = "config_path",
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "profile",
+        help = "使用する設定ファイルのパス（プロファイルと QUICK_PROJ_CONFIG より優先）"
+    )]
+    pub config: Option<PathBuf>,
+
+    /// JSON形式のログを追記するファイル（設定の log_file より優先）
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "JSON形式のログを追記するファイル（レベルは設定の log_level）"
+    )]
+    pub log_file: Option<PathBuf>,
+
+    /// プロジェクト一覧の並び順
+    #[arg(
+        long,
+        global = true,
+        value_name = "CRITERION",
+        help = "並び順（name, path, type, frecency, modified, age, smart）"
+    )]
+    pub sort: Option<SortCriterion>,
+
+    /// 選択UIと一覧表示でプロジェクトをグループ化
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        help = "選択UIと一覧表示でプロジェクトをグループ化（root, type, marker, none）"
+    )]
+    pub group_by: Option<GroupMode>,
+
+    /// 選択UIのバックエンド（この実行のみ設定ファイルの selector を上書き）
+    #[arg(
+        long,
+        global = true,
+        value_name = "BACKEND",
+        help = "選択UIのバックエンド（internal, skim, fzf, numbered）"
+    )]
+    pub backend: Option<Selector>,
+
+    /// TUIを使わず、番号付きの一覧から番号またはプロジェクト名を入力して選ぶ（`--backend numbered` と同じ）
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["backend", "multi"],
+        help = "TUIを使わず、番号付きの一覧から番号またはプロジェクト名を入力して選ぶ"
+    )]
+    pub no_tui: bool,
+
+    /// 選択UIを表示せず、絞り込み・並び替え後の先頭のプロジェクトを選ぶ
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "multi",
+        help = "選択UIを表示せず、絞り込み・並び替え後の先頭のプロジェクトを選ぶ（--query と併用）"
+    )]
+    pub first: bool,
+
+    /// エディタと一緒にプロジェクトのディレクトリでターミナルを開く
+    #[arg(long, global = true, help = "エディタと一緒にターミナルを開く")]
+    pub terminal: bool,
+
+    /// エディタを開かず、プロジェクトのディレクトリでシェルを開く
+    #[arg(
+        long,
+        global = true,
+        help = "エディタを開かず、プロジェクトのディレクトリでシェル（または設定したターミナル）を開く"
+    )]
+    pub shell: bool,
+
+    /// エディタを開かず、プロジェクトのリモート（origin）をブラウザで開く
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["cd", "copy", "shell"],
+        help = "エディタを開かず、リモート（origin）のWebページをブラウザで開く"
+    )]
+    pub open_remote: bool,
+
+    /// スキャンに使用するスレッド数
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "スキャンに使用するスレッド数"
+    )]
+    pub threads: Option<usize>,
+
+    /// 選択UIに表示するプロジェクト数の上限（並び替え後の先頭から）
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "選択UIに表示するプロジェクト数の上限"
+    )]
+    pub limit: Option<usize>,
+
+    /// 指定したマーカーで検出したプロジェクトだけを対象にする
+    #[arg(
+        long,
+        global = true,
+        value_name = "MARKER",
+        help = "指定したマーカーで検出したプロジェクトだけを対象にする（例: Cargo.toml）"
+    )]
+    pub only: Option<String>,
+
+    /// 名前またはフルパスが正規表現に一致するプロジェクトだけを対象にする
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATTERN",
+        help = "名前またはフルパスが正規表現に一致するプロジェクトだけを対象にする"
+    )]
+    pub regex: Option<String>,
+
+    /// 指定したタグをすべて持つプロジェクトだけを対象にする
+    #[arg(
+        long,
+        global = true,
+        value_name = "TAG",
+        help = "指定したタグをすべて持つプロジェクトだけを対象にする（複数指定可）"
+    )]
+    pub tag: Vec<String>,
+
+    /// 名前またはパスに検索語（空白区切りですべて）を含むプロジェクトだけを対象にする
+    #[arg(
+        long,
+        global = true,
+        value_name = "QUERY",
+        help = "名前またはパスに検索語（空白区切りですべて）を含むプロジェクトだけを対象にする"
+    )]
+    pub query: Option<String>,
+
+    /// 設定ファイルの代わりに標準入力からルートパスを読み込む（1行に1パス）
+    #[arg(
+        long,
+        conflicts_with = "here",
+        help = "設定ファイルの代わりに標準入力からルートパスを読み込む（1行に1パス）"
+    )]
+    pub stdin_roots: bool,
+
+    /// 登録済みのルートパスの代わりにカレントディレクトリをスキャンする
+    #[arg(
+        long,
+        global = true,
+        help = "登録済みのルートパスの代わりにカレントディレクトリをスキャンする"
+    )]
+    pub here: bool,
 }
 
 /// サブコマンドの定義
-#[derive(Subcommand, Debug)]
+#[derive(Subcommand, Debug, Clone)]
 pub enum Command {
     /// 検索対象のパスを追加
-    #[command(about = "検索対象のパスを追加")]
+    #[command(about = "検索対象のパスを追加（複数指定可）")]
     Add {
         /// 追加するパス
-        #[arg(help = "追加するディレクトリパス")]
-        path: PathBuf,
+        #[arg(required = true, help = "追加するディレクトリパス（複数指定可）")]
+        paths: Vec<PathBuf>,
     },
 
     /// 検索対象のパスを削除
     #[command(about = "検索対象のパスを削除")]
+    #[command(group(
+        clap::ArgGroup::new("target").required(true).args(["paths", "index", "all"])
+    ))]
     Remove {
         /// 削除するパス
-        #[arg(help = "削除するディレクトリパス")]
-        path: PathBuf,
+        #[arg(help = "削除するディレクトリパス（複数指定可）")]
+        paths: Vec<PathBuf>,
+
+        /// `quick-proj list` に表示される番号（1始まり）で指定
+        #[arg(
+            long,
+            short = 'n',
+            value_name = "N",
+            help = "list に表示される番号（1始まり）で削除"
+        )]
+        index: Option<usize>,
+
+        /// すべてのパスを削除（確認あり）
+        #[arg(long, help = "すべてのパスを削除（確認あり）")]
+        all: bool,
     },
 
     /// 登録済みのパスを一覧表示
     #[command(about = "登録済みのパスを一覧表示")]
     List,
 
-    /// 設定ファイルのパスを表示
-    #[command(about = "設定ファイルのパスを表示")]
-    Config,
+    /// 存在しないルートパスを削除（--dry-run で確認のみ）
+    #[command(about = "存在しないルートパスを削除（--dry-run で確認のみ）")]
+    Prune,
+
+    /// ルートパスと、別名・タグを設定したプロジェクトの存在を確認
+    #[command(
+        about = "存在しないルートパスと、別名・タグを設定したプロジェクトを確認（--apply で削除）"
+    )]
+    Cleanup {
+        /// 存在しないパスを設定から削除する
+        #[arg(
+            long,
+            conflicts_with = "dry_run",
+            help = "存在しないパスを設定から削除する"
+        )]
+        apply: bool,
+    },
+
+    /// 設定ファイルのパスを表示、またはエクスポート・インポート
+    #[command(about = "設定ファイルのパスを表示、またはエクスポート・インポート")]
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+
+    /// 設定を書き出す（`config export` と同じ）
+    #[command(about = "設定を標準出力またはファイルに書き出す（config export と同じ）")]
+    Export {
+        /// 出力形式（toml, json）
+        #[arg(long, default_value = "toml", help = "出力形式（toml, json）")]
+        format: ConfigFormat,
+
+        /// 出力先ファイル（省略時は標準出力）
+        #[arg(
+            long,
+            short = 'o',
+            visible_alias = "out",
+            value_name = "FILE",
+            help = "出力先ファイル"
+        )]
+        output: Option<PathBuf>,
+    },
+
+    /// 設定を読み込む（`config import` と同じ）
+    #[command(about = "設定ファイルを読み込んで現在の設定にマージ（config import と同じ）")]
+    Import {
+        /// 読み込むファイル（.json 以外はTOMLとして扱う）
+        #[arg(help = "読み込むファイル")]
+        file: PathBuf,
+
+        /// ルートパスとマーカー以外の設定もインポートした値で上書きする
+        #[arg(long, help = "ルートパスとマーカー以外の設定も上書きする")]
+        overwrite: bool,
+
+        /// 現在の設定をインポートした設定で置き換える
+        #[arg(
+            long,
+            conflicts_with = "overwrite",
+            help = "現在の設定をインポートした設定で置き換える"
+        )]
+        replace: bool,
+    },
+
+    /// 設定ファイルをエディタで開く
+    #[command(about = "設定ファイルをエディタで開く（存在しない場合はデフォルト設定で作成）")]
+    EditConfig,
 
     /// プロジェクト一覧をスキャンして表示
     #[command(about = "プロジェクト一覧をスキャンして表示")]
-    Scan,
+    Scan {
+        /// 出力形式（list, table, csv）
+        #[arg(
+            long,
+            default_value = "list",
+            value_name = "FORMAT",
+            help = "出力形式（list, table, csv）。table は列をそろえたパイプ区切りの表"
+        )]
+        format: ListFormat,
+    },
+
+    /// プロジェクトの統計を表示
+    #[command(about = "プロジェクトの統計（種別・ルートパスごとの内訳）を表示")]
+    Stats,
+
+    /// 最後に開いたプロジェクトをスキャンせずに開く
+    #[command(about = "最後に開いたプロジェクトをスキャンせずに開く")]
+    Last,
+
+    /// 検索語に一致するプロジェクトをすべて開く
+    #[command(about = "検索語（空白区切りですべて）に一致するプロジェクトをすべて開く")]
+    OpenAll {
+        /// 検索語（名前またはパスに含まれる語）
+        #[arg(
+            id = "open_query",
+            value_name = "QUERY",
+            help = "検索語（名前またはパスに含まれる語。空白区切りですべて）"
+        )]
+        query: String,
+    },
+
+    /// プロジェクトをピン留めする
+    #[command(
+        about = "検索語に一致するプロジェクトをピン留めし、選択UIで常に先頭に表示（省略時は一覧表示）"
+    )]
+    Pin {
+        /// 検索語（名前またはパスに含まれる語）
+        #[arg(
+            id = "pin_query",
+            value_name = "QUERY",
+            help = "検索語（名前またはパスに含まれる語。複数一致した場合は選択UIで選ぶ）"
+        )]
+        query: Option<String>,
+    },
+
+    /// プロジェクトのピン留めを解除する
+    #[command(about = "検索語に一致するプロジェクトのピン留めを解除")]
+    Unpin {
+        /// 検索語（名前またはパスに含まれる語）
+        #[arg(
+            id = "pin_query",
+            value_name = "QUERY",
+            help = "検索語（名前またはパスに含まれる語。複数一致した場合は選択UIで選ぶ）"
+        )]
+        query: String,
+    },
+
+    /// 起動履歴を管理
+    #[command(about = "起動履歴（開いた回数と最後に開いた日時）を管理")]
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+
+    /// 設定プロファイルを管理
+    #[command(about = "設定プロファイルを管理")]
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+
+    /// 除外パターンを管理
+    #[command(about = "除外パターン（glob）を管理")]
+    Exclude {
+        #[command(subcommand)]
+        action: ExcludeAction,
+    },
+
+    /// プロジェクトの表示名の別名を管理
+    #[command(
+        about = "プロジェクトの表示名の別名を管理",
+        args_conflicts_with_subcommands = true
+    )]
+    Alias {
+        #[command(subcommand)]
+        action: Option<AliasAction>,
+
+        /// プロジェクトのディレクトリ
+        #[arg(help = "プロジェクトのディレクトリ", requires = "alias")]
+        path: Option<PathBuf>,
+
+        /// 表示名
+        #[arg(help = "表示名")]
+        alias: Option<String>,
+    },
+
+    /// プロジェクトのタグを管理
+    #[command(about = "プロジェクトのタグを管理（--tag で絞り込みに使用）")]
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// ターミナル起動コマンドを設定
+    #[command(about = "ターミナル起動コマンドを設定")]
+    SetTerminal {
+        /// ターミナルコマンド（例: alacritty, kitty, wezterm start）
+        #[arg(help = "ターミナルコマンド")]
+        command: String,
+    },
+
+    /// スキャンに使用するスレッド数を設定
+    #[command(about = "スキャンに使用するスレッド数を設定（0 で全コアを使用）")]
+    SetThreads {
+        /// スレッド数（0 でデフォルトに戻す）
+        #[arg(help = "スレッド数（0 でデフォルトに戻す）")]
+        threads: usize,
+    },
+
+    /// 設定と実行環境の問題を診断
+    #[command(about = "設定と実行環境の問題を診断")]
+    Doctor,
+
+    /// 対話形式でルートパス・エディタ・最大深度を設定
+    #[command(about = "対話形式でルートパス・エディタ・最大深度を設定")]
+    Setup,
+
+    /// ルートパスを監視してプロジェクトの追加・削除を表示
+    #[command(about = "ルートパスを監視してプロジェクトの追加・削除を表示")]
+    Watch {
+        /// 変更時に実行するコマンド（QUICK_PROJ_ADDED / QUICK_PROJ_REMOVED が設定される）
+        #[arg(long, value_name = "COMMAND", help = "変更時に実行するコマンド")]
+        exec: Option<String>,
+    },
+
+    /// シェル連携用の関数を出力
+    #[command(
+        about = "シェル連携用の関数とキーバインドを出力（例: eval \"$(quick-proj init bash)\"）"
+    )]
+    Init {
+        /// 対象のシェル（bash, zsh, fish, nushell）
+        #[arg(
+            id = "init_shell",
+            value_name = "SHELL",
+            help = "対象のシェル（bash, zsh, fish, nushell）"
+        )]
+        shell: Shell,
+    },
 
     /// デフォルトのエディタを設定
     #[command(about = "デフォルトのエディタを設定")]
@@ -72,6 +576,168 @@ pub enum Command {
         /// エディタコマンド（例: code, vim, nvim, cursor）
         #[arg(help = "エディタコマンド")]
         editor: String,
+
+        /// 指定した種別のプロジェクトにのみ適用（例: rust, node）
+        #[arg(
+            long = "type",
+            value_name = "TYPE",
+            help = "指定した種別のプロジェクトにのみ適用"
+        )]
+        project_type: Option<ProjectType>,
+    },
+}
+
+/// 除外パターンの操作
+#[derive(Subcommand, Debug, Clone)]
+pub enum ExcludeAction {
+    /// 除外パターンを追加
+    #[command(about = "除外パターンを追加")]
+    Add {
+        /// globパターン（例: "**/dist/**"）
+        #[arg(help = "globパターン（例: \"**/dist/**\"）")]
+        pattern: String,
+    },
+
+    /// 除外パターンを削除
+    #[command(about = "除外パターンを削除")]
+    Remove {
+        /// 削除するglobパターン
+        #[arg(help = "削除するglobパターン")]
+        pattern: String,
+    },
+}
+
+/// 別名の操作
+#[derive(Subcommand, Debug, Clone)]
+pub enum AliasAction {
+    /// 別名を削除
+    #[command(about = "別名を削除")]
+    Remove {
+        /// プロジェクトのディレクトリ
+        #[arg(help = "プロジェクトのディレクトリ")]
+        path: PathBuf,
+    },
+}
+
+/// タグの操作
+#[derive(Subcommand, Debug, Clone)]
+pub enum TagAction {
+    /// タグを追加
+    #[command(about = "プロジェクトにタグを追加")]
+    Add {
+        /// プロジェクトのディレクトリ
+        #[arg(help = "プロジェクトのディレクトリ")]
+        path: PathBuf,
+
+        /// タグ（小文字化し、空白と _ は - に置換）
+        #[arg(id = "tag_name", value_name = "TAG", help = "タグ（例: work）")]
+        tag: String,
+    },
+
+    /// タグを削除
+    #[command(about = "プロジェクトからタグを削除")]
+    Remove {
+        /// プロジェクトのディレクトリ
+        #[arg(help = "プロジェクトのディレクトリ")]
+        path: PathBuf,
+
+        /// 削除するタグ
+        #[arg(id = "tag_name", value_name = "TAG", help = "削除するタグ")]
+        tag: String,
+    },
+
+    /// タグを一覧表示
+    #[command(about = "タグを一覧表示（パス指定時はそのプロジェクトのみ）")]
+    List {
+        /// プロジェクトのディレクトリ
+        #[arg(help = "プロジェクトのディレクトリ")]
+        path: Option<PathBuf>,
+    },
+}
+
+/// 設定ファイルの操作
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigAction {
+    /// 設定を標準出力またはファイルに書き出す
+    #[command(about = "設定を標準出力またはファイルに書き出す")]
+    Export {
+        /// 出力形式（toml, json）
+        #[arg(long, default_value = "toml", help = "出力形式（toml, json）")]
+        format: ConfigFormat,
+
+        /// 出力先ファイル（省略時は標準出力）
+        #[arg(
+            long,
+            short = 'o',
+            visible_alias = "out",
+            value_name = "FILE",
+            help = "出力先ファイル"
+        )]
+        output: Option<PathBuf>,
+    },
+
+    /// 設定ファイルを読み込んで現在の設定にマージ
+    #[command(about = "設定ファイルを読み込んで現在の設定にマージ")]
+    Import {
+        /// 読み込むファイル（.json 以外はTOMLとして扱う）
+        #[arg(help = "読み込むファイル")]
+        file: PathBuf,
+
+        /// ルートパスとマーカー以外の設定もインポートした値で上書きする
+        #[arg(long, help = "ルートパスとマーカー以外の設定も上書きする")]
+        overwrite: bool,
+
+        /// 現在の設定をインポートした設定で置き換える
+        #[arg(
+            long,
+            conflicts_with = "overwrite",
+            help = "現在の設定をインポートした設定で置き換える"
+        )]
+        replace: bool,
+    },
+
+    /// 設定を検証（問題があれば終了コード1）
+    #[command(about = "設定を検証（問題があれば終了コード1）")]
+    Validate,
+
+    /// 設定ファイルを現在の形式に書き直す（コメントは保持し、元のファイルは .bak に保存）
+    #[command(about = "設定ファイルを現在の形式に書き直す（元のファイルは .bak に保存）")]
+    Migrate,
+}
+
+/// 起動履歴の操作
+#[derive(Subcommand, Debug, Clone)]
+pub enum HistoryAction {
+    /// よく開くプロジェクトを一覧表示
+    #[command(about = "よく開くプロジェクトを一覧表示（上位20件。--limit で変更）")]
+    Show,
+
+    /// 起動履歴を消去
+    #[command(about = "起動履歴を消去（開いた回数をリセット）")]
+    Clear,
+}
+
+/// 設定プロファイルの操作
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProfileAction {
+    /// プロファイルを一覧表示
+    #[command(about = "プロファイルを一覧表示")]
+    List,
+
+    /// プロファイルを作成
+    #[command(about = "プロファイルを作成")]
+    Create {
+        /// プロファイル名
+        #[arg(help = "プロファイル名")]
+        name: String,
+    },
+
+    /// プロファイルを削除
+    #[command(about = "プロファイルを削除")]
+    Delete {
+        /// プロファイル名
+        #[arg(help = "プロファイル名")]
+        name: String,
     },
 }
 
@@ -80,6 +746,27 @@ impl Args {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+
+    /// 標準出力に選択結果だけを出力するモード（`--cd` / `--copy`）か
+    pub fn prints_path(&self) -> bool {
+        self.cd || self.copy
+    }
+
+    /// 標準出力に機械向けの出力（パス、シェルスクリプト、設定の書き出し、表やCSV）を行うか
+    ///
+    /// この場合は設定の警告などの付随するメッセージを表示しません。
+    pub fn writes_machine_output(&self) -> bool {
+        self.prints_path()
+            || match &self.command {
+                Some(Command::Init { .. }) => true,
+                Some(Command::Export { output, .. })
+                | Some(Command::Config {
+                    action: Some(ConfigAction::Export { output, .. }),
+                }) => output.is_none(),
+                Some(Command::Scan { format }) => *format != ListFormat::List,
+                _ => false,
+            }
+    }
 }
 
 #[cfg(test)]
@@ -91,17 +778,138 @@ mod tests {
         let args = Args::try_parse_from(["quick-proj"]).unwrap();
         assert!(args.command.is_none());
         assert!(args.editor.is_none());
+        assert!(!args.no_icons);
+    }
+
+    #[test]
+    fn test_args_no_icons() {
+        let args = Args::try_parse_from(["quick-proj", "--no-icons"]).unwrap();
+        assert!(args.no_icons);
+    }
+
+    #[test]
+    fn test_args_exact() {
+        let args = Args::try_parse_from(["quick-proj", "--exact"]).unwrap();
+        assert!(args.exact);
+    }
+
+    #[test]
+    fn test_args_case_sensitive() {
+        let args = Args::try_parse_from(["quick-proj", "--case-sensitive", "--exact"]).unwrap();
+        assert!(args.case_sensitive);
+        assert!(args.exact);
+    }
+
+    #[test]
+    fn test_args_scan_format() {
+        let args = Args::try_parse_from(["quick-proj", "scan"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Scan {
+                format: ListFormat::List
+            })
+        ));
+
+        let args = Args::try_parse_from(["quick-proj", "scan", "--format", "table"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Scan {
+                format: ListFormat::Table
+            })
+        ));
+
+        let args = Args::try_parse_from(["quick-proj", "scan", "--format", "CSV"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Scan {
+                format: ListFormat::Csv
+            })
+        ));
+
+        assert!(Args::try_parse_from(["quick-proj", "scan", "--format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_args_writes_machine_output() {
+        let machine = |args: &[&str]| {
+            Args::try_parse_from(std::iter::once("quick-proj").chain(args.iter().copied()))
+                .unwrap()
+                .writes_machine_output()
+        };
+
+        assert!(machine(&["--cd"]));
+        assert!(machine(&["init", "zsh"]));
+        assert!(machine(&["export"]));
+        assert!(machine(&["config", "export", "--format", "json"]));
+        assert!(machine(&["scan", "--format", "csv"]));
+        assert!(!machine(&["export", "-o", "backup.toml"]));
+        assert!(!machine(&["scan"]));
+        assert!(!machine(&["list"]));
+    }
+
+    #[test]
+    fn test_args_verbose() {
+        let args = Args::try_parse_from(["quick-proj", "scan", "-v"]).unwrap();
+        assert!(args.verbose);
+    }
+
+    #[test]
+    fn test_args_no_preview() {
+        let args = Args::try_parse_from(["quick-proj", "--no-preview"]).unwrap();
+        assert!(args.no_preview);
     }
 
     #[test]
     fn test_args_add_command() {
         let args = Args::try_parse_from(["quick-proj", "add", "/tmp/test"]).unwrap();
         match args.command {
-            Some(Command::Add { path }) => {
-                assert_eq!(path, PathBuf::from("/tmp/test"));
+            Some(Command::Add { paths }) => {
+                assert_eq!(paths, vec![PathBuf::from("/tmp/test")]);
             }
             _ => panic!("Expected Add command"),
         }
+
+        let args = Args::try_parse_from(["quick-proj", "add", "/tmp/a", "/tmp/b"]).unwrap();
+        match args.command {
+            Some(Command::Add { paths }) => assert_eq!(paths.len(), 2),
+            _ => panic!("Expected Add command"),
+        }
+
+        assert!(Args::try_parse_from(["quick-proj", "add"]).is_err());
+    }
+
+    #[test]
+    fn test_args_remove_command() {
+        let args = Args::try_parse_from(["quick-proj", "remove", "-n", "2"]).unwrap();
+        match args.command {
+            Some(Command::Remove { paths, index, all }) => {
+                assert!(paths.is_empty());
+                assert_eq!(index, Some(2));
+                assert!(!all);
+            }
+            _ => panic!("Expected Remove command"),
+        }
+
+        let args = Args::try_parse_from(["quick-proj", "remove", "--all"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Remove { all: true, .. })
+        ));
+
+        let args = Args::try_parse_from(["quick-proj", "remove", "/tmp/a", "/tmp/b"]).unwrap();
+        match args.command {
+            Some(Command::Remove { paths, .. }) => {
+                assert_eq!(
+                    paths,
+                    vec![PathBuf::from("/tmp/a"), PathBuf::from("/tmp/b")]
+                );
+            }
+            _ => panic!("Expected Remove command"),
+        }
+
+        // パス・番号・--all のいずれか1つが必要
+        assert!(Args::try_parse_from(["quick-proj", "remove"]).is_err());
+        assert!(Args::try_parse_from(["quick-proj", "remove", "/tmp/a", "--index", "1"]).is_err());
     }
 
     #[test]
@@ -110,6 +918,494 @@ mod tests {
         assert_eq!(args.editor, Some("vim".to_string()));
     }
 
+    #[test]
+    fn test_args_dry_run() {
+        let args = Args::try_parse_from(["quick-proj", "--dry-run"]).unwrap();
+        assert!(args.dry_run);
+    }
+
+    #[test]
+    fn test_args_cd() {
+        let args = Args::try_parse_from(["quick-proj", "--cd"]).unwrap();
+        assert!(args.cd);
+    }
+
+    #[test]
+    fn test_args_copy() {
+        let args = Args::try_parse_from(["quick-proj", "--copy"]).unwrap();
+        assert!(args.copy);
+        assert!(args.prints_path());
+
+        assert!(Args::try_parse_from(["quick-proj", "--copy", "--cd"]).is_err());
+    }
+
+    #[test]
+    fn test_args_open_remote() {
+        let args = Args::try_parse_from(["quick-proj", "--open-remote"]).unwrap();
+        assert!(args.open_remote);
+
+        assert!(Args::try_parse_from(["quick-proj", "--open-remote", "--cd"]).is_err());
+    }
+
+    #[test]
+    fn test_args_shell() {
+        let args = Args::try_parse_from(["quick-proj", "--shell"]).unwrap();
+        assert!(args.shell);
+
+        let args = Args::try_parse_from(["quick-proj", "last", "--shell"]).unwrap();
+        assert!(args.shell);
+    }
+
+    #[test]
+    fn test_args_init() {
+        let args = Args::try_parse_from(["quick-proj", "init", "zsh"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Init {
+                shell: crate::shell_init::Shell::Zsh
+            })
+        ));
+        assert!(!args.shell);
+    }
+
+    #[test]
+    fn test_args_setup() {
+        let args = Args::try_parse_from(["quick-proj", "setup"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Setup)));
+    }
+
+    #[test]
+    fn test_args_open_all() {
+        let args =
+            Args::try_parse_from(["quick-proj", "open-all", "service", "--dry-run"]).unwrap();
+        match args.command {
+            Some(Command::OpenAll { query }) => assert_eq!(query, "service"),
+            _ => panic!("Expected OpenAll command"),
+        }
+        assert!(args.dry_run);
+        assert_eq!(args.query, None);
+    }
+
+    #[test]
+    fn test_args_pin() {
+        let args = Args::try_parse_from(["quick-proj", "pin", "api"]).unwrap();
+        match args.command {
+            Some(Command::Pin { query }) => assert_eq!(query.as_deref(), Some("api")),
+            _ => panic!("Expected Pin command"),
+        }
+
+        let args = Args::try_parse_from(["quick-proj", "pin"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Pin { query: None })));
+
+        let args = Args::try_parse_from(["quick-proj", "unpin", "api"]).unwrap();
+        match args.command {
+            Some(Command::Unpin { query }) => assert_eq!(query, "api"),
+            _ => panic!("Expected Unpin command"),
+        }
+        assert!(Args::try_parse_from(["quick-proj", "unpin"]).is_err());
+    }
+
+    #[test]
+    fn test_args_history() {
+        let args = Args::try_parse_from(["quick-proj", "history", "show", "--limit", "5"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::History {
+                action: HistoryAction::Show
+            })
+        ));
+        assert_eq!(args.limit, Some(5));
+
+        let args = Args::try_parse_from(["quick-proj", "history", "clear"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::History {
+                action: HistoryAction::Clear
+            })
+        ));
+    }
+
+    #[test]
+    fn test_args_tag() {
+        let args = Args::try_parse_from(["quick-proj", "tag", "add", "~/src/api", "work"]).unwrap();
+        match args.command {
+            Some(Command::Tag {
+                action: TagAction::Add { path, tag },
+            }) => {
+                assert_eq!(path, PathBuf::from("~/src/api"));
+                assert_eq!(tag, "work");
+            }
+            _ => panic!("Expected Tag Add command"),
+        }
+        assert!(args.tag.is_empty());
+
+        let args =
+            Args::try_parse_from(["quick-proj", "--tag", "work", "--tag", "active"]).unwrap();
+        assert_eq!(args.tag, vec!["work", "active"]);
+    }
+
+    #[test]
+    fn test_args_tmux() {
+        let args = Args::try_parse_from(["quick-proj", "--tmux"]).unwrap();
+        assert!(args.tmux);
+    }
+
+    #[test]
+    fn test_args_multi() {
+        let args = Args::try_parse_from(["quick-proj", "--multi"]).unwrap();
+        assert!(args.multi);
+
+        assert!(Args::try_parse_from(["quick-proj", "--multi", "--cd"]).is_err());
+    }
+
+    #[test]
+    fn test_args_sort() {
+        let args = Args::try_parse_from(["quick-proj", "--sort", "modified"]).unwrap();
+        assert_eq!(args.sort, Some(SortCriterion::Modified));
+
+        assert!(Args::try_parse_from(["quick-proj", "--sort", "size"]).is_err());
+    }
+
+    #[test]
+    fn test_args_limit() {
+        let args = Args::try_parse_from(["quick-proj", "--limit", "200"]).unwrap();
+        assert_eq!(args.limit, Some(200));
+    }
+
+    #[test]
+    fn test_args_last() {
+        let args = Args::try_parse_from(["quick-proj", "last", "--editor", "vim"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Last)));
+        assert_eq!(args.editor.as_deref(), Some("vim"));
+    }
+
+    #[test]
+    fn test_args_only() {
+        let args = Args::try_parse_from(["quick-proj", "scan", "--only", "Cargo.toml"]).unwrap();
+        assert_eq!(args.only.as_deref(), Some("Cargo.toml"));
+    }
+
+    #[test]
+    fn test_args_regex() {
+        let args = Args::try_parse_from(["quick-proj", "--regex", "^api-"]).unwrap();
+        assert_eq!(args.regex.as_deref(), Some("^api-"));
+
+        let args = Args::try_parse_from(["quick-proj", "stats", "--regex", "/work/"]).unwrap();
+        assert_eq!(args.regex.as_deref(), Some("/work/"));
+    }
+
+    #[test]
+    fn test_args_query() {
+        let args = Args::try_parse_from(["quick-proj", "--query", "rust api"]).unwrap();
+        assert_eq!(args.query.as_deref(), Some("rust api"));
+    }
+
+    #[test]
+    fn test_args_stdin_roots() {
+        let args = Args::try_parse_from(["quick-proj", "--stdin-roots", "--cd"]).unwrap();
+        assert!(args.stdin_roots);
+        assert!(args.cd);
+    }
+
+    #[test]
+    fn test_args_here() {
+        let args = Args::try_parse_from(["quick-proj", "--here"]).unwrap();
+        assert!(args.here);
+
+        let args = Args::try_parse_from(["quick-proj", "scan", "--here"]).unwrap();
+        assert!(args.here);
+
+        assert!(Args::try_parse_from(["quick-proj", "--here", "--stdin-roots"]).is_err());
+    }
+
+    #[test]
+    fn test_args_group_by() {
+        let args = Args::try_parse_from(["quick-proj", "--group-by", "root"]).unwrap();
+        assert_eq!(args.group_by, Some(GroupMode::Root));
+    }
+
+    #[test]
+    fn test_args_monorepo() {
+        let args = Args::try_parse_from(["quick-proj", "scan", "--monorepo"]).unwrap();
+        assert!(args.monorepo);
+        assert!(!Args::try_parse_from(["quick-proj"]).unwrap().monorepo);
+    }
+
+    #[test]
+    fn test_args_backend() {
+        let args = Args::try_parse_from(["quick-proj", "--backend", "fzf"]).unwrap();
+        assert_eq!(args.backend, Some(Selector::Fzf));
+
+        let args = Args::try_parse_from(["quick-proj", "--backend", "internal"]).unwrap();
+        assert_eq!(args.backend, Some(Selector::Internal));
+
+        assert!(Args::try_parse_from(["quick-proj", "--backend", "peco"]).is_err());
+
+        let args = Args::try_parse_from(["quick-proj", "--no-tui"]).unwrap();
+        assert!(args.no_tui);
+        assert!(Args::try_parse_from(["quick-proj", "--no-tui", "--backend", "fzf"]).is_err());
+        assert!(Args::try_parse_from(["quick-proj", "--no-tui", "--multi"]).is_err());
+
+        let args = Args::try_parse_from(["quick-proj", "--first", "--query", "api"]).unwrap();
+        assert!(args.first);
+        assert!(Args::try_parse_from(["quick-proj", "--first", "--multi"]).is_err());
+    }
+
+    #[test]
+    fn test_args_set_editor_with_type() {
+        let args =
+            Args::try_parse_from(["quick-proj", "set-editor", "rustrover", "--type", "rust"])
+                .unwrap();
+        match args.command {
+            Some(Command::SetEditor {
+                editor,
+                project_type,
+            }) => {
+                assert_eq!(editor, "rustrover");
+                assert_eq!(project_type, Some(ProjectType::Rust));
+            }
+            _ => panic!("Expected SetEditor command"),
+        }
+
+        assert!(
+            Args::try_parse_from(["quick-proj", "set-editor", "x", "--type", "cobol"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_args_threads() {
+        let args = Args::try_parse_from(["quick-proj", "scan", "--threads", "2"]).unwrap();
+        assert_eq!(args.threads, Some(2));
+
+        let args = Args::try_parse_from(["quick-proj", "set-threads", "4"]).unwrap();
+        match args.command {
+            Some(Command::SetThreads { threads }) => assert_eq!(threads, 4),
+            _ => panic!("Expected SetThreads command"),
+        }
+    }
+
+    #[test]
+    fn test_args_set_terminal() {
+        let args = Args::try_parse_from(["quick-proj", "set-terminal", "kitty"]).unwrap();
+        match args.command {
+            Some(Command::SetTerminal { command }) => assert_eq!(command, "kitty"),
+            _ => panic!("Expected SetTerminal command"),
+        }
+    }
+
+    #[test]
+    fn test_args_exclude_add() {
+        let args = Args::try_parse_from(["quick-proj", "exclude", "add", "**/dist/**"]).unwrap();
+        match args.command {
+            Some(Command::Exclude {
+                action: ExcludeAction::Add { pattern },
+            }) => assert_eq!(pattern, "**/dist/**"),
+            _ => panic!("Expected Exclude Add command"),
+        }
+    }
+
+    #[test]
+    fn test_args_alias() {
+        let args = Args::try_parse_from(["quick-proj", "alias", "~/src/tool", "tool"]).unwrap();
+        match args.command {
+            Some(Command::Alias {
+                action: None,
+                path,
+                alias,
+            }) => {
+                assert_eq!(path, Some(PathBuf::from("~/src/tool")));
+                assert_eq!(alias.as_deref(), Some("tool"));
+            }
+            _ => panic!("Expected Alias command"),
+        }
+
+        let args = Args::try_parse_from(["quick-proj", "alias", "remove", "~/src/tool"]).unwrap();
+        match args.command {
+            Some(Command::Alias {
+                action: Some(AliasAction::Remove { path }),
+                ..
+            }) => assert_eq!(path, PathBuf::from("~/src/tool")),
+            _ => panic!("Expected Alias Remove command"),
+        }
+
+        // 別名を省略した場合はエラー
+        assert!(Args::try_parse_from(["quick-proj", "alias", "~/src/tool"]).is_err());
+    }
+
+    #[test]
+    fn test_args_watch_exec() {
+        let args = Args::try_parse_from(["quick-proj", "watch", "--exec", "echo hi"]).unwrap();
+        match args.command {
+            Some(Command::Watch { exec }) => assert_eq!(exec.as_deref(), Some("echo hi")),
+            _ => panic!("Expected Watch command"),
+        }
+    }
+
+    #[test]
+    fn test_args_cleanup() {
+        let args = Args::try_parse_from(["quick-proj", "cleanup", "--apply"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Cleanup { apply: true })
+        ));
+
+        assert!(Args::try_parse_from(["quick-proj", "cleanup", "--apply", "--dry-run"]).is_err());
+    }
+
+    #[test]
+    fn test_args_prune_dry_run() {
+        let args = Args::try_parse_from(["quick-proj", "prune", "--dry-run"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Prune)));
+        assert!(args.dry_run);
+    }
+
+    #[test]
+    fn test_args_config_export_import() {
+        let args = Args::try_parse_from(["quick-proj", "config"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Config { action: None })
+        ));
+
+        let args =
+            Args::try_parse_from(["quick-proj", "config", "export", "--format", "json"]).unwrap();
+        match args.command {
+            Some(Command::Config {
+                action: Some(ConfigAction::Export { format, output }),
+            }) => {
+                assert_eq!(format, ConfigFormat::Json);
+                assert!(output.is_none());
+            }
+            _ => panic!("Expected Config Export command"),
+        }
+
+        let args =
+            Args::try_parse_from(["quick-proj", "config", "import", "base.toml", "--overwrite"])
+                .unwrap();
+        match args.command {
+            Some(Command::Config {
+                action:
+                    Some(ConfigAction::Import {
+                        file,
+                        overwrite,
+                        replace,
+                    }),
+            }) => {
+                assert_eq!(file, PathBuf::from("base.toml"));
+                assert!(overwrite);
+                assert!(!replace);
+            }
+            _ => panic!("Expected Config Import command"),
+        }
+    }
+
+    #[test]
+    fn test_args_top_level_export_import() {
+        let args = Args::try_parse_from(["quick-proj", "export", "--out", "qp.toml"]).unwrap();
+        match args.command {
+            Some(Command::Export { format, output }) => {
+                assert_eq!(format, ConfigFormat::Toml);
+                assert_eq!(output, Some(PathBuf::from("qp.toml")));
+            }
+            _ => panic!("Expected Export command"),
+        }
+
+        let args = Args::try_parse_from(["quick-proj", "import", "qp.toml", "--replace"]).unwrap();
+        match args.command {
+            Some(Command::Import { replace, .. }) => assert!(replace),
+            _ => panic!("Expected Import command"),
+        }
+
+        assert!(Args::try_parse_from([
+            "quick-proj",
+            "import",
+            "qp.toml",
+            "--replace",
+            "--overwrite"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_args_edit_config() {
+        let args = Args::try_parse_from(["quick-proj", "edit-config", "-e", "vim"]).unwrap();
+        assert!(matches!(args.command, Some(Command::EditConfig)));
+        assert_eq!(args.editor.as_deref(), Some("vim"));
+    }
+
+    #[test]
+    fn test_args_log_file() {
+        let args =
+            Args::try_parse_from(["quick-proj", "scan", "--log-file", "/tmp/qp.log"]).unwrap();
+        assert_eq!(args.log_file, Some(PathBuf::from("/tmp/qp.log")));
+
+        let args = Args::try_parse_from(["quick-proj"]).unwrap();
+        assert_eq!(args.log_file, None);
+    }
+
+    #[test]
+    fn test_args_config_validate() {
+        let args = Args::try_parse_from(["quick-proj", "config", "validate"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Config {
+                action: Some(ConfigAction::Validate)
+            })
+        ));
+
+        let args = Args::try_parse_from(["quick-proj", "config", "migrate"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Config {
+                action: Some(ConfigAction::Migrate)
+            })
+        ));
+    }
+
+    #[test]
+    fn test_args_color() {
+        let args = Args::try_parse_from(["quick-proj"]).unwrap();
+        assert_eq!(args.color, ColorMode::Auto);
+
+        let args = Args::try_parse_from(["quick-proj", "list", "--color", "never"]).unwrap();
+        assert_eq!(args.color, ColorMode::Never);
+
+        assert!(Args::try_parse_from(["quick-proj", "--color", "rainbow"]).is_err());
+    }
+
+    #[test]
+    fn test_args_profile() {
+        let args = Args::try_parse_from(["quick-proj", "--profile", "work"]).unwrap();
+        assert_eq!(args.profile.as_deref(), Some("work"));
+
+        let args = Args::try_parse_from(["quick-proj", "profile", "create", "work"]).unwrap();
+        match args.command {
+            Some(Command::Profile {
+                action: ProfileAction::Create { name },
+            }) => assert_eq!(name, "work"),
+            _ => panic!("Expected Profile Create command"),
+        }
+    }
+
+    #[test]
+    fn test_args_config_path() {
+        let args =
+            Args::try_parse_from(["quick-proj", "scan", "--config", "./quick-proj.toml"]).unwrap();
+        assert_eq!(args.config, Some(PathBuf::from("./quick-proj.toml")));
+
+        // `config` サブコマンドとも併用できる
+        let args = Args::try_parse_from(["quick-proj", "--config", "a.toml", "config"]).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Config { action: None })
+        ));
+
+        assert!(
+            Args::try_parse_from(["quick-proj", "--config", "a.toml", "--profile", "work"])
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_args_list_command() {
         let args = Args::try_parse_from(["quick-proj", "list"]).unwrap();
@@ -0,0 +1,87 @@
+//! クリップボード連携モジュール
+//!
+//! OS標準のクリップボードコマンド（pbcopy, wl-copy, xclip など）を使って
+//! テキストをコピーします。
+
+use anyhow::{bail, Context, Result};
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+
+/// クリップボードにコピーするコマンドの候補（優先順）
+///
+/// Linuxなどでは、Waylandのセッションであれば `wl-copy` を優先します。
+fn clipboard_commands(wayland: bool) -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        return vec![("pbcopy", &[])];
+    }
+    if cfg!(windows) {
+        return vec![("clip", &[])];
+    }
+
+    let mut commands: Vec<(&'static str, &'static [&'static str])> = vec![
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ];
+    if wayland {
+        commands.insert(0, ("wl-copy", &[]));
+    }
+    commands
+}
+
+/// テキストをクリップボードにコピー
+///
+/// 見つかった最初のクリップボードコマンドに標準入力で渡します。
+pub fn copy(text: &str) -> Result<()> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+
+    for (program, args) in clipboard_commands(wayland) {
+        let mut child = match Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to run {}", program)),
+        };
+
+        child
+            .stdin
+            .take()
+            .context("Failed to open clipboard command stdin")?
+            .write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write to {}", program))?;
+
+        let status = child
+            .wait()
+            .with_context(|| format!("Failed to wait for {}", program))?;
+        if !status.success() {
+            bail!("{} exited with {}", program, status);
+        }
+
+        return Ok(());
+    }
+
+    bail!("No clipboard command found. Install wl-copy, xclip or xsel.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_clipboard_commands_prefers_wl_copy_on_wayland() {
+        let names = |wayland| -> Vec<&str> {
+            clipboard_commands(wayland)
+                .into_iter()
+                .map(|(program, _)| program)
+                .collect()
+        };
+
+        assert_eq!(names(false), vec!["xclip", "xsel"]);
+        assert_eq!(names(true), vec!["wl-copy", "xclip", "xsel"]);
+    }
+}
@@ -3,48 +3,644 @@
 //! アプリケーションの設定をTOMLファイルで永続化します。
 //! OS標準の設定ディレクトリに保存されます。
 
-use anyhow::{Context, Result};
+use crate::scanner::ProjectType;
+use crate::state::State;
+use anyhow::{anyhow, bail, Context, Result};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 /// アプリケーション名
 const APP_NAME: &str = "quick-proj";
 /// 設定ファイル名
 const CONFIG_FILE_NAME: &str = "config.toml";
+/// デフォルトのプロファイル名（`config.toml` に対応）
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// プロファイルを指定する環境変数
+const PROFILE_ENV: &str = "QUICK_PROJ_PROFILE";
+/// 設定ファイルのパスを直接指定する環境変数（プロファイルより優先）
+pub const CONFIG_ENV: &str = "QUICK_PROJ_CONFIG";
+/// 状態・キャッシュ・履歴の保存先ディレクトリを指定する環境変数（`data_dir` より優先）
+pub const DATA_DIR_ENV: &str = "QUICK_PROJ_DATA_DIR";
+
+/// 現在の設定ファイルのスキーマバージョン
+///
+/// `version` を持たない設定ファイルはバージョン0として扱います。
+pub const CONFIG_VERSION: u32 = 1;
+
+/// `max_depth` として妥当な範囲（読み込み時にこの範囲に収める）
+pub const MAX_DEPTH_RANGE: std::ops::RangeInclusive<usize> = 1..=20;
+
+/// `nix_markers` が有効な場合に追加するNixのマーカー（優先度は最も低い）
+const NIX_EXTRA_MARKERS: &[&str] = &["flake.lock", "default.nix", "shell.nix"];
+
+/// 現在の実行で使用するプロファイル
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// `--config` で指定された設定ファイルのパス
+static CLI_CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
 
 /// アプリケーション設定
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// 設定ファイルのスキーマバージョン（古い形式は読み込み時に移行します）
+    #[serde(default)]
+    pub version: u32,
+
     /// 検索対象のルートパス一覧
     #[serde(default)]
     pub root_paths: Vec<PathBuf>,
 
-    /// デフォルトのエディタコマンド
+    /// デフォルトのエディタコマンド（リストの場合は先頭から順に試行）
     #[serde(default)]
-    pub editor: Option<String>,
+    pub editor: Option<EditorSetting>,
 
     /// スキャンの最大深度
     #[serde(default = "default_max_depth")]
     pub max_depth: usize,
 
     /// プロジェクトとみなすマーカーファイル/ディレクトリ
+    ///
+    /// 先頭にあるものほど優先されます。複数のマーカーを持つディレクトリでは
+    /// 最初に一致したマーカーでプロジェクト種別が決まります。
     #[serde(default = "default_project_markers")]
     pub project_markers: Vec<String>,
 
     /// 除外するディレクトリ名
     #[serde(default = "default_exclude_dirs")]
     pub exclude_dirs: Vec<String>,
+
+    /// Nixの補助的なマーカー（`flake.lock`, `default.nix`, `shell.nix`）を
+    /// 最も低い優先度で追加するか
+    #[serde(default = "default_nix_markers")]
+    pub nix_markers: bool,
+
+    /// 他のマーカーがない場合に、`iac_markers` でIaC（Terraform, Pulumi, Ansible）の
+    /// プロジェクトを検出するか
+    #[serde(default)]
+    pub detect_iac: bool,
+
+    /// IaCのプロジェクトとみなすマーカー（globパターン可、例: "*.tf"）
+    #[serde(default = "default_iac_markers")]
+    pub iac_markers: Vec<String>,
+
+    /// 他のマーカーがない場合に、CI設定ファイル（GitHub Actions, GitLab CI,
+    /// CircleCI, Jenkins）でプロジェクトを検出するか
+    #[serde(default)]
+    pub detect_ci_markers: bool,
+
+    /// プロジェクトとみなさないマーカー（`project_markers` から除いて判定する）
+    #[serde(default)]
+    pub exclude_markers: Vec<String>,
+
+    /// スキャン時にシンボリックリンクをたどるか
+    #[serde(default)]
+    pub follow_symlinks: bool,
+
+    /// スキャン時に .gitignore（グローバル設定と .git/info/exclude を含む）に従うか
+    #[serde(default = "default_respect_gitignore")]
+    pub respect_gitignore: bool,
+
+    /// スキャン時に隠しディレクトリ（`.` で始まるディレクトリ）の配下も探索するか
+    #[serde(default = "default_scan_hidden")]
+    pub scan_hidden: bool,
+
+    /// スキャン前に存在しないルートパスを警告するか（対話的な実行では続行するか確認）
+    #[serde(default = "default_warn_missing_paths")]
+    pub warn_missing_paths: bool,
+
+    /// スキャンに使用するスレッド数（未設定の場合は全コアを使用）
+    #[serde(default)]
+    pub scan_threads: Option<usize>,
+
+    /// 前回のスキャン結果を再利用し、更新されたディレクトリのみ読み直すか
+    #[serde(default)]
+    pub incremental_scan: bool,
+
+    /// Cargoワークスペースのメンバークレートも個別のプロジェクトとして表示するか
+    #[serde(default)]
+    pub expand_cargo_workspaces: bool,
+
+    /// npm / pnpm / Yarn ワークスペースのメンバーパッケージも個別のプロジェクトとして表示するか
+    #[serde(default)]
+    pub expand_npm_workspaces: bool,
+
+    /// git worktree で作成した作業ツリーも個別のプロジェクトとして表示するか
+    #[serde(default)]
+    pub expand_git_worktrees: bool,
+
+    /// 除外するパスのglobパターン（フルパスに対して照合、例: "**/dist/**"）
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>,
+
+    /// 除外するディレクトリのパス（配下も含めてスキャンしない。`~` はホームディレクトリに展開）
+    ///
+    /// `exclude_dirs` は名前が一致するすべてのディレクトリを除外するのに対し、
+    /// こちらは指定した場所のディレクトリだけを除外します。
+    #[serde(default)]
+    pub exclude_paths: Vec<PathBuf>,
+
+    /// 選択UIでプロジェクト種別のアイコンを表示するか
+    #[serde(default = "default_show_icons")]
+    pub show_icons: bool,
+
+    /// 一覧表示で最終更新からの経過時間（例: "3d ago"）を表示するか
+    #[serde(default)]
+    pub show_age: bool,
+
+    /// 選択UIの右側にREADMEのプレビューを表示するか（端末の幅が100桁以上の場合のみ）
+    #[serde(default = "default_show_preview")]
+    pub show_preview: bool,
+
+    /// 選択UIを完全一致（部分文字列）検索で開始するか（Ctrl+Eであいまい検索と切り替え）
+    #[serde(default)]
+    pub exact_match: bool,
+
+    /// 選択UIの検索で大文字と小文字を区別するか（Ctrl+Sで切り替え）
+    #[serde(default)]
+    pub case_sensitive: bool,
+
+    /// 選択UIの検索欄に前回の検索クエリを入れておくか
+    #[serde(default = "default_remember_query")]
+    pub remember_query: bool,
+
+    /// 選択UIの検索欄が空のときに数字キー（1〜9）を押すと、表示中のN番目のプロジェクトをすぐに開くか
+    #[serde(default = "default_quick_open_digits")]
+    pub quick_open_digits: bool,
+
+    /// tmuxセッション名の接頭辞（例: "work/"）
+    #[serde(default)]
+    pub tmux_session_prefix: Option<String>,
+
+    /// 新しく作成したtmuxセッション内でエディタを起動するか
+    #[serde(default)]
+    pub tmux_open_editor: bool,
+
+    /// デフォルトの並び順（name, path, type, frecency, modified, age, smart）
+    #[serde(default = "default_sort")]
+    pub default_sort: String,
+
+    /// `smart` の並び順で使用する重み
+    #[serde(default)]
+    pub ranking: RankingConfig,
+
+    /// 選択UIのバックエンド（"internal"（以前の "dialoguer" でも可）、"skim"、"fzf" または "numbered"）
+    #[serde(default, alias = "selection_backend")]
+    pub selector: Selector,
+
+    /// 選択UIのプロンプト（`{count}` はプロジェクト数に置き換える）
+    #[serde(default = "default_prompt")]
+    pub prompt: String,
+
+    /// 選択UIの配色（"colorful" または "simple"）
+    #[serde(default)]
+    pub theme: SelectorTheme,
+
+    /// 選択UIと一覧表示でのグループ化方法（"root", "type", "marker"）
+    #[serde(default)]
+    pub group_by: Option<GroupMode>,
+
+    /// 選択UIに表示するプロジェクト数の上限（並び替え後の先頭から）
+    #[serde(default)]
+    pub max_results: Option<usize>,
+
+    /// 選択UIでの表示テンプレート（例: "{parent}/{name}"）
+    ///
+    /// `DISPLAY_FORMAT_PLACEHOLDERS` のプレースホルダーが使用でき、あいまい検索の対象にもなります。
+    /// 未指定時は `{name} [マーカー] ({short_path})` の形式で表示します。
+    #[serde(default)]
+    pub display_format: Option<String>,
+
+    /// プロジェクトの表示名の別名（キーはプロジェクトの実体パス）
+    #[serde(default)]
+    pub project_aliases: HashMap<PathBuf, String>,
+
+    /// 別名を設定したディレクトリの識別子（キーは別名と同じ実体パス、値は [`dir_id`]）
+    ///
+    /// ディレクトリの名前変更や移動で実体パスが変わっても、同じディレクトリに別名を付け続けるために使います。
+    #[serde(default)]
+    pub project_alias_ids: HashMap<PathBuf, String>,
+
+    /// プロジェクトのタグ（キーはプロジェクトの実体パス、タグは正規化済み）
+    #[serde(default)]
+    pub project_tags: HashMap<PathBuf, Vec<String>>,
+
+    /// プロジェクト種別ごとのエディタ（例: rust = "rustrover"）
+    #[serde(default)]
+    pub type_editors: HashMap<String, String>,
+
+    /// ユーザー定義のエディタエイリアス（例: work-code = "code --profile work"）
+    ///
+    /// 組み込みのエイリアスより優先します。値に空白を含む場合は、先頭をプログラム、
+    /// 残りをプロジェクトのパスの前に渡す引数として扱います。
+    #[serde(default)]
+    pub editor_aliases: HashMap<String, String>,
+
+    /// 端末上で動作するエディタ（切り離さずに起動する）
+    #[serde(default = "default_terminal_editors")]
+    pub terminal_editors: Vec<String>,
+
+    /// エディタ起動前に実行するシェルコマンド（失敗時は起動を中止）
+    #[serde(default)]
+    pub pre_launch_hook: Option<String>,
+
+    /// エディタ起動後にバックグラウンドで実行するシェルコマンド
+    #[serde(default)]
+    pub post_launch_hook: Option<String>,
+
+    /// エディタと一緒にターミナルを開くか
+    #[serde(default)]
+    pub open_terminal: bool,
+
+    /// `.quick-proj.toml` の `editor` / `open_in_terminal` を信頼して使うか
+    ///
+    /// リポジトリに含まれるファイルはコマンドを指定できるため、既定では無視します。
+    #[serde(default)]
+    pub trust_project_overrides: bool,
+
+    /// ターミナル起動コマンド（未設定の場合は自動検出）
+    ///
+    /// `{path}` はプロジェクトのパスに置き換えられます（例: "alacritty --working-directory {path}"）。
+    #[serde(default)]
+    pub terminal_command: Option<String>,
+
+    /// 選択UIの Ctrl+F で使うファイルマネージャー（未設定の場合はOS標準のコマンド）
+    #[serde(default)]
+    pub file_manager: Option<String>,
+
+    /// 選択したプロジェクトを開く先（"editor" または "shell"）
+    #[serde(default)]
+    pub default_launch_target: LaunchTarget,
+
+    /// 状態・スキャンキャッシュ・起動履歴の保存先（未設定の場合はOS標準のデータディレクトリ）
+    ///
+    /// プロファイルによらず、デフォルトの設定ファイル（または `--config` で指定したファイル）の値を使います。
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+
+    /// JSON形式のログを追記するファイル（未設定の場合はログを出力しない。`--log-file` で上書き）
+    #[serde(default)]
+    pub log_file: Option<PathBuf>,
+
+    /// ログに記録する最低レベル（"error", "warn", "info", "debug", "trace"）
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+}
+
+/// 設定のインポート方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportMode {
+    /// ルートパスとマーカーは和集合にし、エディタと最大深度はインポートした値を使う
+    #[default]
+    Merge,
+    /// `Merge` に加えて、その他の設定もインポートした値で上書きする
+    Overwrite,
+    /// 現在の設定をインポートした設定で置き換える
+    Replace,
+}
+
+/// インポートする設定
+///
+/// 省略したキーとデフォルト値を区別するため、ファイルに明示された値を別に持ちます。
+#[derive(Debug, Clone)]
+pub struct ImportedConfig {
+    /// 読み込んだ設定（省略したキーはデフォルト値）
+    pub config: Config,
+    /// ファイルに明示された `max_depth`
+    pub max_depth: Option<usize>,
+}
+
+/// インポートする設定ファイルに明示されたか確認するキー
+#[derive(Debug, Default, Deserialize)]
+struct ExplicitKeys {
+    max_depth: Option<usize>,
+}
+
+impl ImportedConfig {
+    /// 指定した形式の文字列からインポートする設定を読み込む
+    pub fn parse(content: &str, format: ConfigFormat) -> Result<Self> {
+        let config = Config::parse(content, format)?;
+        let explicit: ExplicitKeys = match format {
+            ConfigFormat::Toml => toml::from_str(content).context("Failed to parse TOML config")?,
+            ConfigFormat::Json => {
+                serde_json::from_str(content).context("Failed to parse JSON config")?
+            }
+        };
+
+        Ok(Self {
+            config,
+            max_depth: explicit.max_depth,
+        })
+    }
+}
+
+/// 設定のインポート結果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// 追加されたルートパスの数
+    pub added: usize,
+    /// 存在しないため取り込まなかったルートパス
+    pub skipped: Vec<PathBuf>,
+}
+
+/// 表示テンプレートで使用できるプレースホルダー
+pub const DISPLAY_FORMAT_PLACEHOLDERS: &[&str] = &[
+    "name",
+    "parent",
+    "path",
+    "short_path",
+    "full_path",
+    "marker",
+    "type",
+    "description",
+    "open_count",
+    "last_opened",
+];
+
+/// 表示テンプレートに含まれる未知のプレースホルダーを取得
+pub fn unknown_placeholders(template: &str) -> Vec<&str> {
+    let mut unknown = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+
+        let key = &after[..end];
+        if !DISPLAY_FORMAT_PLACEHOLDERS.contains(&key) && !unknown.contains(&key) {
+            unknown.push(key);
+        }
+        rest = &after[end + 1..];
+    }
+
+    unknown
+}
+
+/// プレースホルダーを `{name}, {path}` の形式で列挙
+pub fn format_placeholders(keys: &[&str]) -> String {
+    keys.iter()
+        .map(|key| format!("{{{}}}", key))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 設定の読み込み時に自動で修正した項目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigWarning {
+    /// 設定キー
+    pub key: &'static str,
+    /// 修正内容
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// プロジェクトのグループ化方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupMode {
+    /// 登録済みのルートパスごと
+    Root,
+    /// プロジェクト種別ごと
+    Type,
+    /// 検出マーカー（言語ラベル）ごと
+    Marker,
+    /// グループ化しない（設定ファイルの指定を打ち消す）
+    None,
+}
+
+impl GroupMode {
+    /// グループ化方法の名前（`--group-by` に指定する値）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupMode::Root => "root",
+            GroupMode::Type => "type",
+            GroupMode::Marker => "marker",
+            GroupMode::None => "none",
+        }
+    }
+}
+
+impl std::str::FromStr for GroupMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "root" => Ok(GroupMode::Root),
+            "type" => Ok(GroupMode::Type),
+            "marker" => Ok(GroupMode::Marker),
+            "none" => Ok(GroupMode::None),
+            _ => Err(format!(
+                "unknown group mode '{}' (expected: root, type, marker, none)",
+                s
+            )),
+        }
+    }
+}
+
+/// 設定のエクスポート・インポート形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigFormat {
+    /// TOML（デフォルト）
+    #[default]
+    Toml,
+    /// JSON
+    Json,
+}
+
+impl ConfigFormat {
+    /// ファイルの拡張子から形式を推測（`.json` 以外はTOML）
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ConfigFormat::Json,
+            _ => ConfigFormat::Toml,
+        }
+    }
+}
+
+impl std::str::FromStr for ConfigFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(ConfigFormat::Toml),
+            "json" => Ok(ConfigFormat::Json),
+            _ => Err(format!("unknown format '{}' (expected: toml, json)", s)),
+        }
+    }
+}
+
+/// 選択UIのバックエンド
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Selector {
+    /// 組み込みの選択UI（デフォルト。以前の名前の "dialoguer" でも可）
+    #[default]
+    #[serde(alias = "dialoguer")]
+    Internal,
+    /// 外部コマンドのskim（`sk`）。プレビュー表示に対応
+    Skim,
+    /// 外部コマンドのfzf。`FZF_DEFAULT_OPTS` の配色やキー割り当てをそのまま使う
+    Fzf,
+    /// 番号付きの一覧を表示し、番号またはプロジェクト名を入力して選ぶ（TUIを使えない端末向け）
+    Numbered,
+}
+
+impl std::str::FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "internal" | "dialoguer" => Ok(Selector::Internal),
+            "skim" | "sk" => Ok(Selector::Skim),
+            "fzf" => Ok(Selector::Fzf),
+            "numbered" => Ok(Selector::Numbered),
+            _ => Err(format!(
+                "unknown selector '{}' (expected: internal, skim, fzf, numbered)",
+                s
+            )),
+        }
+    }
+}
+
+/// 選択UIの配色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectorTheme {
+    /// 色付きの表示（デフォルト）
+    #[default]
+    Colorful,
+    /// 色を使わない表示（色の表示が崩れる端末向け）
+    Simple,
+}
+
+/// `smart` の並び順の重み
+///
+/// 起動回数による頻度の項と、最後に開いてからの経過日数で半減する新しさの項（最大 1.0）を足したスコアで並べます。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankingConfig {
+    /// 頻度の項（`ln(1 + 起動回数)`）に掛ける重み
+    #[serde(default = "default_frequency_weight")]
+    pub frequency_weight: f64,
+
+    /// 新しさの項が半分になるまでの日数（0 で新しさを考慮しない）
+    #[serde(default = "default_recency_half_life_days")]
+    pub recency_half_life_days: f64,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            frequency_weight: default_frequency_weight(),
+            recency_half_life_days: default_recency_half_life_days(),
+        }
+    }
+}
+
+fn default_frequency_weight() -> f64 {
+    0.5
+}
+
+fn default_recency_half_life_days() -> f64 {
+    3.0
+}
+
+/// 選択したプロジェクトを開く先
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchTarget {
+    /// エディタ（デフォルト）
+    #[default]
+    Editor,
+    /// プロジェクトのディレクトリで開くシェル
+    Shell,
+}
+
+/// エディタ設定
+///
+/// 単一のコマンド、または優先順位付きのフォールバックリストを指定できます。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EditorSetting {
+    /// 単一のエディタ
+    Single(String),
+    /// 先頭から順に試行するエディタのリスト
+    Chain(Vec<String>),
+}
+
+impl EditorSetting {
+    /// エディタのリストに変換
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            EditorSetting::Single(editor) => vec![editor.clone()],
+            EditorSetting::Chain(editors) => editors.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for EditorSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_vec().join(", "))
+    }
+}
+
+fn default_log_level() -> String {
+    "warn".to_string()
 }
 
 fn default_max_depth() -> usize {
     4
 }
 
+fn default_show_icons() -> bool {
+    true
+}
+
+fn default_respect_gitignore() -> bool {
+    true
+}
+
+fn default_scan_hidden() -> bool {
+    true
+}
+
+fn default_warn_missing_paths() -> bool {
+    true
+}
+
+fn default_remember_query() -> bool {
+    true
+}
+
+fn default_quick_open_digits() -> bool {
+    true
+}
+
+fn default_show_preview() -> bool {
+    true
+}
+
+fn default_prompt() -> String {
+    "Select a project".to_string()
+}
+
+fn default_sort() -> String {
+    "name".to_string()
+}
+
 fn default_project_markers() -> Vec<String> {
+    // 言語固有のマーカーを汎用的なもの（Makefile, .git）より優先する
     vec![
-        ".git".to_string(),
         "Cargo.toml".to_string(),
         "package.json".to_string(),
         "go.mod".to_string(),
@@ -52,13 +648,44 @@ fn default_project_markers() -> Vec<String> {
         "setup.py".to_string(),
         "pom.xml".to_string(),
         "build.gradle".to_string(),
-        "Makefile".to_string(),
         "CMakeLists.txt".to_string(),
         "composer.json".to_string(),
         "Gemfile".to_string(),
         "mix.exs".to_string(),
         "deno.json".to_string(),
+        "pubspec.yaml".to_string(),
+        "*.sln".to_string(),
+        "*.csproj".to_string(),
+        "Package.swift".to_string(),
+        "build.zig".to_string(),
+        "flake.nix".to_string(),
+        "Makefile".to_string(),
+        ".git".to_string(),
+    ]
+}
+
+fn default_iac_markers() -> Vec<String> {
+    [
+        "main.tf",
+        "*.tf",
+        "Pulumi.yaml",
+        "playbook.yml",
+        "ansible.cfg",
     ]
+    .iter()
+    .map(|marker| marker.to_string())
+    .collect()
+}
+
+fn default_nix_markers() -> bool {
+    true
+}
+
+fn default_terminal_editors() -> Vec<String> {
+    ["vi", "vim", "nvim", "nano", "hx", "helix", "micro", "kak"]
+        .iter()
+        .map(|editor| editor.to_string())
+        .collect()
 }
 
 fn default_exclude_dirs() -> Vec<String> {
@@ -80,39 +707,274 @@ fn default_exclude_dirs() -> Vec<String> {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             root_paths: vec![],
             editor: None,
             max_depth: default_max_depth(),
             project_markers: default_project_markers(),
             exclude_dirs: default_exclude_dirs(),
+            nix_markers: default_nix_markers(),
+            detect_iac: false,
+            iac_markers: default_iac_markers(),
+            detect_ci_markers: false,
+            exclude_markers: vec![],
+            follow_symlinks: false,
+            respect_gitignore: default_respect_gitignore(),
+            scan_hidden: default_scan_hidden(),
+            warn_missing_paths: default_warn_missing_paths(),
+            scan_threads: None,
+            incremental_scan: false,
+            expand_cargo_workspaces: false,
+            expand_npm_workspaces: false,
+            expand_git_worktrees: false,
+            exclude_patterns: vec![],
+            exclude_paths: vec![],
+            show_icons: default_show_icons(),
+            show_age: false,
+            show_preview: default_show_preview(),
+            exact_match: false,
+            case_sensitive: false,
+            remember_query: default_remember_query(),
+            quick_open_digits: default_quick_open_digits(),
+            tmux_session_prefix: None,
+            tmux_open_editor: false,
+            default_sort: default_sort(),
+            ranking: RankingConfig::default(),
+            selector: Selector::default(),
+            prompt: default_prompt(),
+            theme: SelectorTheme::default(),
+            group_by: None,
+            max_results: None,
+            display_format: None,
+            project_aliases: HashMap::new(),
+            project_alias_ids: HashMap::new(),
+            project_tags: HashMap::new(),
+            type_editors: HashMap::new(),
+            editor_aliases: HashMap::new(),
+            terminal_editors: default_terminal_editors(),
+            pre_launch_hook: None,
+            post_launch_hook: None,
+            open_terminal: false,
+            trust_project_overrides: false,
+            terminal_command: None,
+            file_manager: None,
+            default_launch_target: LaunchTarget::default(),
+            data_dir: None,
+            log_file: None,
+            log_level: default_log_level(),
         }
     }
 }
 
 impl Config {
-    /// 設定ファイルを読み込む
+    /// 使用中の設定ファイルを読み込む
     ///
     /// ファイルが存在しない場合はデフォルト設定を返します。
     pub fn load() -> Result<Self> {
-        let config_path = Self::config_path()?;
+        Self::load_checked().map(|(config, _)| config)
+    }
+
+    /// 使用中の設定ファイルを読み込み、読み込み時に修正した項目も返す
+    pub fn load_checked() -> Result<(Self, Vec<ConfigWarning>)> {
+        Self::load_from(&Self::config_path()?)
+    }
 
+    /// 使用中の設定ファイルを正規化せずに読み込む
+    ///
+    /// 設定を書き換えて保存するコマンドと、設定の検証で使います。
+    /// 範囲外の `max_depth` や重複したルートパスも、ファイルに書かれたまま保持します。
+    pub fn load_raw() -> Result<Self> {
+        Self::read_from(&Self::config_path()?).map(|(config, _)| config)
+    }
+
+    /// 指定したパスの設定ファイルを読み込み、正規化する
+    fn load_from(config_path: &Path) -> Result<(Self, Vec<ConfigWarning>)> {
+        let (mut config, mut warnings) = Self::read_from(config_path)?;
+        warnings.extend(config.normalize());
+        Ok((config, warnings))
+    }
+
+    /// 指定したパスの設定ファイルを正規化せずに読み込む
+    ///
+    /// 古いバージョンの設定ファイルはメモリ上で現在の形式に移行して読み込み、ファイルは書き換えません。
+    /// 旧形式のままでは読めない変更がある場合だけ、`config migrate` の実行を促す警告を返します。
+    fn read_from(config_path: &Path) -> Result<(Self, Vec<ConfigWarning>)> {
         if !config_path.exists() {
-            return Ok(Self::default());
+            return Ok((Self::default(), vec![]));
         }
 
-        let content = fs::read_to_string(&config_path)
+        let content = fs::read_to_string(config_path)
             .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
 
-        let config: Config = toml::from_str(&content)
+        let parse_context = || format!("Failed to parse config file: {}", config_path.display());
+        let mut document: toml_edit::DocumentMut = content.parse().with_context(parse_context)?;
+        let migration = migrate(&mut document);
+        let table: toml::Table = document.to_string().parse().with_context(parse_context)?;
+        let config = Self::from_table(table).with_context(parse_context)?;
+
+        let mut warnings = Vec::new();
+        if let Some(Migration {
+            from,
+            rewrite: true,
+        }) = migration
+        {
+            warnings.push(ConfigWarning {
+                key: "version",
+                message: format!(
+                    "Config uses the version {} format; run `quick-proj config migrate` to update it",
+                    from
+                ),
+            });
+        } else if config.version > CONFIG_VERSION {
+            warnings.push(ConfigWarning {
+                key: "version",
+                message: format!(
+                    "Config was written by a newer quick-proj (version {}, supported: {}); \
+                     unknown settings are ignored",
+                    config.version, CONFIG_VERSION
+                ),
+            });
+        }
+
+        Ok((config, warnings))
+    }
+
+    /// TOMLから設定を読み込む
+    ///
+    /// 古いバージョンの形式は現在の形式に移行してから読み込みます。
+    fn from_toml(content: &str) -> Result<Self> {
+        let mut document: toml_edit::DocumentMut = content.parse()?;
+        migrate(&mut document);
+        Self::from_table(document.to_string().parse()?)
+    }
+
+    /// 設定ファイルを現在のスキーマバージョンに書き直す（`config migrate` 用）
+    ///
+    /// コメントや書式はそのまま残し、移行で変わるキーだけを書き換えます。
+    /// 書き直す前に元のファイルを `<ファイル名>.bak` に保存し、移行した場合は元のバージョンを返します。
+    pub fn migrate_file(config_path: &Path) -> Result<Option<u32>> {
+        let content = fs::read_to_string(config_path)
+            .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+        let mut document: toml_edit::DocumentMut = content
+            .parse()
             .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
 
-        Ok(config)
+        let Some(migration) = migrate(&mut document) else {
+            return Ok(None);
+        };
+
+        let mut backup = config_path.as_os_str().to_owned();
+        backup.push(".bak");
+        let backup = PathBuf::from(backup);
+        fs::write(&backup, &content)
+            .with_context(|| format!("Failed to write backup: {}", backup.display()))?;
+        fs::write(config_path, document.to_string())
+            .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
+
+        Ok(Some(migration.from))
+    }
+
+    /// TOMLのテーブルから設定を読み込む
+    ///
+    /// 値の型が合わない場合は、どのキーのどの値が問題かをエラーに含めます。
+    fn from_table(table: toml::Table) -> Result<Self> {
+        toml::Value::Table(table.clone())
+            .try_into()
+            .map_err(|err: toml::de::Error| {
+                // キーを1つずつ読み込んで、問題のあるキーを特定する
+                table
+                    .iter()
+                    .find_map(|(key, value)| {
+                        let single = toml::Table::from_iter([(key.clone(), value.clone())]);
+                        toml::Value::Table(single)
+                            .try_into::<Config>()
+                            .err()
+                            .map(|e| {
+                                anyhow!(
+                                    "Invalid value for `{}` ({} = {}): {}",
+                                    key,
+                                    key,
+                                    value,
+                                    e.message()
+                                )
+                            })
+                    })
+                    .unwrap_or_else(|| err.into())
+            })
+    }
+
+    /// 読み込んだ設定を正規化し、修正した項目を返す
+    ///
+    /// `max_depth` を妥当な範囲に収め、重複したルートパスとマーカーを取り除きます。
+    pub fn normalize(&mut self) -> Vec<ConfigWarning> {
+        let mut warnings = Vec::new();
+
+        let clamped = self
+            .max_depth
+            .clamp(*MAX_DEPTH_RANGE.start(), *MAX_DEPTH_RANGE.end());
+        if clamped != self.max_depth {
+            warnings.push(ConfigWarning {
+                key: "max_depth",
+                message: format!(
+                    "{} is out of range ({}-{}), using {}",
+                    self.max_depth,
+                    MAX_DEPTH_RANGE.start(),
+                    MAX_DEPTH_RANGE.end(),
+                    clamped
+                ),
+            });
+            self.max_depth = clamped;
+        }
+
+        let mut seen = HashSet::new();
+        self.root_paths.retain(|path| {
+            let unique = seen.insert(resolve_root(path));
+            if !unique {
+                warnings.push(ConfigWarning {
+                    key: "root_paths",
+                    message: format!("Ignoring duplicate path {}", path.display()),
+                });
+            }
+            unique
+        });
+
+        let mut seen = HashSet::new();
+        self.project_markers.retain(|marker| {
+            let unique = seen.insert(marker.clone());
+            if !unique {
+                warnings.push(ConfigWarning {
+                    key: "project_markers",
+                    message: format!("Ignoring duplicate marker \"{}\"", marker),
+                });
+            }
+            unique
+        });
+
+        // 未知のプレースホルダーはそのまま表示されるため、警告だけにとどめる
+        if let Some(template) = &self.display_format {
+            let unknown = unknown_placeholders(template);
+            if !unknown.is_empty() {
+                warnings.push(ConfigWarning {
+                    key: "display_format",
+                    message: format!(
+                        "Unknown placeholder {} (available: {})",
+                        format_placeholders(&unknown),
+                        format_placeholders(DISPLAY_FORMAT_PLACEHOLDERS)
+                    ),
+                });
+            }
+        }
+
+        warnings
     }
 
-    /// 設定ファイルに保存する
+    /// 使用中の設定ファイルに保存する
     pub fn save(&self) -> Result<()> {
-        let config_path = Self::config_path()?;
+        self.save_to(&Self::config_path()?)
+    }
 
+    /// 指定したパスに保存する
+    fn save_to(&self, config_path: &Path) -> Result<()> {
         // 親ディレクトリを作成
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent).with_context(|| {
@@ -122,21 +984,28 @@ impl Config {
 
         let content = toml::to_string_pretty(self).with_context(|| "Failed to serialize config")?;
 
-        fs::write(&config_path, content)
+        fs::write(config_path, content)
             .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
 
         Ok(())
     }
 
-    /// 設定ファイルのパスを取得
+    /// 使用中の設定ファイルのパスを取得
+    ///
+    /// `--config` または環境変数 `QUICK_PROJ_CONFIG` が指定されている場合はそのパスを、
+    /// それ以外は使用中のプロファイルの設定ファイルのパスを返します。
     pub fn config_path() -> Result<PathBuf> {
-        let proj_dirs =
-            ProjectDirs::from("", "", APP_NAME).context("Failed to determine config directory")?;
+        if let Some((path, _)) = config_path_override() {
+            return expand_path(&path);
+        }
 
-        Ok(proj_dirs.config_dir().join(CONFIG_FILE_NAME))
+        profile_path(&active_profile())
     }
 
     /// ルートパスを追加
+    ///
+    /// シンボリックリンクはリンクのパスのまま登録し、スキャン時にリンク先を探索します。
+    /// 重複チェックはリンク先の実体で行います。
     pub fn add_root_path(&mut self, path: &Path) -> Result<bool> {
         // パスを展開して正規化
         let expanded = expand_path(path)?;
@@ -148,52 +1017,799 @@ impl Config {
         })?;
 
         // 既に登録済みかチェック
-        if self.root_paths.contains(&canonical) {
+        if self.root_paths.iter().any(|p| resolve_root(p) == canonical) {
             return Ok(false);
         }
 
-        self.root_paths.push(canonical);
+        let entry = if is_symlink(&expanded) {
+            std::path::absolute(&expanded)
+                .with_context(|| format!("Failed to resolve path: {}", expanded.display()))?
+        } else {
+            canonical
+        };
+
+        self.root_paths.push(entry);
         Ok(true)
     }
 
-    /// ルートパスを削除
-    pub fn remove_root_path(&mut self, path: &Path) -> Result<bool> {
-        let expanded = expand_path(path)?;
+    /// 指定したパスと重なる（祖先または子孫にあたる）登録済みのルートパスを取得
+    ///
+    /// ルートが重なるとスキャンが重複するため、追加時の警告に使います。
+    pub fn overlapping_roots(&self, path: &Path) -> Vec<&PathBuf> {
+        let target = resolve_root(path);
+
+        self.root_paths
+            .iter()
+            .filter(|root| {
+                let root = resolve_root(root);
+                root != target && (target.starts_with(&root) || root.starts_with(&target))
+            })
+            .collect()
+    }
+
+    /// ルートパスを削除
+    pub fn remove_root_path(&mut self, path: &Path) -> Result<bool> {
+        let expanded = expand_path(path)?;
 
         // 正規化を試みる（存在しない場合は展開後のパスで比較）
-        let target = fs::canonicalize(&expanded).unwrap_or(expanded);
+        let target = fs::canonicalize(&expanded).unwrap_or_else(|_| expanded.clone());
 
         let original_len = self.root_paths.len();
-        self.root_paths.retain(|p| p != &target);
+        self.root_paths
+            .retain(|p| p != &expanded && resolve_root(p) != target);
 
         Ok(self.root_paths.len() < original_len)
     }
 
+    /// 一覧の番号（1始まり）でルートパスを削除し、削除したパスを返す
+    pub fn remove_root_path_at(&mut self, index: usize) -> Result<PathBuf> {
+        if self.root_paths.is_empty() {
+            bail!("No root paths configured");
+        }
+
+        if index == 0 || index > self.root_paths.len() {
+            bail!(
+                "Index {} is out of range (valid: 1-{})",
+                index,
+                self.root_paths.len()
+            );
+        }
+
+        Ok(self.root_paths.remove(index - 1))
+    }
+
+    /// 存在しないルートパスの一覧を取得
+    pub fn missing_root_paths(&self) -> Vec<PathBuf> {
+        self.root_paths
+            .iter()
+            .filter(|p| !p.exists())
+            .cloned()
+            .collect()
+    }
+
+    /// 存在しないルートパスを削除し、削除したパスを返す
+    pub fn prune_root_paths(&mut self) -> Result<Vec<PathBuf>> {
+        let missing = self.missing_root_paths();
+
+        for path in &missing {
+            self.remove_root_path(path)?;
+        }
+
+        Ok(missing)
+    }
+
+    /// 元のパスが存在しない別名を、ディレクトリの識別子から引けるようにした一覧
+    ///
+    /// キーは [`dir_id`]、値は別名を設定したときの実体パスです。
+    pub fn moved_alias_paths(&self) -> HashMap<String, PathBuf> {
+        self.project_alias_ids
+            .iter()
+            .filter(|(path, _)| self.project_aliases.contains_key(*path) && !path.exists())
+            .map(|(path, id)| (id.clone(), path.clone()))
+            .collect()
+    }
+
+    /// 別名を設定した後に名前変更・移動されたプロジェクトを探す
+    ///
+    /// 別名のパスが存在せず、記録した識別子と同じディレクトリが `dirs` にある場合に、
+    /// 元のパスと新しい実体パスの組を返します。
+    pub fn renamed_project_paths<'a>(
+        &self,
+        dirs: impl IntoIterator<Item = &'a Path>,
+    ) -> Vec<(PathBuf, PathBuf)> {
+        let moved = self.moved_alias_paths();
+        if moved.is_empty() {
+            return vec![];
+        }
+
+        let mut renamed: Vec<(PathBuf, PathBuf)> = dirs
+            .into_iter()
+            .filter_map(|dir| {
+                let key = resolve_root(dir);
+                let old = moved.get(&dir_id(&key)?)?;
+                Some((old.clone(), key))
+            })
+            .collect();
+        renamed.sort();
+        renamed.dedup();
+        renamed
+    }
+
+    /// 名前変更・移動されたプロジェクトの別名・タグを新しいパスに付け替える
+    pub fn move_project_path(&mut self, old: &Path, new: &Path) {
+        if let Some(alias) = self.project_aliases.remove(old) {
+            self.project_aliases.insert(new.to_path_buf(), alias);
+        }
+        if let Some(id) = self.project_alias_ids.remove(old) {
+            self.project_alias_ids.insert(new.to_path_buf(), id);
+        }
+        if let Some(tags) = self.project_tags.remove(old) {
+            self.project_tags.insert(new.to_path_buf(), tags);
+        }
+    }
+
+    /// 別名・タグが設定された、存在しないプロジェクトのパスの一覧を取得
+    pub fn stale_project_paths(&self) -> Vec<PathBuf> {
+        let mut stale: Vec<PathBuf> = self
+            .project_aliases
+            .keys()
+            .chain(self.project_tags.keys())
+            .filter(|p| !p.exists())
+            .cloned()
+            .collect();
+        stale.sort();
+        stale.dedup();
+        stale
+    }
+
+    /// 存在しないプロジェクトの別名・タグを削除し、削除したパスを返す
+    pub fn prune_project_paths(&mut self) -> Vec<PathBuf> {
+        let stale = self.stale_project_paths();
+
+        for path in &stale {
+            self.project_aliases.remove(path);
+            self.project_alias_ids.remove(path);
+            self.project_tags.remove(path);
+        }
+
+        stale
+    }
+
+    /// 設定を指定した形式の文字列に変換
+    pub fn export(&self, format: ConfigFormat) -> Result<String> {
+        match format {
+            ConfigFormat::Toml => {
+                toml::to_string_pretty(self).context("Failed to serialize config")
+            }
+            ConfigFormat::Json => {
+                serde_json::to_string_pretty(self).context("Failed to serialize config")
+            }
+        }
+    }
+
+    /// 指定した形式の文字列から設定を読み込む
+    pub fn parse(content: &str, format: ConfigFormat) -> Result<Self> {
+        match format {
+            ConfigFormat::Toml => Self::from_toml(content).context("Failed to parse TOML config"),
+            ConfigFormat::Json => {
+                let mut config: Config =
+                    serde_json::from_str(content).context("Failed to parse JSON config")?;
+                // 旧形式のキーは別名で読み込めるため、バージョンだけを揃える
+                config.version = config.version.max(CONFIG_VERSION);
+                Ok(config)
+            }
+        }
+    }
+
+    /// 除外するディレクトリのパスを展開する
+    ///
+    /// 走査するパスはルートパスの書き方のままなので、展開したパスに加えて
+    /// 実体パスが異なる場合はそれも返します（シンボリックリンク経由でもどちらでも一致します）。
+    pub fn resolved_exclude_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for path in self
+            .exclude_paths
+            .iter()
+            .filter_map(|path| expand_path(path).ok())
+        {
+            let real = resolve_root(&path);
+            if real != path {
+                paths.push(real);
+            }
+            paths.push(path);
+        }
+        paths
+    }
+
+    /// インポートした設定を取り込む
+    ///
+    /// インポートしたルートパスは実体パスに解決し、存在しないものは取り込まずに
+    /// 結果の `skipped` に含めます。取り込み方は `mode` で切り替えます。
+    /// `Merge` ではファイルに明示された設定だけを上書きします。
+    pub fn merge(&mut self, imported: ImportedConfig, mode: ImportMode) -> ImportReport {
+        let ImportedConfig {
+            config: imported,
+            max_depth,
+        } = imported;
+        let mut report = ImportReport::default();
+        let mut imported_roots = Vec::new();
+
+        for path in &imported.root_paths {
+            match expand_path(path) {
+                Ok(expanded) if expanded.is_dir() => imported_roots.push(resolve_root(&expanded)),
+                _ => report.skipped.push(path.clone()),
+            }
+        }
+
+        let (mut root_paths, mut markers) = match mode {
+            ImportMode::Replace => (vec![], vec![]),
+            _ => (
+                std::mem::take(&mut self.root_paths),
+                std::mem::take(&mut self.project_markers),
+            ),
+        };
+
+        for path in imported_roots {
+            if !root_paths.iter().any(|p| resolve_root(p) == path) {
+                root_paths.push(path);
+                report.added += 1;
+            }
+        }
+
+        for marker in &imported.project_markers {
+            if !markers.contains(marker) {
+                markers.push(marker.clone());
+            }
+        }
+
+        match mode {
+            ImportMode::Merge => {
+                if imported.editor.is_some() {
+                    self.editor = imported.editor;
+                }
+                if let Some(max_depth) = max_depth {
+                    self.max_depth = max_depth;
+                }
+            }
+            ImportMode::Overwrite | ImportMode::Replace => *self = imported,
+        }
+        self.root_paths = root_paths;
+        self.project_markers = markers;
+
+        report
+    }
+
+    /// プロジェクトの別名を設定
+    ///
+    /// パスは実体パスに解決して登録します。既存の別名は上書きします。
+    /// 名前変更や移動の後も別名を引き継げるよう、ディレクトリの識別子も記録します。
+    pub fn set_project_alias(&mut self, path: &Path, alias: &str) -> Result<PathBuf> {
+        let alias = alias.trim();
+        if alias.is_empty() {
+            bail!("Alias must not be empty");
+        }
+
+        let expanded = expand_path(path)?;
+        if !expanded.is_dir() {
+            bail!("Directory does not exist: {}", expanded.display());
+        }
+
+        let key = resolve_root(&expanded);
+        self.project_aliases.insert(key.clone(), alias.to_string());
+        match dir_id(&key) {
+            Some(id) => self.project_alias_ids.insert(key.clone(), id),
+            None => self.project_alias_ids.remove(&key),
+        };
+        Ok(key)
+    }
+
+    /// プロジェクトの別名を削除
+    ///
+    /// 削除した場合は `true` を返します。ディレクトリが既に存在しなくても削除できます。
+    pub fn remove_project_alias(&mut self, path: &Path) -> Result<bool> {
+        let expanded = expand_path(path)?;
+        let mut removed = false;
+        for key in [resolve_root(&expanded), expanded] {
+            self.project_alias_ids.remove(&key);
+            removed |= self.project_aliases.remove(&key).is_some();
+        }
+        Ok(removed)
+    }
+
+    /// プロジェクトにタグを追加
+    ///
+    /// パスは実体パスに解決して登録します。既に同じタグがある場合は `false` を返します。
+    pub fn add_project_tag(&mut self, path: &Path, tag: &str) -> Result<bool> {
+        let tag = canonicalize_tag(tag)?;
+
+        let expanded = expand_path(path)?;
+        if !expanded.is_dir() {
+            bail!("Directory does not exist: {}", expanded.display());
+        }
+
+        let tags = self
+            .project_tags
+            .entry(resolve_root(&expanded))
+            .or_default();
+        if tags.contains(&tag) {
+            return Ok(false);
+        }
+        tags.push(tag);
+        tags.sort();
+        Ok(true)
+    }
+
+    /// プロジェクトからタグを削除
+    ///
+    /// 削除した場合は `true` を返します。タグがなくなったプロジェクトは設定から取り除きます。
+    pub fn remove_project_tag(&mut self, path: &Path, tag: &str) -> Result<bool> {
+        let tag = canonicalize_tag(tag)?;
+        let expanded = expand_path(path)?;
+
+        for key in [resolve_root(&expanded), expanded] {
+            let Some(tags) = self.project_tags.get_mut(&key) else {
+                continue;
+            };
+            let before = tags.len();
+            tags.retain(|t| *t != tag);
+            if tags.len() == before {
+                continue;
+            }
+            if tags.is_empty() {
+                self.project_tags.remove(&key);
+            }
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// 除外パターンを追加
+    ///
+    /// 既に登録済みの場合は `false` を返します。
+    pub fn add_exclude_pattern(&mut self, pattern: &str) -> Result<bool> {
+        glob::Pattern::new(pattern)
+            .with_context(|| format!("Invalid glob pattern: {}", pattern))?;
+
+        if self.exclude_patterns.iter().any(|p| p == pattern) {
+            return Ok(false);
+        }
+
+        self.exclude_patterns.push(pattern.to_string());
+        Ok(true)
+    }
+
+    /// 除外パターンを削除
+    pub fn remove_exclude_pattern(&mut self, pattern: &str) -> bool {
+        let original_len = self.exclude_patterns.len();
+        self.exclude_patterns.retain(|p| p != pattern);
+        self.exclude_patterns.len() < original_len
+    }
+
     /// エディタを設定
     pub fn set_editor(&mut self, editor: &str) {
-        self.editor = Some(editor.to_string());
+        self.editor = Some(EditorSetting::Single(editor.to_string()));
     }
 
-    /// 使用するエディタを取得（優先順位に従う）
-    pub fn get_editor(&self, cli_editor: Option<&str>) -> String {
+    /// プロジェクト種別のエディタを設定
+    pub fn set_type_editor(&mut self, project_type: ProjectType, editor: &str) {
+        self.type_editors
+            .insert(project_type.as_str().to_string(), editor.to_string());
+    }
+
+    /// スキャンで使用するマーカーを優先順に取得
+    ///
+    /// `nix_markers` が有効な場合は、Nixの補助的なマーカーを末尾に追加します。
+    pub fn active_markers(&self) -> Vec<String> {
+        let mut markers = self.project_markers.clone();
+
+        if self.nix_markers {
+            for marker in NIX_EXTRA_MARKERS {
+                if !markers.iter().any(|m| m == marker) {
+                    markers.push(marker.to_string());
+                }
+            }
+        }
+
+        markers
+    }
+
+    /// 使用するエディタの候補リストを取得（優先順位に従う）
+    pub fn get_editor(
+        &self,
+        cli_editor: Option<&str>,
+        project_type: Option<ProjectType>,
+    ) -> Vec<String> {
         // 1. CLIオプション
         if let Some(editor) = cli_editor {
-            return editor.to_string();
+            return vec![editor.to_string()];
         }
 
         // 2. 設定ファイル
         if let Some(ref editor) = self.editor {
-            return editor.clone();
+            return editor.to_vec();
+        }
+
+        // 3. プロジェクト種別ごとの設定
+        if let Some(editor) = project_type.and_then(|t| self.type_editors.get(t.as_str())) {
+            return vec![editor.clone()];
         }
 
-        // 3. 環境変数 EDITOR
+        // 4. 環境変数 EDITOR
         if let Ok(editor) = std::env::var("EDITOR") {
-            return editor;
+            return vec![editor];
+        }
+
+        // 5. デフォルト
+        vec!["code".to_string()]
+    }
+}
+
+/// 環境変数 `QUICK_PROJ_CONFIG` で指定された設定ファイルのパス
+pub fn env_config_path() -> Option<PathBuf> {
+    std::env::var_os(CONFIG_ENV)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `--config` で指定された設定ファイルのパスを記録する
+///
+/// 設定を読み込む前に一度だけ呼び出します。
+pub fn init_config_path(cli_path: Option<&Path>) {
+    if let Some(path) = cli_path {
+        let _ = CLI_CONFIG_PATH.set(path.to_path_buf());
+    }
+}
+
+/// 設定ファイルのパスの指定と、その指定元（`--config` または環境変数名）
+///
+/// 優先順位: `--config` > 環境変数 `QUICK_PROJ_CONFIG`。どちらもない場合は `None` です。
+pub fn config_path_override() -> Option<(PathBuf, &'static str)> {
+    select_config_override(CLI_CONFIG_PATH.get().cloned(), env_config_path())
+}
+
+/// `--config` と環境変数の指定から、使用する設定ファイルのパスを選ぶ
+fn select_config_override(
+    cli_path: Option<PathBuf>,
+    env_path: Option<PathBuf>,
+) -> Option<(PathBuf, &'static str)> {
+    cli_path
+        .map(|path| (path, "--config"))
+        .or_else(|| env_path.map(|path| (path, CONFIG_ENV)))
+}
+
+/// データディレクトリ内のファイルのパスを取得
+///
+/// 状態・キャッシュ・履歴を保存するモジュールはすべてこの関数でパスを決めます。
+/// ディレクトリは作成しないため、書き込む側が保存時に作成します。
+pub fn data_path(file_name: &str) -> Result<PathBuf> {
+    Ok(data_dir()?.join(file_name))
+}
+
+/// データディレクトリを取得
+///
+/// 優先順位: 環境変数 `QUICK_PROJ_DATA_DIR` > 設定の `data_dir` > OS標準のデータディレクトリ。
+pub fn data_dir() -> Result<PathBuf> {
+    let env_dir = std::env::var_os(DATA_DIR_ENV)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+    let configured = if env_dir.is_some() {
+        None
+    } else {
+        configured_data_dir()
+    };
+    let default_dir =
+        ProjectDirs::from("", "", APP_NAME).map(|proj_dirs| proj_dirs.data_dir().to_path_buf());
+
+    select_data_dir(env_dir, configured, default_dir)
+        .context("Failed to determine data directory")
+        .and_then(|dir| expand_path(&dir))
+}
+
+/// 環境変数・設定・OS標準のデータディレクトリから、使用するものを選ぶ
+fn select_data_dir(
+    env_dir: Option<PathBuf>,
+    configured: Option<PathBuf>,
+    default_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    env_dir.or(configured).or(default_dir)
+}
+
+/// 設定ファイルの `data_dir`
+///
+/// 状態の読み込みはプロファイルの決定より前に行うため、プロファイルの設定ファイルではなく
+/// デフォルトの設定ファイル（または `--config` / `QUICK_PROJ_CONFIG` で指定したファイル）から読みます。
+/// 読み込めない場合は `None` です（設定ファイル自体のエラーは `Config::load` で報告します）。
+fn configured_data_dir() -> Option<PathBuf> {
+    let path = match config_path_override() {
+        Some((path, _)) => expand_path(&path).ok()?,
+        None => config_dir().ok()?.join(CONFIG_FILE_NAME),
+    };
+    let content = fs::read_to_string(path).ok()?;
+    let table: toml::Table = toml::from_str(&content).ok()?;
+
+    table
+        .get("data_dir")
+        .and_then(toml::Value::as_str)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 設定ディレクトリを取得
+fn config_dir() -> Result<PathBuf> {
+    let proj_dirs =
+        ProjectDirs::from("", "", APP_NAME).context("Failed to determine config directory")?;
+
+    Ok(proj_dirs.config_dir().to_path_buf())
+}
+
+/// プロファイル名に対応する設定ファイル名
+///
+/// `default` は `config.toml`、それ以外は `config-<name>.toml` になります。
+fn profile_file_name(name: &str) -> String {
+    if name == DEFAULT_PROFILE {
+        CONFIG_FILE_NAME.to_string()
+    } else {
+        format!("config-{}.toml", name)
+    }
+}
+
+/// プロファイルの設定ファイルのパスを取得
+pub fn profile_path(name: &str) -> Result<PathBuf> {
+    validate_profile_name(name)?;
+    Ok(config_dir()?.join(profile_file_name(name)))
+}
+
+/// プロファイル名として使用できるかチェック（英数字、`-`、`_` のみ）
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !is_valid {
+        bail!(
+            "Invalid profile name '{}': use only letters, digits, '-' and '_'",
+            name
+        );
+    }
+
+    Ok(())
+}
+
+/// 現在使用中のプロファイル名を取得
+pub fn active_profile() -> String {
+    ACTIVE_PROFILE
+        .get()
+        .cloned()
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// 使用するプロファイルを決定する
+///
+/// 優先順位: `--profile` > 環境変数 `QUICK_PROJ_PROFILE` > 前回使用したプロファイル > `default`。
+/// `--profile` で指定した場合のみ、次回のために状態ファイルへ記録します。
+pub fn init_profile(cli_profile: Option<&str>) -> Result<()> {
+    let env_profile = std::env::var(PROFILE_ENV).ok().filter(|p| !p.is_empty());
+
+    let profile = match cli_profile.or(env_profile.as_deref()) {
+        Some(name) => {
+            if name != DEFAULT_PROFILE && !profile_path(name)?.exists() {
+                bail!(
+                    "Profile '{}' does not exist. Create it with `quick-proj profile create {}`",
+                    name,
+                    name
+                );
+            }
+
+            if cli_profile.is_some() {
+                let mut state = State::load()?;
+                if state.last_profile.as_deref() != Some(name) {
+                    state.last_profile = Some(name.to_string());
+                    state.save()?;
+                }
+            }
+            name.to_string()
         }
+        None => State::load()
+            .ok()
+            .and_then(|state| state.last_profile)
+            .filter(|name| profile_path(name).is_ok_and(|p| p.exists()))
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string()),
+    };
 
-        // 4. デフォルト
-        "code".to_string()
+    let _ = ACTIVE_PROFILE.set(profile);
+    Ok(())
+}
+
+/// 存在するプロファイルの一覧を取得（`default` は常に含む）
+pub fn list_profiles() -> Result<Vec<String>> {
+    let mut profiles = vec![DEFAULT_PROFILE.to_string()];
+
+    if let Ok(entries) = fs::read_dir(config_dir()?) {
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                file_name
+                    .strip_prefix("config-")?
+                    .strip_suffix(".toml")
+                    .map(str::to_string)
+            })
+            .filter(|name| validate_profile_name(name).is_ok())
+            .collect();
+        names.sort();
+        profiles.extend(names);
+    }
+
+    Ok(profiles)
+}
+
+/// 新しいプロファイルをデフォルト設定で作成
+pub fn create_profile(name: &str) -> Result<PathBuf> {
+    let path = profile_path(name)?;
+
+    if path.exists() {
+        bail!("Profile '{}' already exists", name);
+    }
+
+    Config::default().save_to(&path)?;
+    Ok(path)
+}
+
+/// プロファイルを削除（`default` は削除不可）
+pub fn delete_profile(name: &str) -> Result<()> {
+    if name == DEFAULT_PROFILE {
+        bail!("The default profile cannot be deleted");
+    }
+
+    let path = profile_path(name)?;
+    if !path.exists() {
+        bail!("Profile '{}' does not exist", name);
+    }
+
+    fs::remove_file(&path)
+        .with_context(|| format!("Failed to delete profile: {}", path.display()))?;
+
+    // 前回のプロファイルとして記録されていれば解除する
+    let mut state = State::load()?;
+    if state.last_profile.as_deref() == Some(name) {
+        state.last_profile = None;
+        state.save()?;
     }
+
+    Ok(())
+}
+
+/// パスがシンボリックリンクかどうか
+pub fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+/// 設定ファイルの移行結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Migration {
+    /// 移行前のバージョン
+    from: u32,
+    /// 旧形式のままでは読み込めない変更を含むか（ファイルの書き直しが必要か）
+    rewrite: bool,
+}
+
+/// 古いスキーマバージョンの設定を現在の形式に移行する
+///
+/// 1バージョンずつ順に移行し、移行した場合は元のバージョンと書き直しの要否を返します。
+/// `version` が整数でない場合は移行せず、読み込み時のエラーに任せます。
+fn migrate(document: &mut toml_edit::DocumentMut) -> Option<Migration> {
+    let from = match document.get("version") {
+        None => 0,
+        Some(value) => u32::try_from(value.as_integer()?).ok()?,
+    };
+    if from >= CONFIG_VERSION {
+        return None;
+    }
+
+    let mut rewrite = false;
+    if from < 1 {
+        rewrite |= migrate_v0(document);
+    }
+
+    document["version"] = toml_edit::value(i64::from(CONFIG_VERSION));
+    Some(Migration { from, rewrite })
+}
+
+/// バージョン0（`version` なし）からバージョン1への移行
+///
+/// `selection_backend` を `selector` に置き換えます。
+/// 別名で読み込めるため、ファイルの書き直しは不要です（`false` を返します）。
+fn migrate_v0(document: &mut toml_edit::DocumentMut) -> bool {
+    if let Some(backend) = document.remove("selection_backend") {
+        if !document.contains_key("selector") {
+            document.insert("selector", backend);
+        }
+    }
+    false
+}
+
+/// 登録済みルートパスの実体を取得（解決できない場合はそのまま）
+fn resolve_root(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// タグを正規化する（小文字化し、空白とアンダースコアをハイフンに置換）
+///
+/// 正規化後のタグは `[a-z0-9-]+` に一致する必要があります。
+pub fn canonicalize_tag(tag: &str) -> Result<String> {
+    let canonical: String = tag
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c == '_' {
+                '-'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect();
+
+    if canonical.is_empty()
+        || !canonical
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        bail!(
+            "Invalid tag '{}': use only letters, digits and '-' (e.g. work, side-project)",
+            tag
+        );
+    }
+
+    Ok(canonical)
+}
+
+/// プロジェクトに付けられたタグを取得
+///
+/// タグは実体パスをキーに保存されているため、見つからなければ実体パスに解決して探します。
+pub fn project_tags<'a>(tags: &'a HashMap<PathBuf, Vec<String>>, path: &Path) -> &'a [String] {
+    if tags.is_empty() {
+        return &[];
+    }
+
+    tags.get(path)
+        .or_else(|| tags.get(&resolve_root(path)))
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+/// ディレクトリの識別子（デバイス番号・inode番号と、取得できれば作成日時）
+///
+/// 名前変更や同じファイルシステム内での移動の前後で変わらないため、別名を付けたディレクトリの追跡に使います。
+/// 削除されたディレクトリのinode番号が再利用されても区別できるよう、作成日時も含めます。
+/// Unix以外では `None` です。
+#[cfg(unix)]
+pub fn dir_id(path: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_dir() {
+        return None;
+    }
+
+    let mut id = format!("{}:{}", metadata.dev(), metadata.ino());
+    if let Some(created) = metadata
+        .created()
+        .ok()
+        .and_then(|created| created.duration_since(std::time::UNIX_EPOCH).ok())
+    {
+        id.push_str(&format!(":{}", created.as_nanos()));
+    }
+    Some(id)
+}
+
+/// ディレクトリの識別子（Unix以外では取得しない）
+#[cfg(not(unix))]
+pub fn dir_id(_path: &Path) -> Option<String> {
+    None
 }
 
 /// パスを展開する（~ をホームディレクトリに展開）
@@ -214,6 +1830,9 @@ mod tests {
         assert!(config.root_paths.is_empty());
         assert_eq!(config.max_depth, 4);
         assert!(config.project_markers.contains(&".git".to_string()));
+        assert!(config.show_icons);
+        assert_eq!(config.log_file, None);
+        assert_eq!(config.log_level, "warn");
     }
 
     #[test]
@@ -232,6 +1851,32 @@ mod tests {
         assert_eq!(config.root_paths.len(), 1);
     }
 
+    #[test]
+    fn test_overlapping_roots() {
+        let dir = tempdir().unwrap();
+        let nested = dir.path().join("rust");
+        let sibling = dir.path().join("go");
+        fs::create_dir_all(nested.join("sub")).unwrap();
+        fs::create_dir_all(&sibling).unwrap();
+
+        let mut config = Config::default();
+        config.add_root_path(&nested).unwrap();
+        config.add_root_path(&sibling).unwrap();
+
+        // 親ディレクトリは両方と重なる
+        assert_eq!(config.overlapping_roots(dir.path()).len(), 2);
+
+        // 子ディレクトリは親と重なる
+        config.add_root_path(dir.path()).unwrap();
+        let overlaps = config.overlapping_roots(&nested.join("sub"));
+        assert_eq!(overlaps.len(), 2);
+
+        // 自分自身とは重ならない
+        assert!(!config
+            .overlapping_roots(&sibling)
+            .contains(&&resolve_root(&sibling)));
+    }
+
     #[test]
     fn test_remove_root_path() {
         let dir = tempdir().unwrap();
@@ -245,19 +1890,674 @@ mod tests {
         assert!(config.root_paths.is_empty());
     }
 
+    #[test]
+    fn test_remove_root_path_at() {
+        let mut config = Config {
+            root_paths: vec![
+                PathBuf::from("/a"),
+                PathBuf::from("/b"),
+                PathBuf::from("/c"),
+            ],
+            ..Config::default()
+        };
+
+        assert_eq!(config.remove_root_path_at(2).unwrap(), PathBuf::from("/b"));
+        assert_eq!(
+            config.root_paths,
+            vec![PathBuf::from("/a"), PathBuf::from("/c")]
+        );
+
+        let err = config.remove_root_path_at(3).unwrap_err().to_string();
+        assert!(err.contains("valid: 1-2"));
+        assert!(config.remove_root_path_at(0).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_add_symlinked_root_path() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("target");
+        let link = dir.path().join("link");
+        fs::create_dir(&target).unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let mut config = Config::default();
+
+        // リンクのパスのまま登録される
+        assert!(config.add_root_path(&link).unwrap());
+        assert_eq!(config.root_paths, vec![link.clone()]);
+
+        // 実体が同じパスは重複として扱う
+        assert!(!config.add_root_path(&target).unwrap());
+
+        assert!(config.remove_root_path(&target).unwrap());
+        assert!(config.root_paths.is_empty());
+    }
+
+    #[test]
+    fn test_prune_root_paths() {
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("missing");
+
+        let mut config = Config {
+            root_paths: vec![dir.path().to_path_buf(), missing.clone()],
+            ..Config::default()
+        };
+
+        assert_eq!(config.missing_root_paths(), vec![missing.clone()]);
+        assert_eq!(config.prune_root_paths().unwrap(), vec![missing]);
+        assert_eq!(config.root_paths, vec![dir.path().to_path_buf()]);
+    }
+
+    #[test]
+    fn test_prune_project_paths() {
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("missing");
+
+        let mut config = Config::default();
+        config.add_project_tag(dir.path(), "work").unwrap();
+        config
+            .project_aliases
+            .insert(missing.clone(), "gone".to_string());
+        config
+            .project_tags
+            .insert(missing.clone(), vec!["old".to_string()]);
+
+        assert_eq!(config.stale_project_paths(), vec![missing.clone()]);
+        assert_eq!(config.prune_project_paths(), vec![missing]);
+        assert!(config.project_aliases.is_empty());
+        assert_eq!(config.project_tags.len(), 1);
+    }
+
+    #[test]
+    fn test_from_toml_reports_invalid_field() {
+        let err = Config::from_toml("max_depth = \"four\"\nshow_icons = true\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`max_depth`"));
+        assert!(err.contains("\"four\""));
+        assert!(err.contains("expected usize"));
+
+        // 構文エラーはそのまま返す
+        assert!(Config::from_toml("max_depth = ").is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let dir = tempdir().unwrap();
+        let mut config = Config {
+            max_depth: 0,
+            root_paths: vec![dir.path().to_path_buf(), dir.path().join(".")],
+            project_markers: vec![".git".to_string(), ".git".to_string()],
+            ..Config::default()
+        };
+
+        let warnings = config.normalize();
+        let keys: Vec<&str> = warnings.iter().map(|w| w.key).collect();
+        assert_eq!(keys, vec!["max_depth", "root_paths", "project_markers"]);
+        assert_eq!(config.max_depth, 1);
+        assert_eq!(config.root_paths.len(), 1);
+        assert_eq!(config.project_markers, vec![".git".to_string()]);
+
+        assert!(config.normalize().is_empty());
+    }
+
+    #[test]
+    fn test_read_from_keeps_raw_values() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "version = 1\nmax_depth = 50\n").unwrap();
+
+        let (loaded, warnings) = Config::load_from(&path).unwrap();
+        assert_eq!(loaded.max_depth, *MAX_DEPTH_RANGE.end());
+        assert!(warnings.iter().any(|w| w.key == "max_depth"));
+
+        // 書き換えて保存するコマンドは正規化前の値を使うため、範囲外の値も書き換えない
+        let (mut raw, warnings) = Config::read_from(&path).unwrap();
+        assert_eq!(raw.max_depth, 50);
+        assert!(warnings.is_empty());
+        raw.set_editor("vim");
+        raw.save_to(&path).unwrap();
+        assert_eq!(Config::read_from(&path).unwrap().0.max_depth, 50);
+    }
+
+    #[test]
+    fn test_normalize_warns_unknown_placeholders() {
+        assert_eq!(
+            unknown_placeholders("{name} {nmae} {path} {nmae} {x"),
+            vec!["nmae"]
+        );
+
+        let mut config = Config {
+            display_format: Some("{parent}/{name} {count}".to_string()),
+            ..Config::default()
+        };
+        let warnings = config.normalize();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].key, "display_format");
+        assert!(warnings[0]
+            .message
+            .starts_with("Unknown placeholder {count} (available: {name}, {parent},"));
+
+        config.display_format = Some("{short_path} {open_count}".to_string());
+        assert!(config.normalize().is_empty());
+    }
+
+    #[test]
+    fn test_project_aliases() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::default();
+
+        let key = config.set_project_alias(dir.path(), "  tool ").unwrap();
+        assert_eq!(key, fs::canonicalize(dir.path()).unwrap());
+        assert_eq!(
+            config.project_aliases.get(&key).map(String::as_str),
+            Some("tool")
+        );
+
+        assert!(config.set_project_alias(dir.path(), " ").is_err());
+        assert!(config
+            .set_project_alias(&dir.path().join("missing"), "x")
+            .is_err());
+
+        assert!(config.remove_project_alias(dir.path()).unwrap());
+        assert!(!config.remove_project_alias(dir.path()).unwrap());
+        assert!(config.project_alias_ids.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_renamed_project_paths() {
+        let dir = tempdir().unwrap();
+        let old = dir.path().join("old-name");
+        let other = dir.path().join("other");
+        fs::create_dir(&old).unwrap();
+        fs::create_dir(&other).unwrap();
+
+        let mut config = Config::default();
+        let old_key = config.set_project_alias(&old, "tool").unwrap();
+        config.add_project_tag(&old, "work").unwrap();
+        assert!(config.project_alias_ids.contains_key(&old_key));
+        assert!(config.moved_alias_paths().is_empty());
+
+        let new = dir.path().join("new-name");
+        fs::rename(&old, &new).unwrap();
+        let new_key = fs::canonicalize(&new).unwrap();
+
+        let renamed = config.renamed_project_paths([other.as_path(), new.as_path()]);
+        assert_eq!(renamed, vec![(old_key.clone(), new_key.clone())]);
+
+        // 別名・識別子・タグをまとめて付け替え、古いパスは残さない
+        config.move_project_path(&old_key, &new_key);
+        assert_eq!(
+            config.project_aliases.get(&new_key).map(String::as_str),
+            Some("tool")
+        );
+        assert!(config.project_alias_ids.contains_key(&new_key));
+        assert_eq!(project_tags(&config.project_tags, &new_key), ["work"]);
+        assert!(config.stale_project_paths().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dir_id_ignores_recreated_directory() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app");
+        fs::create_dir(&path).unwrap();
+        let id = dir_id(&path).unwrap();
+        assert_eq!(dir_id(&path), Some(id.clone()));
+
+        fs::remove_dir(&path).unwrap();
+        fs::create_dir(&path).unwrap();
+        assert!(dir_id(&dir.path().join("missing")).is_none());
+        // 作成日時を含むため、inode番号が再利用されても別のディレクトリとして扱う
+        if fs::metadata(&path).unwrap().created().is_ok() {
+            assert_ne!(dir_id(&path), Some(id));
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_tag() {
+        assert_eq!(canonicalize_tag(" Work ").unwrap(), "work");
+        assert_eq!(canonicalize_tag("Side Project").unwrap(), "side-project");
+        assert_eq!(canonicalize_tag("client_a2").unwrap(), "client-a2");
+        assert!(canonicalize_tag("").is_err());
+        assert!(canonicalize_tag("c++").is_err());
+        assert!(canonicalize_tag("仕事").is_err());
+    }
+
+    #[test]
+    fn test_project_tags() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::default();
+
+        assert!(config.add_project_tag(dir.path(), "Work").unwrap());
+        assert!(config.add_project_tag(dir.path(), "active").unwrap());
+        assert!(!config.add_project_tag(dir.path(), "work").unwrap());
+        assert!(config
+            .add_project_tag(&dir.path().join("missing"), "work")
+            .is_err());
+        assert_eq!(
+            project_tags(&config.project_tags, dir.path()),
+            ["active", "work"]
+        );
+
+        assert!(config.remove_project_tag(dir.path(), "WORK").unwrap());
+        assert!(!config.remove_project_tag(dir.path(), "work").unwrap());
+        assert!(config.remove_project_tag(dir.path(), "active").unwrap());
+        assert!(config.project_tags.is_empty());
+    }
+
+    #[test]
+    fn test_exclude_patterns() {
+        let mut config = Config::default();
+
+        assert!(config.add_exclude_pattern("**/dist/**").unwrap());
+        assert!(!config.add_exclude_pattern("**/dist/**").unwrap());
+        assert!(config.add_exclude_pattern("[invalid").is_err());
+
+        assert!(config.remove_exclude_pattern("**/dist/**"));
+        assert!(!config.remove_exclude_pattern("**/dist/**"));
+    }
+
     #[test]
     fn test_get_editor_priority() {
         let mut config = Config::default();
 
         // デフォルト
-        assert_eq!(config.get_editor(None), "code");
+        assert_eq!(config.get_editor(None, None), vec!["code"]);
+
+        // プロジェクト種別ごとの設定
+        config.set_type_editor(ProjectType::Rust, "rustrover");
+        assert_eq!(
+            config.get_editor(None, Some(ProjectType::Rust)),
+            vec!["rustrover"]
+        );
+        assert_eq!(config.get_editor(None, Some(ProjectType::Go)), vec!["code"]);
 
         // 設定ファイル
         config.set_editor("vim");
-        assert_eq!(config.get_editor(None), "vim");
+        assert_eq!(
+            config.get_editor(None, Some(ProjectType::Rust)),
+            vec!["vim"]
+        );
 
         // CLIオプションが最優先
-        assert_eq!(config.get_editor(Some("nvim")), "nvim");
+        assert_eq!(config.get_editor(Some("nvim"), None), vec!["nvim"]);
+    }
+
+    #[test]
+    fn test_editor_setting_parse() {
+        let config: Config = toml::from_str(r#"editor = "vim""#).unwrap();
+        assert_eq!(config.get_editor(None, None), vec!["vim"]);
+
+        let config: Config = toml::from_str(r#"editor = ["cursor", "code", "vim"]"#).unwrap();
+        assert_eq!(config.get_editor(None, None), vec!["cursor", "code", "vim"]);
+    }
+
+    #[test]
+    fn test_selector_parse() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.selector, Selector::Internal);
+
+        let config: Config = toml::from_str(r#"selector = "skim""#).unwrap();
+        assert_eq!(config.selector, Selector::Skim);
+
+        let config: Config = toml::from_str(r#"selector = "internal""#).unwrap();
+        assert_eq!(config.selector, Selector::Internal);
+
+        // 以前の名前でも読み込める
+        let config: Config = toml::from_str(r#"selector = "dialoguer""#).unwrap();
+        assert_eq!(config.selector, Selector::Internal);
+        assert_eq!("dialoguer".parse::<Selector>(), Ok(Selector::Internal));
+
+        let config: Config = toml::from_str(r#"selection_backend = "fzf""#).unwrap();
+        assert_eq!(config.selector, Selector::Fzf);
+
+        assert_eq!("FZF".parse::<Selector>(), Ok(Selector::Fzf));
+        assert!("peco".parse::<Selector>().is_err());
+    }
+
+    #[test]
+    fn test_prompt_and_theme_parse() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.prompt, "Select a project");
+        assert_eq!(config.theme, SelectorTheme::Colorful);
+
+        let config: Config =
+            toml::from_str("prompt = \"Projects ({count} found)\"\ntheme = \"simple\"").unwrap();
+        assert_eq!(config.prompt, "Projects ({count} found)");
+        assert_eq!(config.theme, SelectorTheme::Simple);
+    }
+
+    #[test]
+    fn test_launch_target_parse() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.default_launch_target, LaunchTarget::Editor);
+
+        let config: Config = toml::from_str(r#"default_launch_target = "shell""#).unwrap();
+        assert_eq!(config.default_launch_target, LaunchTarget::Shell);
+
+        assert!(Config::parse(r#"default_launch_target = "browser""#, ConfigFormat::Toml).is_err());
+    }
+
+    #[test]
+    fn test_display_format_parse() {
+        let config: Config = toml::from_str(r#"display_format = "{parent}/{name}""#).unwrap();
+        assert_eq!(config.display_format.as_deref(), Some("{parent}/{name}"));
+    }
+
+    #[test]
+    fn test_group_mode_parse() {
+        let config: Config = toml::from_str(r#"group_by = "root""#).unwrap();
+        assert_eq!(config.group_by, Some(GroupMode::Root));
+
+        assert_eq!("Type".parse(), Ok(GroupMode::Type));
+        assert_eq!("marker".parse(), Ok(GroupMode::Marker));
+        assert_eq!("none".parse(), Ok(GroupMode::None));
+        assert!("size".parse::<GroupMode>().is_err());
+        assert_eq!(GroupMode::Marker.as_str().parse(), Ok(GroupMode::Marker));
+    }
+
+    #[test]
+    fn test_config_format() {
+        assert_eq!("json".parse::<ConfigFormat>(), Ok(ConfigFormat::Json));
+        assert_eq!("TOML".parse::<ConfigFormat>(), Ok(ConfigFormat::Toml));
+        assert!("yaml".parse::<ConfigFormat>().is_err());
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("dotfiles/quick-proj.json")),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.toml")),
+            ConfigFormat::Toml
+        );
+    }
+
+    #[test]
+    fn test_export_and_parse_roundtrip() {
+        let mut config = Config::default();
+        config.set_editor("vim");
+        config.root_paths.push(PathBuf::from("/srv/projects"));
+
+        for format in [ConfigFormat::Toml, ConfigFormat::Json] {
+            let content = config.export(format).unwrap();
+            let parsed = Config::parse(&content, format).unwrap();
+            assert_eq!(parsed.root_paths, config.root_paths);
+            assert_eq!(parsed.editor, config.editor);
+        }
+    }
+
+    #[test]
+    fn test_merge_unions_root_paths() {
+        let dir = tempdir().unwrap();
+        let (local, shared, remote) = (
+            dir.path().join("local"),
+            dir.path().join("shared"),
+            dir.path().join("remote"),
+        );
+        for path in [&local, &shared, &remote] {
+            fs::create_dir_all(path).unwrap();
+        }
+        let resolve = |path: &Path| fs::canonicalize(path).unwrap();
+
+        let config = Config {
+            root_paths: vec![local.clone(), shared.clone()],
+            project_markers: vec![".git".to_string()],
+            max_depth: 3,
+            show_icons: false,
+            ..Default::default()
+        };
+
+        let mut imported = Config {
+            root_paths: vec![shared.clone(), remote.clone(), dir.path().join("missing")],
+            project_markers: vec!["Cargo.toml".to_string(), ".git".to_string()],
+            max_depth: 8,
+            ..Default::default()
+        };
+        imported.set_editor("nvim");
+
+        let imported = ImportedConfig {
+            config: imported,
+            max_depth: Some(8),
+        };
+
+        let mut merged = config.clone();
+        let report = merged.merge(imported.clone(), ImportMode::Merge);
+        assert_eq!(report.added, 1);
+        assert_eq!(report.skipped, vec![dir.path().join("missing")]);
+        assert_eq!(
+            merged.root_paths,
+            vec![local, shared.clone(), resolve(&remote)]
+        );
+        assert_eq!(
+            merged.project_markers,
+            vec![".git".to_string(), "Cargo.toml".to_string()]
+        );
+        assert_eq!(merged.max_depth, 8);
+        assert_eq!(merged.get_editor(None, None), vec!["nvim"]);
+        assert!(!merged.show_icons);
+
+        // 省略した max_depth はデフォルト値で上書きしない
+        let toml = format!("root_paths = [{:?}]\n", remote);
+        let partial = ImportedConfig::parse(&toml, ConfigFormat::Toml).unwrap();
+        assert_eq!(partial.max_depth, None);
+        let mut merged = config.clone();
+        merged.merge(partial, ImportMode::Merge);
+        assert_eq!(merged.max_depth, 3);
+        let json = r#"{"max_depth": 6}"#;
+        let explicit = ImportedConfig::parse(json, ConfigFormat::Json).unwrap();
+        merged.merge(explicit, ImportMode::Merge);
+        assert_eq!(merged.max_depth, 6);
+
+        let mut merged = config.clone();
+        merged.merge(imported.clone(), ImportMode::Overwrite);
+        assert_eq!(merged.root_paths.len(), 3);
+        assert!(merged.show_icons);
+
+        let mut merged = config;
+        merged.merge(imported, ImportMode::Replace);
+        assert_eq!(merged.root_paths, vec![resolve(&shared), resolve(&remote)]);
+        assert_eq!(
+            merged.project_markers,
+            vec!["Cargo.toml".to_string(), ".git".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_load_from_custom_path() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        fs::write(&path, "max_depth = 7\nroot_paths = [\"/srv/ci\"]\n").unwrap();
+
+        // どのパスを使うかは test_select_config_override で確認する
+        let (loaded, _) = Config::load_from(&path).unwrap();
+        assert_eq!(loaded.max_depth, 7);
+        assert_eq!(loaded.root_paths, vec![PathBuf::from("/srv/ci")]);
+    }
+
+    #[test]
+    fn test_select_data_dir() {
+        let env = PathBuf::from("/tmp/qp-data");
+        let configured = PathBuf::from("~/.local/share/qp");
+        let default_dir = PathBuf::from("/home/me/.local/share/quick-proj");
+
+        assert_eq!(
+            select_data_dir(
+                Some(env.clone()),
+                Some(configured.clone()),
+                Some(default_dir.clone())
+            ),
+            Some(env)
+        );
+        assert_eq!(
+            select_data_dir(None, Some(configured.clone()), Some(default_dir.clone())),
+            Some(configured)
+        );
+        assert_eq!(
+            select_data_dir(None, None, Some(default_dir.clone())),
+            Some(default_dir)
+        );
+        assert_eq!(select_data_dir(None, None, None), None);
+    }
+
+    #[test]
+    fn test_select_config_override() {
+        let cli = PathBuf::from("./repo.toml");
+        let env = PathBuf::from("/etc/quick-proj.toml");
+
+        assert_eq!(
+            select_config_override(Some(cli.clone()), Some(env.clone())),
+            Some((cli, "--config"))
+        );
+        assert_eq!(
+            select_config_override(None, Some(env.clone())),
+            Some((env, CONFIG_ENV))
+        );
+        assert_eq!(select_config_override(None, None), None);
+    }
+
+    #[test]
+    fn test_profile_file_name() {
+        assert_eq!(profile_file_name("default"), "config.toml");
+        assert_eq!(profile_file_name("work"), "config-work.toml");
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("work").is_ok());
+        assert!(validate_profile_name("my_profile-2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../etc").is_err());
+    }
+
+    #[test]
+    fn test_load_and_save_to_path() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("config-work.toml");
+
+        let mut config = Config::default();
+        config.set_editor("vim");
+        config.save_to(&path).unwrap();
+
+        let (loaded, _) = Config::load_from(&path).unwrap();
+        assert_eq!(loaded.get_editor(None, None), vec!["vim"]);
+    }
+
+    /// フィクスチャを一時ディレクトリに書き出して読み込む
+    fn load_fixture(content: &str) -> (Config, Vec<ConfigWarning>, PathBuf, tempfile::TempDir) {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, content).unwrap();
+        let (config, warnings) = Config::load_from(&path).unwrap();
+        (config, warnings, path, dir)
+    }
+
+    fn has_version_warning(warnings: &[ConfigWarning]) -> bool {
+        warnings.iter().any(|w| w.key == "version")
+    }
+
+    #[test]
+    fn test_migrate_v0() {
+        let (config, warnings, path, _dir) =
+            load_fixture(include_str!("../tests/fixtures/config/v0.toml"));
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.root_paths, vec![PathBuf::from("/srv/projects")]);
+        assert_eq!(config.get_editor(None, None), vec!["code"]);
+        assert_eq!(config.max_depth, 3);
+        assert_eq!(
+            config.project_markers,
+            vec!["Cargo.toml", "package.json", ".git"]
+        );
+        assert_eq!(config.selector, Selector::Internal);
+        assert!(config.show_preview);
+
+        // 別名で読める変更だけなので、警告もファイルの書き換えもしない
+        assert!(!has_version_warning(&warnings));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            include_str!("../tests/fixtures/config/v0.toml")
+        );
+    }
+
+    #[test]
+    fn test_migrate_v0_selection_backend() {
+        let (config, warnings, path, _dir) = load_fixture(include_str!(
+            "../tests/fixtures/config/v0-selection-backend.toml"
+        ));
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.selector, Selector::Internal);
+        assert_eq!(config.get_editor(None, None), vec!["nvim", "vim"]);
+        assert!(!config.show_preview);
+        assert!(!has_version_warning(&warnings));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            include_str!("../tests/fixtures/config/v0-selection-backend.toml")
+        );
+    }
+
+    #[test]
+    fn test_migrate_file_keeps_comments() {
+        let original = include_str!("../tests/fixtures/config/v0-selection-backend.toml");
+        let (_, _, path, _dir) = load_fixture(original);
+
+        assert_eq!(Config::migrate_file(&path).unwrap(), Some(0));
+        let migrated = fs::read_to_string(&path).unwrap();
+        assert!(migrated.starts_with("# selection_backend で選択UIを指定していた形式"));
+        assert!(!migrated.contains("selection_backend ="));
+        assert!(migrated.contains("selector = \"internal\""));
+        assert!(migrated.contains(&format!("version = {}", CONFIG_VERSION)));
+        assert!(migrated.contains("show_preview = false"));
+        assert!(!migrated.contains("max_depth"));
+
+        let backup = path.with_file_name("config.toml.bak");
+        assert_eq!(fs::read_to_string(backup).unwrap(), original);
+
+        // 移行済みのファイルはそのまま
+        assert_eq!(Config::migrate_file(&path).unwrap(), None);
+        assert_eq!(fs::read_to_string(&path).unwrap(), migrated);
+    }
+
+    #[test]
+    fn test_load_current_version_is_not_rewritten() {
+        let content = include_str!("../tests/fixtures/config/v1.toml");
+        let (config, warnings, path, _dir) = load_fixture(content);
+        assert_eq!(config.version, 1);
+        assert_eq!(config.selector, Selector::Fzf);
+        assert!(!has_version_warning(&warnings));
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_load_newer_version_warns() {
+        let content = include_str!("../tests/fixtures/config/newer.toml");
+        let (config, warnings, path, _dir) = load_fixture(content);
+        assert_eq!(config.version, 99);
+        assert_eq!(config.get_editor(None, None), vec!["code"]);
+        let warning = warnings.iter().find(|w| w.key == "version").unwrap();
+        assert!(warning.message.contains("newer quick-proj"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[test]
+    fn test_migrate_document() {
+        let mut document: toml_edit::DocumentMut = "selection_backend = \"skim\"".parse().unwrap();
+        assert_eq!(
+            migrate(&mut document),
+            Some(Migration {
+                from: 0,
+                rewrite: false
+            })
+        );
+        assert_eq!(
+            document.get("selector").and_then(|v| v.as_str()),
+            Some("skim")
+        );
+        assert_eq!(migrate(&mut document), None);
+
+        let mut document: toml_edit::DocumentMut = "version = \"one\"".parse().unwrap();
+        assert_eq!(migrate(&mut document), None);
+        assert!(Config::from_table(document.to_string().parse().unwrap()).is_err());
     }
 
     #[test]
@@ -0,0 +1,508 @@
+//! 設定診断モジュール
+//!
+//! 設定ファイルやルートパス、エディタ、外部コマンドや実行環境の状態をチェックし、
+//! 問題点を重要度付きの診断結果として返します。
+
+use crate::config::{self, is_symlink, Config, MAX_DEPTH_RANGE};
+use crate::launcher::{self, Launcher};
+use crate::scanner::Scanner;
+use console::Term;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// データディレクトリに必要な空き容量（これを下回るとキャッシュや履歴を保存できない恐れがある）
+#[cfg(unix)]
+const MIN_FREE_SPACE: u64 = 50 * 1024 * 1024;
+
+/// 診断結果の重要度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// 問題なし
+    Ok,
+    /// 動作はするが確認が必要
+    Warning,
+    /// 正常に動作しない
+    Error,
+}
+
+/// 個々のチェック結果
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// 重要度
+    pub severity: Severity,
+    /// チェック結果のメッセージ
+    pub message: String,
+    /// 対処方法の提案
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    fn ok(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Ok,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    fn warning(message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            suggestion: Some(suggestion.into()),
+        }
+    }
+
+    fn error(message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            suggestion: Some(suggestion.into()),
+        }
+    }
+}
+
+/// すべてのチェックを実行
+pub fn run_checks() -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    // 設定ファイルが読めない場合はデフォルト設定で残りのチェックを続ける
+    // 範囲外の値も報告できるよう、正規化する前の値をチェックしてから正規化する
+    let mut config = match Config::load_raw() {
+        Ok(config) => {
+            diagnostics.push(Diagnostic::ok("Config file parses correctly"));
+            config
+        }
+        Err(e) => {
+            diagnostics.push(Diagnostic::error(
+                format!("Config file is invalid: {:#}", e),
+                "Fix the file shown by `quick-proj config`",
+            ));
+            Config::default()
+        }
+    };
+    diagnostics.push(check_max_depth(&config));
+    config.normalize();
+
+    diagnostics.extend(check_root_paths(&config));
+    diagnostics.push(check_editor(&config));
+    diagnostics.push(check_git());
+    #[cfg(unix)]
+    diagnostics.push(check_disk_space());
+    diagnostics.push(check_colors());
+    diagnostics.extend(check_symlink_loops(&config));
+
+    diagnostics
+}
+
+/// ルートパスの存在・読み取り可否と、検出されるプロジェクト数をチェック
+pub fn check_root_paths(config: &Config) -> Vec<Diagnostic> {
+    if config.root_paths.is_empty() {
+        return vec![Diagnostic::warning(
+            "No root paths configured",
+            "Add one with `quick-proj add <PATH>`",
+        )];
+    }
+
+    let scanner = Scanner::from_config(config);
+
+    config
+        .root_paths
+        .iter()
+        .map(|root| {
+            if !root.exists() {
+                return Diagnostic::warning(
+                    format!("Root path does not exist: {}", root.display()),
+                    format!("Remove it with `quick-proj remove {}`", root.display()),
+                );
+            }
+
+            if let Err(e) = fs::read_dir(root) {
+                return Diagnostic::error(
+                    format!("Root path is not readable: {} ({})", root.display(), e),
+                    "Check the directory permissions",
+                );
+            }
+
+            match scanner.scan(&[PathBuf::from(root)]) {
+                Ok(projects) => {
+                    Diagnostic::ok(format!("{} ({} projects)", root.display(), projects.len()))
+                }
+                Err(e) => Diagnostic::error(
+                    format!("Failed to scan {}: {:#}", root.display(), e),
+                    "Check the directory permissions",
+                ),
+            }
+        })
+        .collect()
+}
+
+/// 設定されたエディタがPATH上にあるかチェック
+pub fn check_editor(config: &Config) -> Diagnostic {
+    let editors = config.get_editor(None, None);
+
+    let launcher = Launcher::with_fallbacks(editors.clone())
+        .with_editor_aliases(config.editor_aliases.clone());
+    match launcher.select_editor() {
+        Ok(editor) if launcher::is_command_available(&editor) => {
+            Diagnostic::ok(format!("Editor '{}' found in PATH", editor))
+        }
+        _ => Diagnostic::error(
+            format!("Editor not found in PATH: {}", editors.join(", ")),
+            "Install it or change it with `quick-proj set-editor <EDITOR>`",
+        ),
+    }
+}
+
+/// git がPATH上にあるかチェック（gitの状態表示やリモートを開く機能に必要）
+pub fn check_git() -> Diagnostic {
+    if launcher::is_command_available("git") {
+        Diagnostic::ok("git found in PATH")
+    } else {
+        Diagnostic::warning(
+            "git not found in PATH",
+            "Install git to enable git status and remote features",
+        )
+    }
+}
+
+/// データディレクトリ（状態・キャッシュ・履歴の保存先）の空き容量をチェック
+#[cfg(unix)]
+pub fn check_disk_space() -> Diagnostic {
+    let dir = match config::data_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            return Diagnostic::error(
+                format!("Data directory is unavailable: {:#}", e),
+                format!("Set it with `data_dir` or {}", config::DATA_DIR_ENV),
+            )
+        }
+    };
+
+    match free_space(&dir) {
+        Some(available) if available >= MIN_FREE_SPACE => Diagnostic::ok(format!(
+            "{} MiB free in {}",
+            available / 1024 / 1024,
+            dir.display()
+        )),
+        Some(available) => Diagnostic::error(
+            format!(
+                "Only {} MiB free in {}",
+                available / 1024 / 1024,
+                dir.display()
+            ),
+            "Free up disk space, or move the data with `data_dir`",
+        ),
+        None => Diagnostic::warning(
+            format!("Could not determine free disk space in {}", dir.display()),
+            "Make sure the directory is accessible",
+        ),
+    }
+}
+
+/// パスのあるファイルシステムの空き容量（バイト）
+///
+/// ディレクトリはまだ作成されていないことがあるため、存在する最も近い祖先で調べます。
+#[cfg(unix)]
+fn free_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|dir| dir.exists())?;
+    let stat = rustix::fs::statvfs(existing).ok()?;
+    Some(stat.f_bavail.saturating_mul(stat.f_frsize))
+}
+
+/// 選択UIを表示する端末（標準エラー出力）が色を表示できるかチェック
+pub fn check_colors() -> Diagnostic {
+    if Term::stderr().features().colors_supported() {
+        Diagnostic::ok("Terminal supports colors")
+    } else {
+        Diagnostic::warning(
+            "Terminal does not support colors",
+            "The selector still works, but without highlighting",
+        )
+    }
+}
+
+/// ルートパス以下に、自身の祖先を指す（循環する）シンボリックリンクがないかチェック
+///
+/// スキャンと同じく `max_depth` までを調べ、`exclude_dirs` のディレクトリには入りません。
+pub fn check_symlink_loops(config: &Config) -> Vec<Diagnostic> {
+    let mut loops = Vec::new();
+    for root in config.root_paths.iter().filter(|root| root.is_dir()) {
+        find_symlink_loops(config, root, 0, &mut loops);
+    }
+
+    if loops.is_empty() {
+        return vec![Diagnostic::ok("No circular symlinks in root paths")];
+    }
+
+    loops
+        .into_iter()
+        .map(|link| {
+            Diagnostic::warning(
+                format!("Circular symlink: {}", link.display()),
+                "Scans skip it, but remove the link if it is not intended",
+            )
+        })
+        .collect()
+}
+
+/// ディレクトリ以下の循環するシンボリックリンクを集める
+fn find_symlink_loops(config: &Config, dir: &Path, depth: usize, loops: &mut Vec<PathBuf>) {
+    if depth >= config.max_depth {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if is_symlink(&path) {
+            if is_circular_symlink(&path) {
+                loops.push(path);
+            }
+            continue;
+        }
+
+        let is_excluded = config
+            .exclude_dirs
+            .iter()
+            .any(|name| entry.file_name().to_string_lossy() == name.as_str());
+        if path.is_dir() && !is_excluded {
+            find_symlink_loops(config, &path, depth + 1, loops);
+        }
+    }
+}
+
+/// シンボリックリンクが自身を含むディレクトリ（祖先）を指しているか
+fn is_circular_symlink(link: &Path) -> bool {
+    let (Ok(target), Some(parent)) = (fs::canonicalize(link), link.parent()) else {
+        return false;
+    };
+
+    fs::canonicalize(parent).is_ok_and(|parent| parent.starts_with(&target))
+}
+
+/// 設定の妥当性を検証（`config validate` 用）
+///
+/// ファイルシステムを走査せずに確認できる項目のみをチェックし、
+/// 問題はすべてエラーとして返します。
+pub fn validate_config(config: &Config) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = config
+        .root_paths
+        .iter()
+        .map(|root| {
+            if root.is_dir() {
+                Diagnostic::ok(format!("Root path exists: {}", root.display()))
+            } else if root.exists() {
+                Diagnostic::error(
+                    format!("Root path is not a directory: {}", root.display()),
+                    format!("Remove it with `quick-proj remove {}`", root.display()),
+                )
+            } else {
+                Diagnostic::error(
+                    format!("Root path does not exist: {}", root.display()),
+                    "Remove missing paths with `quick-proj prune`",
+                )
+            }
+        })
+        .collect();
+
+    diagnostics.push(check_editor(config));
+
+    if config.project_markers.is_empty() {
+        diagnostics.push(Diagnostic::error(
+            "project_markers is empty",
+            "Add at least one marker such as \".git\"",
+        ));
+    } else {
+        diagnostics.push(Diagnostic::ok(format!(
+            "{} project markers configured",
+            config.project_markers.len()
+        )));
+    }
+
+    diagnostics.push(check_max_depth(config));
+
+    // exclude_dirs はディレクトリ名と照合するため、区切り文字を含むと一致しない
+    let invalid_dirs: Vec<&str> = config
+        .exclude_dirs
+        .iter()
+        .filter(|dir| dir.contains('/') || dir.contains('\\'))
+        .map(String::as_str)
+        .collect();
+    if invalid_dirs.is_empty() {
+        diagnostics.push(Diagnostic::ok("exclude_dirs contains only directory names"));
+    } else {
+        diagnostics.push(Diagnostic::error(
+            format!(
+                "exclude_dirs entries contain path separators: {}",
+                invalid_dirs.join(", ")
+            ),
+            "Use plain directory names, or glob patterns via `quick-proj exclude add`",
+        ));
+    }
+
+    diagnostics
+}
+
+/// `max_depth` が妥当な範囲にあるかチェック
+///
+/// 読み込み時には範囲内に収められるため、正規化する前の設定を渡します。
+pub fn check_max_depth(config: &Config) -> Diagnostic {
+    if MAX_DEPTH_RANGE.contains(&config.max_depth) {
+        Diagnostic::ok(format!("max_depth is {}", config.max_depth))
+    } else {
+        Diagnostic::error(
+            format!("max_depth {} is out of range", config.max_depth),
+            format!(
+                "Use a value between {} and {}",
+                MAX_DEPTH_RANGE.start(),
+                MAX_DEPTH_RANGE.end()
+            ),
+        )
+    }
+}
+
+/// エラーが含まれるかどうか
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_root_paths() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("app").join(".git")).unwrap();
+
+        let config = Config {
+            root_paths: vec![dir.path().to_path_buf(), dir.path().join("missing")],
+            ..Config::default()
+        };
+
+        let diagnostics = check_root_paths(&config);
+        assert_eq!(diagnostics.len(), 2);
+
+        assert_eq!(diagnostics[0].severity, Severity::Ok);
+        assert!(diagnostics[0].message.contains("1 projects"));
+
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+        assert!(diagnostics[1]
+            .suggestion
+            .as_deref()
+            .unwrap()
+            .contains("quick-proj remove"));
+    }
+
+    #[test]
+    fn test_check_editor_missing() {
+        let mut config = Config::default();
+        config.set_editor("quick-proj-missing-editor");
+
+        let diagnostic = check_editor(&config);
+        assert_eq!(diagnostic.severity, Severity::Error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_editor_reports_selected_fallback() {
+        let mut config = Config::default();
+        config.editor = Some(config::EditorSetting::Chain(vec![
+            "quick-proj-missing-editor".to_string(),
+            "sh".to_string(),
+        ]));
+
+        // 先頭が見つからなくても、実際に使われるエディタを報告する
+        let diagnostic = check_editor(&config);
+        assert_eq!(diagnostic.severity, Severity::Ok);
+        assert_eq!(diagnostic.message, "Editor 'sh' found in PATH");
+        assert!(validate_config(&config)
+            .iter()
+            .any(|d| d.message == "Editor 'sh' found in PATH"));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+
+        let mut config = Config {
+            root_paths: vec![dir.path().to_path_buf(), file, dir.path().join("missing")],
+            max_depth: 0,
+            project_markers: vec![],
+            exclude_dirs: vec!["node_modules".to_string(), "build/out".to_string()],
+            ..Config::default()
+        };
+        config.set_editor("quick-proj-missing-editor");
+
+        let diagnostics = validate_config(&config);
+        let errors: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .map(|d| d.message.as_str())
+            .collect();
+
+        assert_eq!(diagnostics[0].severity, Severity::Ok);
+        assert_eq!(errors.len(), 6);
+        assert!(errors.iter().any(|m| m.contains("not a directory")));
+        assert!(errors.iter().any(|m| m.contains("does not exist")));
+        assert!(errors
+            .iter()
+            .any(|m| m.contains("quick-proj-missing-editor")));
+        assert!(errors.iter().any(|m| m.contains("project_markers")));
+        assert!(errors.iter().any(|m| m.contains("max_depth 0")));
+        assert!(errors.iter().any(|m| m.contains("build/out")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_free_space() {
+        let dir = tempdir().unwrap();
+        let available = free_space(dir.path()).unwrap();
+
+        // まだ作成されていないディレクトリは、存在する祖先と同じファイルシステムで調べる
+        let missing = free_space(&dir.path().join("not").join("yet")).unwrap();
+        assert!(available > 0);
+        assert!(missing > 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_symlink_loops() {
+        use std::os::unix::fs::symlink;
+
+        let root = tempdir().unwrap();
+        let other = tempdir().unwrap();
+        fs::create_dir_all(root.path().join("group")).unwrap();
+        symlink(root.path(), root.path().join("group").join("loop")).unwrap();
+        symlink(other.path(), root.path().join("group").join("other")).unwrap();
+
+        let config = Config {
+            root_paths: vec![root.path().to_path_buf()],
+            ..Config::default()
+        };
+
+        let diagnostics = check_symlink_loops(&config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert!(diagnostics[0].message.ends_with("loop"));
+
+        fs::remove_file(root.path().join("group").join("loop")).unwrap();
+        let diagnostics = check_symlink_loops(&config);
+        assert_eq!(diagnostics[0].severity, Severity::Ok);
+    }
+
+    #[test]
+    fn test_has_errors() {
+        assert!(!has_errors(&[Diagnostic::ok("fine")]));
+        assert!(has_errors(&[
+            Diagnostic::ok("fine"),
+            Diagnostic::error("broken", "fix it"),
+        ]));
+    }
+}
@@ -0,0 +1,148 @@
+//! 起動履歴モジュール
+//!
+//! プロジェクトを開いた回数と最後に開いた日時を、
+//! データディレクトリ（`config::data_path`）にTOMLで保存します。
+
+use crate::config::data_path;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 履歴ファイル名
+const HISTORY_FILE_NAME: &str = "history.toml";
+
+/// `history show` で表示する件数のデフォルト
+pub const DEFAULT_TOP_LIMIT: usize = 20;
+
+/// プロジェクトごとの起動履歴
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectHistory {
+    /// プロジェクトのパス
+    pub path: PathBuf,
+    /// 開いた回数
+    pub open_count: u64,
+    /// 最後に開いた日時（Unix時間の秒）
+    pub last_opened: i64,
+}
+
+/// 起動履歴
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    /// プロジェクトごとの履歴
+    #[serde(default)]
+    pub projects: Vec<ProjectHistory>,
+}
+
+impl History {
+    /// 履歴ファイルを読み込む
+    ///
+    /// ファイルが存在しない場合は空の履歴を返します。
+    pub fn load() -> Result<Self> {
+        let history_path = Self::history_path()?;
+
+        if !history_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&history_path)
+            .with_context(|| format!("Failed to read history file: {}", history_path.display()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse history file: {}", history_path.display()))
+    }
+
+    /// 履歴ファイルに保存する
+    pub fn save(&self) -> Result<()> {
+        let history_path = Self::history_path()?;
+
+        if let Some(parent) = history_path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create data directory: {}", parent.display())
+            })?;
+        }
+
+        let content = toml::to_string_pretty(self).context("Failed to serialize history")?;
+
+        fs::write(&history_path, content)
+            .with_context(|| format!("Failed to write history file: {}", history_path.display()))?;
+
+        Ok(())
+    }
+
+    /// 履歴ファイルのパスを取得
+    pub fn history_path() -> Result<PathBuf> {
+        data_path(HISTORY_FILE_NAME)
+    }
+
+    /// 開いた回数の多い順（同じ回数なら最近開いた順）に最大 `limit` 件を取得
+    pub fn top(&self, limit: usize) -> Vec<&ProjectHistory> {
+        let mut entries: Vec<&ProjectHistory> = self.projects.iter().collect();
+        entries.sort_by(|a, b| {
+            b.open_count
+                .cmp(&a.open_count)
+                .then(b.last_opened.cmp(&a.last_opened))
+        });
+        entries.truncate(limit);
+        entries
+    }
+
+    /// プロジェクトを開いたことを記録
+    pub fn record(&mut self, path: &Path, now: i64) {
+        match self.projects.iter_mut().find(|entry| entry.path == path) {
+            Some(entry) => {
+                entry.open_count += 1;
+                entry.last_opened = now;
+            }
+            None => self.projects.push(ProjectHistory {
+                path: path.to_path_buf(),
+                open_count: 1,
+                last_opened: now,
+            }),
+        }
+    }
+}
+
+/// 現在時刻（Unix時間の秒）
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_record() {
+        let mut history = History::default();
+        history.record(Path::new("/src/app"), 100);
+        history.record(Path::new("/src/lib"), 150);
+        history.record(Path::new("/src/app"), 200);
+
+        assert_eq!(
+            history.projects[0],
+            ProjectHistory {
+                path: PathBuf::from("/src/app"),
+                open_count: 2,
+                last_opened: 200,
+            }
+        );
+        assert_eq!(history.projects[1].open_count, 1);
+
+        history.record(Path::new("/src/tool"), 300);
+        let top: Vec<&Path> = history
+            .top(2)
+            .into_iter()
+            .map(|entry| entry.path.as_path())
+            .collect();
+        assert_eq!(top, vec![Path::new("/src/app"), Path::new("/src/tool")]);
+
+        let content = toml::to_string_pretty(&history).unwrap();
+        let parsed: History = toml::from_str(&content).unwrap();
+        assert_eq!(parsed, history);
+    }
+}
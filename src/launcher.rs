@@ -2,9 +2,13 @@
 //!
 //! 選択されたプロジェクトを指定のエディタで開きます。
 
-use anyhow::{Context, Result};
+use crate::scanner::Project;
+use crate::tmux;
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::io::IsTerminal;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 /// エディタコマンドのエイリアスマッピング
 const EDITOR_ALIASES: &[(&str, &[&str])] = &[
@@ -27,85 +31,647 @@ const EDITOR_ALIASES: &[(&str, &[&str])] = &[
     ("zed", &["zed"]),
 ];
 
+/// エイリアスを展開したエディタコマンド
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EditorCommand {
+    /// 実行するプログラム
+    program: String,
+    /// プロジェクトのパスの前に渡す引数（ユーザー定義のエイリアスで指定したもの）
+    args: Vec<String>,
+}
+
 /// エディタランチャー
 pub struct Launcher {
-    /// エディタコマンド
-    editor: String,
+    /// エディタコマンドの候補（先頭から順に試行）
+    editors: Vec<String>,
+    /// ユーザー定義のエディタエイリアス（組み込みのエイリアスより優先）
+    editor_aliases: HashMap<String, String>,
+    /// エディタ起動前に実行するシェルコマンド
+    pre_launch_hook: Option<String>,
+    /// エディタ起動後に実行するシェルコマンド
+    post_launch_hook: Option<String>,
+    /// ターミナル起動コマンド（未設定の場合は自動検出）
+    terminal_command: Option<String>,
+    /// 端末上で動作するエディタ（切り離さずに起動する）
+    terminal_editors: Vec<String>,
 }
 
 impl Launcher {
     /// 新しいランチャーを作成
     pub fn new(editor: &str) -> Self {
+        Self::with_fallbacks(vec![editor.to_string()])
+    }
+
+    /// フォールバック付きのランチャーを作成
+    ///
+    /// 起動時には先頭から順に利用可能かチェックし、最初に見つかったエディタを使用します。
+    pub fn with_fallbacks(editors: Vec<String>) -> Self {
         Self {
-            editor: editor.to_string(),
+            editors,
+            editor_aliases: HashMap::new(),
+            pre_launch_hook: None,
+            post_launch_hook: None,
+            terminal_command: None,
+            terminal_editors: vec![],
         }
     }
 
+    /// ユーザー定義のエディタエイリアスを設定
+    pub fn with_editor_aliases(mut self, editor_aliases: HashMap<String, String>) -> Self {
+        self.editor_aliases = editor_aliases;
+        self
+    }
+
+    /// 端末上で動作するエディタを設定
+    pub fn with_terminal_editors(mut self, terminal_editors: Vec<String>) -> Self {
+        self.terminal_editors = terminal_editors;
+        self
+    }
+
+    /// ターミナル起動コマンドを設定
+    pub fn with_terminal(mut self, terminal_command: Option<String>) -> Self {
+        self.terminal_command = terminal_command;
+        self
+    }
+
+    /// 起動前後に実行するフックを設定
+    pub fn with_hooks(mut self, pre: Option<String>, post: Option<String>) -> Self {
+        self.pre_launch_hook = pre;
+        self.post_launch_hook = post;
+        self
+    }
+
     /// プロジェクトをエディタで開く
-    pub fn launch(&self, project_path: &Path) -> Result<()> {
-        let editor_cmd = self.resolve_editor();
+    ///
+    /// 起動前フックが失敗した場合はエディタを起動しません。
+    /// 端末エディタは終了するまで待ち、0以外で終了した場合はエラーを返します。
+    /// 起動後フックは完了を待たずにバックグラウンドで実行します。
+    pub fn launch(&self, project: &Project) -> Result<()> {
+        let editor = self.select_editor_command()?;
+        tracing::info!(
+            editor = %editor.program,
+            args = ?editor.args,
+            project = %project.path.display(),
+            "launching editor"
+        );
 
-        // エディタを起動
-        Command::new(&editor_cmd)
-            .arg(project_path)
-            .spawn()
-            .with_context(|| {
+        if let Some(ref hook) = self.pre_launch_hook {
+            tracing::info!(hook = %hook, "running pre-launch hook");
+            run_pre_launch_hook(hook, project)?;
+        }
+
+        // 端末エディタは終了まで待ち、GUIエディタはシェルのセッションから切り離す
+        let mut command = build_command(&editor, &project.path);
+        let launched = if self.is_terminal_editor(&editor.program) {
+            wait_for_editor(&mut command, &editor.program)
+        } else {
+            detach(&mut command);
+            command.spawn().map(|_| ()).with_context(|| {
                 format!(
                     "Failed to launch editor '{}'. Is it installed and in PATH?",
-                    editor_cmd
+                    editor.program
                 )
-            })?;
+            })
+        };
+        if let Err(ref e) = launched {
+            tracing::error!(editor = %editor.program, error = %format!("{:#}", e), "failed to launch editor");
+        }
+        launched?;
+
+        tracing::info!(editor = %editor.program, "editor launched");
+
+        if let Some(ref hook) = self.post_launch_hook {
+            tracing::info!(hook = %hook, "running post-launch hook");
+            hook_command(hook, project)
+                .spawn()
+                .with_context(|| format!("Failed to run post-launch hook: {}", hook))?;
+        }
+
+        Ok(())
+    }
+
+    /// ファイルをエディタで開き、エディタが終了するまで待つ
+    ///
+    /// GUIエディタはすぐに終了するため、編集の完了までは待てません。
+    pub fn edit_file(&self, path: &Path) -> Result<()> {
+        let editor = self.select_editor_command()?;
+        wait_for_editor(&mut build_command(&editor, path), &editor.program)
+    }
+
+    /// プロジェクトのディレクトリを作業ディレクトリとしてターミナルを開く
+    pub fn launch_with_terminal(&self, project_path: &Path) -> Result<()> {
+        let command_line = self.terminal_command_line()?;
+        let mut command = terminal_command(&command_line, project_path)?;
+
+        command
+            .current_dir(project_path)
+            .spawn()
+            .with_context(|| format!("Failed to launch terminal '{}'", command_line))?;
 
         Ok(())
     }
 
-    /// エディタコマンドを解決（エイリアスを展開）
+    /// プロジェクトのディレクトリでシェルを開く
+    ///
+    /// ターミナルが設定されていればそのターミナルを開きます。
+    /// 設定がなく端末から実行されている場合は `$SHELL` をその場で起動し、終了を待ちます。
+    pub fn launch_shell(&self, project_path: &Path) -> Result<()> {
+        if self.terminal_command.is_some() || !std::io::stdin().is_terminal() {
+            return self.launch_with_terminal(project_path);
+        }
+
+        let shell = default_shell();
+        Command::new(&shell)
+            .current_dir(project_path)
+            .status()
+            .with_context(|| format!("Failed to launch shell '{}'", shell))?;
+
+        Ok(())
+    }
+
+    /// シェルを開くときに実行されるコマンドラインを取得
+    pub fn shell_command_line(&self, project_path: &Path) -> Result<String> {
+        if self.terminal_command.is_some() || !std::io::stdin().is_terminal() {
+            let command = terminal_command(&self.terminal_command_line()?, project_path)?;
+            return Ok(quoted_command_line(&command));
+        }
+
+        Ok(quote_path(Path::new(&default_shell())))
+    }
+
+    /// 設定済み、または自動検出したターミナルの起動コマンドを取得
+    fn terminal_command_line(&self) -> Result<String> {
+        self.terminal_command
+            .clone()
+            .or_else(detect_terminal)
+            .context("No terminal found. Set one with `quick-proj set-terminal <CMD>`")
+    }
+
+    /// プロジェクト用のtmuxセッションを作成または切り替える
+    ///
+    /// セッションが存在しない場合は作成し、`open_editor` が有効なら
+    /// セッション内でエディタを起動します。
+    pub fn launch_tmux(
+        &self,
+        project: &Project,
+        session_prefix: Option<&str>,
+        open_editor: bool,
+    ) -> Result<()> {
+        let name = tmux::session_name(session_prefix, &project.name);
+
+        if !tmux::has_session(&name) {
+            tmux::new_session(&name, &project.path)?;
+
+            if open_editor {
+                tmux::send_command(&name, &self.command_line(&project.path)?)?;
+            }
+        }
+
+        tmux::attach(&name)
+    }
+
+    /// 実行されるコマンドラインをシェルに貼り付け可能な形式で取得
+    pub fn command_line(&self, project_path: &Path) -> Result<String> {
+        let command = build_command(&self.select_editor_command()?, project_path);
+        Ok(quoted_command_line(&command))
+    }
+
+    /// 使用するエディタのプログラム名を決定
+    ///
+    /// 候補が1つだけの場合はチェックせずにそのまま使用します。
+    pub fn select_editor(&self) -> Result<String> {
+        self.select_editor_command().map(|editor| editor.program)
+    }
+
+    /// 使用するエディタコマンドを決定（引数を含む）
+    fn select_editor_command(&self) -> Result<EditorCommand> {
+        if let [editor] = self.editors.as_slice() {
+            return Ok(self.resolve_alias(editor));
+        }
+
+        for editor in &self.editors {
+            let editor_cmd = self.resolve_alias(editor);
+            if is_command_available(&editor_cmd.program) {
+                return Ok(editor_cmd);
+            }
+        }
+
+        bail!(
+            "No available editor found. Tried: {}",
+            self.editors.join(", ")
+        )
+    }
+
+    /// 端末上で動作するエディタかどうか
+    ///
+    /// コマンド名（パスを除く）を、エイリアスを展開したうえで比較します。
+    pub fn is_terminal_editor(&self, editor_cmd: &str) -> bool {
+        let name = Path::new(editor_cmd)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        self.terminal_editors
+            .iter()
+            .any(|editor| self.resolve_alias(editor).program == name)
+    }
+
+    /// エディタコマンドを解決（エイリアスを展開したプログラム名）
     fn resolve_editor(&self) -> String {
-        let editor_lower = self.editor.to_lowercase();
+        self.editors
+            .first()
+            .map(|editor| self.resolve_alias(editor).program)
+            .unwrap_or_default()
+    }
 
-        // エイリアスをチェック
-        for (alias, commands) in EDITOR_ALIASES {
-            if *alias == editor_lower {
-                return commands[0].to_string();
+    /// エイリアスを実際のコマンドに展開
+    ///
+    /// 前後の空白を取り除き、ユーザー定義のエイリアス、組み込みのエイリアスの順に
+    /// 大文字小文字を区別せずに照合します。ユーザー定義のエイリアスの値は空白で
+    /// プログラムと引数に分け、プログラムはさらに組み込みのエイリアスで展開します。
+    fn resolve_alias(&self, editor: &str) -> EditorCommand {
+        let editor = editor.trim();
+        let user_alias = self.editor_aliases.get(editor).or_else(|| {
+            self.editor_aliases
+                .iter()
+                .find(|(alias, _)| alias.trim().to_lowercase() == editor.to_lowercase())
+                .map(|(_, command)| command)
+        });
+
+        if let Some(command) = user_alias {
+            let mut parts = command.split_whitespace();
+            if let Some(program) = parts.next() {
+                return EditorCommand {
+                    program: resolve_builtin_alias(program),
+                    args: parts.map(String::from).collect(),
+                };
             }
         }
 
-        // エイリアスになければそのまま返す
-        self.editor.clone()
+        EditorCommand {
+            program: resolve_builtin_alias(editor),
+            args: vec![],
+        }
     }
 
     /// エディタが利用可能かチェック
     pub fn check_editor_available(&self) -> bool {
-        let editor_cmd = self.resolve_editor();
+        is_command_available(&self.resolve_editor())
+    }
+}
 
-        // `which` コマンドでチェック（Unix系）
-        #[cfg(unix)]
-        {
-            Command::new("which")
-                .arg(&editor_cmd)
-                .output()
-                .map(|o| o.status.success())
-                .unwrap_or(false)
+/// 自動検出の対象とするターミナル（優先順）
+const KNOWN_TERMINALS: &[&str] = &[
+    "wezterm",
+    "alacritty",
+    "kitty",
+    "ghostty",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "xterm",
+    "wt",
+];
+
+/// ターミナルの起動コマンドを組み立てる
+///
+/// 引数中の `{path}` はプロジェクトのパスに置き換えます。
+fn terminal_command(command_line: &str, project_path: &Path) -> Result<Command> {
+    let path = project_path.to_string_lossy();
+    let mut parts = command_line.split_whitespace();
+    let program = parts.next().context("Terminal command is empty")?;
+
+    let mut command = Command::new(program);
+    command.args(parts.map(|part| part.replace("{path}", &path)));
+    Ok(command)
+}
+
+/// ログインシェル（`$SHELL`、Windowsでは `%COMSPEC%`）を取得
+fn default_shell() -> String {
+    let (var, fallback) = if cfg!(windows) {
+        ("COMSPEC", "cmd")
+    } else {
+        ("SHELL", "sh")
+    };
+
+    std::env::var(var)
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// 使用中のターミナルを検出
+///
+/// `$TERM_PROGRAM`、`$VTE_VERSION`、既知のターミナルの順に確認します。
+fn detect_terminal() -> Option<String> {
+    if let Ok(term_program) = std::env::var("TERM_PROGRAM") {
+        let command = match term_program.as_str() {
+            "Apple_Terminal" => Some("open -a Terminal ."),
+            "iTerm.app" => Some("open -a iTerm ."),
+            "WezTerm" => Some("wezterm start"),
+            "ghostty" => Some("ghostty"),
+            _ => None,
+        };
+        if let Some(command) = command {
+            return Some(command.to_string());
         }
+    }
 
-        // `where` コマンドでチェック（Windows）
-        #[cfg(windows)]
-        {
-            Command::new("where")
-                .arg(&editor_cmd)
-                .output()
-                .map(|o| o.status.success())
-                .unwrap_or(false)
+    // VTEベースのターミナル（GNOME Terminalなど）
+    if std::env::var_os("VTE_VERSION").is_some() && is_command_available("gnome-terminal") {
+        return Some("gnome-terminal".to_string());
+    }
+
+    KNOWN_TERMINALS
+        .iter()
+        .find(|terminal| is_command_available(terminal))
+        .map(|terminal| terminal.to_string())
+}
+
+/// 文字列をシェル経由で実行するコマンドを組み立てる
+pub fn shell_command(command_line: &str) -> Command {
+    #[cfg(unix)]
+    {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(command_line);
+        command
+    }
+
+    #[cfg(windows)]
+    {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(command_line);
+        command
+    }
+}
+
+/// フック用のシェルコマンドを組み立てる
+///
+/// プロジェクトのディレクトリで実行し、`QUICK_PROJ_PATH` と `QUICK_PROJ_NAME` を設定します。
+fn hook_command(hook: &str, project: &Project) -> Command {
+    let mut command = shell_command(hook);
+    command
+        .current_dir(&project.path)
+        .env("QUICK_PROJ_PATH", &project.path)
+        .env("QUICK_PROJ_NAME", &project.name);
+    command
+}
+
+/// 起動前フックを実行し、終了を待つ
+fn run_pre_launch_hook(hook: &str, project: &Project) -> Result<()> {
+    let output = hook_command(hook, project)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .output()
+        .with_context(|| format!("Failed to run pre-launch hook: {}", hook))?;
+
+    if !output.status.success() {
+        bail!(
+            "Pre-launch hook failed ({}): {}\n{}",
+            output.status,
+            hook,
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        );
+    }
+
+    Ok(())
+}
+
+/// エディタ起動用のコマンドを組み立てる
+fn build_command(editor: &EditorCommand, project_path: &Path) -> Command {
+    let mut command = Command::new(&editor.program);
+    command.args(&editor.args).arg(project_path);
+    command
+}
+
+/// エディタを起動して終了を待つ
+///
+/// 0以外で終了した場合はエラーを返します。
+fn wait_for_editor(command: &mut Command, editor_cmd: &str) -> Result<()> {
+    let status = command.status().with_context(|| {
+        format!(
+            "Failed to launch editor '{}'. Is it installed and in PATH?",
+            editor_cmd
+        )
+    })?;
+
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor_cmd, status);
+    }
+
+    Ok(())
+}
+
+/// 起動するプロセスを呼び出し元のセッションから切り離す
+///
+/// Unixでは新しいプロセスグループで起動し、端末の入出力を引き継がないようにします。
+/// Windowsではコンソールウィンドウを開かずに起動します。
+fn detach(command: &mut Command) {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW);
+    }
+}
+
+/// 組み込みのエイリアスを実際のコマンド名に展開
+fn resolve_builtin_alias(editor: &str) -> String {
+    let editor_lower = editor.to_lowercase();
+
+    // エイリアスをチェック
+    for (alias, commands) in EDITOR_ALIASES {
+        if *alias == editor_lower {
+            return commands[0].to_string();
         }
     }
+
+    // エイリアスになければそのまま返す
+    editor.to_string()
+}
+
+/// コマンドがPATH上に存在するかチェック
+pub fn is_command_available(command: &str) -> bool {
+    // `which` コマンドでチェック（Unix系）
+    #[cfg(unix)]
+    {
+        Command::new("which")
+            .arg(command)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    // `where` コマンドでチェック（Windows）
+    #[cfg(windows)]
+    {
+        Command::new("where")
+            .arg(command)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+}
+
+/// プロジェクトのリモート（origin）のWebページのURLを取得
+///
+/// リモートがない場合や、Webページに変換できないURL（ローカルパスなど）の場合は `None` を返します。
+pub fn remote_web_url(project_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(project_path)
+        .args(["remote", "get-url", "origin"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    web_url(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+/// git のリモートURLをブラウザで開けるURLに変換
+///
+/// `git@github.com:user/repo.git` や `ssh://git@host:22/user/repo` は
+/// `https://github.com/user/repo` の形式に変換し、認証情報と末尾の `.git` を取り除きます。
+pub fn web_url(remote: &str) -> Option<String> {
+    let (scheme, rest) = match remote.split_once("://") {
+        Some(("http", rest)) => ("http", rest),
+        Some(("https" | "ssh" | "git" | "git+ssh", rest)) => ("https", rest),
+        Some(_) => return None,
+        // scp形式（git@github.com:user/repo.git）
+        None => match remote.split_once(':') {
+            Some((host, path)) if !host.contains('/') && !path.starts_with('/') => {
+                return web_url(&format!("ssh://{}/{}", host, path));
+            }
+            _ => return None,
+        },
+    };
+
+    let (authority, path) = rest.split_once('/')?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    // ssh のポート番号はWebページには使えない
+    let host = if remote.starts_with("http") {
+        host
+    } else {
+        host.split(':').next().unwrap_or(host)
+    };
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some(format!("{}://{}/{}", scheme, host, path))
+}
+
+/// URLを既定のブラウザで開く
+pub fn open_url(url: &str) -> Result<()> {
+    open::that_detached(url).with_context(|| format!("Failed to open '{}' in the browser", url))
+}
+
+/// ディレクトリをファイルマネージャーで開く
+///
+/// `file_manager` を指定した場合はそのコマンドを、指定しない場合はOS標準のコマンド
+/// （Linuxでは `xdg-open`、macOSでは `open`、Windowsでは `explorer.exe`）を使います。
+pub fn open_file_manager(path: &Path, file_manager: Option<&str>) -> Result<()> {
+    let mut command = file_manager_command(path, file_manager)?;
+    let program = command.get_program().to_string_lossy().into_owned();
+
+    detach(&mut command);
+    command.spawn().with_context(|| {
+        format!(
+            "Failed to open '{}' in the file manager '{}'",
+            path.display(),
+            program
+        )
+    })?;
+
+    Ok(())
+}
+
+/// ファイルマネージャーの起動コマンドを組み立てる
+///
+/// コマンドは空白で分割し、最後にディレクトリのパスを渡します。
+fn file_manager_command(path: &Path, file_manager: Option<&str>) -> Result<Command> {
+    let command_line = file_manager.unwrap_or(DEFAULT_FILE_MANAGER);
+    let mut parts = command_line.split_whitespace();
+    let program = parts.next().context("File manager command is empty")?;
+
+    let mut command = Command::new(program);
+    command.args(parts).arg(path);
+    Ok(command)
+}
+
+/// OS標準のファイルマネージャーを開くコマンド
+const DEFAULT_FILE_MANAGER: &str = if cfg!(target_os = "macos") {
+    "open"
+} else if cfg!(windows) {
+    "explorer.exe"
+} else {
+    "xdg-open"
+};
+
+/// パスをシェルのコマンド文字列に埋め込めるようにクォート
+///
+/// 空白・引用符・`$` などを含むパスをシェルのコマンド文字列（`cd` の出力や tmux の send-keys など）に
+/// 埋め込む場合は、必ずこの関数を通します。`Command::arg` で直接渡す場合は不要です。
+pub fn quote_path(path: &Path) -> String {
+    shell_quote(&path.to_string_lossy())
+}
+
+/// コマンドをシェルに貼り付け可能なコマンドラインに変換
+fn quoted_command_line(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| quote_path(Path::new(part)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// シェルで安全に扱えるように文字列をクォート
+///
+/// 安全な文字のみで構成されている場合はそのまま返し、
+/// それ以外はシングルクォートで囲みます。
+pub fn shell_quote(s: &str) -> String {
+    let is_safe = !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+
+    if is_safe {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
 }
 
 /// 利用可能なエディタの一覧を取得
-#[allow(dead_code)]
+///
+/// 同じコマンドを指すエイリアス（`code` と `vscode` など）は先にあるものだけを返します。
 pub fn get_available_editors() -> Vec<String> {
     let mut available = Vec::new();
+    let mut seen = Vec::new();
 
     for (alias, commands) in EDITOR_ALIASES {
+        if seen.contains(&commands[0]) {
+            continue;
+        }
+        seen.push(commands[0]);
+
         let launcher = Launcher::new(commands[0]);
         if launcher.check_editor_available() {
             available.push(alias.to_string());
@@ -128,12 +694,263 @@ mod tests {
         assert_eq!(launcher.resolve_editor(), "nvim");
     }
 
+    #[test]
+    fn test_resolve_user_alias() {
+        let aliases = HashMap::from([
+            ("e".to_string(), "neovim".to_string()),
+            ("Work-Code".to_string(), "code  --profile work".to_string()),
+            ("vscode".to_string(), "codium".to_string()),
+        ]);
+        let launcher = Launcher::new("  E ").with_editor_aliases(aliases.clone());
+        assert_eq!(launcher.resolve_editor(), "nvim");
+
+        // ユーザー定義のエイリアスは組み込みのエイリアスより優先する
+        let launcher = Launcher::new("vscode").with_editor_aliases(aliases.clone());
+        assert_eq!(launcher.resolve_editor(), "codium");
+
+        // 値に空白を含む場合はプログラムと引数に分ける
+        let launcher = Launcher::new("work-code").with_editor_aliases(aliases.clone());
+        assert_eq!(
+            launcher.resolve_alias("work-code"),
+            EditorCommand {
+                program: "code".to_string(),
+                args: vec!["--profile".to_string(), "work".to_string()],
+            }
+        );
+        assert_eq!(
+            launcher.command_line(Path::new("/tmp/test")).unwrap(),
+            "code --profile work /tmp/test"
+        );
+
+        let launcher = Launcher::new(" Neovim\n");
+        assert_eq!(launcher.resolve_editor(), "nvim");
+    }
+
     #[test]
     fn test_resolve_editor_no_alias() {
         let launcher = Launcher::new("my-custom-editor");
         assert_eq!(launcher.resolve_editor(), "my-custom-editor");
     }
 
+    #[test]
+    fn test_command_line() {
+        let launcher = Launcher::new("vscode");
+        assert_eq!(
+            launcher.command_line(Path::new("/tmp/my project")).unwrap(),
+            "code '/tmp/my project'"
+        );
+        assert_eq!(
+            launcher.command_line(Path::new("/tmp/test")).unwrap(),
+            "code /tmp/test"
+        );
+    }
+
+    #[test]
+    fn test_with_fallbacks_none_available() {
+        let launcher = Launcher::with_fallbacks(vec![
+            "quick-proj-missing-editor-1".to_string(),
+            "quick-proj-missing-editor-2".to_string(),
+        ]);
+
+        let err = launcher.select_editor().unwrap_err().to_string();
+        assert!(err.contains("quick-proj-missing-editor-1"));
+        assert!(err.contains("quick-proj-missing-editor-2"));
+    }
+
+    #[test]
+    fn test_is_terminal_editor() {
+        let launcher = Launcher::new("vim")
+            .with_terminal_editors(vec!["neovim".to_string(), "vim".to_string()]);
+
+        assert!(launcher.is_terminal_editor("vim"));
+        assert!(launcher.is_terminal_editor("nvim"));
+        assert!(launcher.is_terminal_editor("/usr/local/bin/nvim"));
+        assert!(!launcher.is_terminal_editor("code"));
+    }
+
+    #[test]
+    fn test_terminal_command_replaces_path() {
+        let command = terminal_command(
+            "alacritty --working-directory {path}",
+            Path::new("/src/my app"),
+        )
+        .unwrap();
+
+        assert_eq!(command.get_program(), "alacritty");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, vec!["--working-directory", "/src/my app"]);
+
+        assert!(terminal_command("  ", Path::new("/src")).is_err());
+    }
+
+    #[test]
+    fn test_file_manager_command() {
+        let command = file_manager_command(Path::new("/src/my app"), Some("nautilus -w")).unwrap();
+        assert_eq!(command.get_program(), "nautilus");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, vec!["-w", "/src/my app"]);
+
+        let command = file_manager_command(Path::new("/src/app"), None).unwrap();
+        assert_eq!(command.get_program(), DEFAULT_FILE_MANAGER);
+
+        assert!(file_manager_command(Path::new("/src"), Some(" ")).is_err());
+    }
+
+    #[test]
+    fn test_shell_command_line_with_terminal() {
+        let launcher = Launcher::new("vim").with_terminal(Some("kitty -d {path}".to_string()));
+        assert_eq!(
+            launcher.shell_command_line(Path::new("/src/app")).unwrap(),
+            "kitty -d /src/app"
+        );
+    }
+
+    #[test]
+    fn test_web_url() {
+        let expected = Some("https://github.com/user/repo".to_string());
+        assert_eq!(web_url("git@github.com:user/repo.git"), expected);
+        assert_eq!(web_url("https://github.com/user/repo.git"), expected);
+        assert_eq!(web_url("https://token@github.com/user/repo"), expected);
+        assert_eq!(web_url("ssh://git@github.com:22/user/repo.git"), expected);
+        assert_eq!(
+            web_url("git@gitlab.com:group/sub/project.git"),
+            Some("https://gitlab.com/group/sub/project".to_string())
+        );
+        assert_eq!(
+            web_url("http://git.local:8080/team/app"),
+            Some("http://git.local:8080/team/app".to_string())
+        );
+        assert_eq!(web_url("/srv/git/repo.git"), None);
+        assert_eq!(web_url("file:///srv/git/repo.git"), None);
+    }
+
+    #[test]
+    fn test_quote_path() {
+        assert_eq!(quote_path(Path::new("/src/app")), "/src/app");
+        assert_eq!(quote_path(Path::new("/src/my app")), "'/src/my app'");
+        assert_eq!(
+            quote_path(Path::new("/src/it's \"new\"")),
+            "'/src/it'\\''s \"new\"'"
+        );
+        assert_eq!(quote_path(Path::new("/src/$HOME")), "'/src/$HOME'");
+        assert_eq!(
+            quote_path(Path::new("/src/my project (old)")),
+            "'/src/my project (old)'"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_quote_path_roundtrips_through_shell() {
+        for name in [
+            "my project (old)",
+            "it's \"quoted\"",
+            "$HOME `id` $(id)",
+            "a;b&c|d",
+        ] {
+            let path = Path::new("/src").join(name);
+            let output = shell_command(&format!("printf %s {}", quote_path(&path)))
+                .output()
+                .unwrap();
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                path.to_string_lossy()
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launch_path_with_spaces_and_parens() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("my project (old)");
+        std::fs::create_dir(&project_dir).unwrap();
+
+        // 受け取った引数がプロジェクトのディレクトリそのものかを確認するエディタ
+        let editor = dir.path().join("check-editor");
+        std::fs::write(
+            &editor,
+            "#!/bin/sh\n[ \"$#\" -eq 1 ] && [ -d \"$1\" ] && [ \"$(basename \"$1\")\" = 'my project (old)' ]\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let project = Project {
+            path: project_dir.clone(),
+            root: dir.path().to_path_buf(),
+            name: "my project (old)".to_string(),
+            marker: ".git".to_string(),
+            project_type: crate::scanner::ProjectType::Generic,
+            modified: None,
+            overrides: None,
+        };
+        let launcher = Launcher::new(&editor.to_string_lossy())
+            .with_terminal_editors(vec!["check-editor".to_string()]);
+
+        launcher.launch(&project).unwrap();
+        assert_eq!(
+            launcher.command_line(&project_dir).unwrap(),
+            format!("{} {}", quote_path(&editor), quote_path(&project_dir))
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("plain"), "plain");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pre_launch_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = Project {
+            path: dir.path().to_path_buf(),
+            root: dir.path().to_path_buf(),
+            name: "hook-test".to_string(),
+            marker: ".git".to_string(),
+            project_type: crate::scanner::ProjectType::Generic,
+            modified: None,
+            overrides: None,
+        };
+
+        // 環境変数が渡されていること
+        assert!(run_pre_launch_hook(r#"test "$QUICK_PROJ_NAME" = hook-test"#, &project).is_ok());
+
+        // 失敗した場合は標準エラー出力をエラーに含める
+        let err = run_pre_launch_hook("echo boom >&2; exit 3", &project)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("boom"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launch_waits_for_terminal_editor() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = Project {
+            path: dir.path().to_path_buf(),
+            root: dir.path().to_path_buf(),
+            name: "editor-test".to_string(),
+            marker: ".git".to_string(),
+            project_type: crate::scanner::ProjectType::Generic,
+            modified: None,
+            overrides: None,
+        };
+        let terminal_editors = vec!["true".to_string(), "false".to_string()];
+
+        let launcher = Launcher::new("true").with_terminal_editors(terminal_editors.clone());
+        assert!(launcher.launch(&project).is_ok());
+
+        // 終了コードが0以外ならエラーになる
+        let launcher = Launcher::new("false").with_terminal_editors(terminal_editors);
+        let err = launcher.launch(&project).unwrap_err().to_string();
+        assert!(err.contains("exited with"));
+    }
+
     #[test]
     fn test_editor_aliases_exist() {
         // エイリアスが正しく定義されているか
@@ -0,0 +1,32 @@
+//! quick-proj
+//!
+//! 開発者のための高速プロジェクトランチャー
+//!
+//! CLIとして使うほか、プロジェクトのスキャンや絞り込み、並び替えを
+//! ライブラリとして自分のツールに組み込めます。
+//!
+//! ```no_run
+//! use quick_proj::{filter_projects, CaseSensitivity, Config, Scanner};
+//!
+//! let config = Config::load()?;
+//! let scanner = Scanner::from_config(&config);
+//! let projects = scanner.scan(&config.root_paths)?;
+//!
+//! for project in filter_projects(&projects, "api", CaseSensitivity::Insensitive) {
+//!     println!("{}", project.display_string());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! 公開APIは [`config`]・[`scanner`]・[`history`]・[`ranking`]・[`state`] の各モジュールです。
+
+pub mod config;
+pub mod history;
+pub mod ranking;
+pub mod scanner;
+pub mod state;
+
+pub use config::Config;
+pub use history::History;
+pub use ranking::{rank, ScoredProject};
+pub use scanner::{filter_projects, CaseSensitivity, Project, ProjectType, Scanner, SortCriterion};
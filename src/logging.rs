@@ -0,0 +1,82 @@
+//! ログ出力モジュール
+//!
+//! `--log-file` または設定の `log_file` が指定された場合に、
+//! JSON形式（1行1イベント）のログをファイルに追記します。
+//! スキャンのディレクトリごとの所要時間や、エディタの起動を記録します。
+
+use crate::config::expand_path;
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// ログファイルへの出力を開始する
+///
+/// `level` は `warn` のようなレベル名のほか、`quick_proj::scanner=debug` の形式も使えます。
+/// スパンの終了時には所要時間（`time.busy` / `time.idle`）を記録します。
+pub fn init(path: &Path, level: &str) -> Result<()> {
+    let path = expand_path(path)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create log directory: {}", parent.display()))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open log file: {}", path.display()))?;
+
+    tracing::subscriber::set_global_default(subscriber(file, level)?)
+        .map_err(|e| anyhow!("Failed to initialize logging: {}", e))
+}
+
+/// ファイルにJSON形式で書き出すサブスクライバーを作成
+fn subscriber(file: File, level: &str) -> Result<impl Subscriber + Send + Sync> {
+    let filter =
+        EnvFilter::try_new(level).with_context(|| format!("Invalid log_level '{}'", level))?;
+
+    Ok(tracing_subscriber::fmt()
+        .json()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_ansi(false)
+        .with_writer(Mutex::new(file))
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_subscriber_writes_json_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("quick-proj.log");
+        let subscriber = subscriber(File::create(&path).unwrap(), "info").unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(editor = "code", "launching editor");
+            tracing::debug!("filtered out by the level");
+        });
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let event: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["fields"]["message"], "launching editor");
+        assert_eq!(event["fields"]["editor"], "code");
+    }
+
+    #[test]
+    fn test_subscriber_rejects_invalid_level() {
+        let dir = tempdir().unwrap();
+        let file = File::create(dir.path().join("quick-proj.log")).unwrap();
+        assert!(subscriber(file, "quick_proj=loud").is_err());
+    }
+}
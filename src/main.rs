@@ -9,197 +9,1849 @@
 //! - 設定管理: 検索対象パスの追加・削除
 
 mod cli;
-mod config;
+mod clipboard;
+mod doctor;
 mod launcher;
-mod scanner;
+mod logging;
+mod preview;
+mod shell_init;
+mod stats;
+mod tmux;
 mod ui;
+mod watch;
+mod wizard;
 
-use anyhow::Result;
-use cli::{Args, Command};
+use anyhow::{anyhow, Context, Result};
+use cli::{
+    AliasAction, Args, Command, ConfigAction, ExcludeAction, HistoryAction, ProfileAction,
+    TagAction,
+};
 use colored::Colorize;
-use config::Config;
-use launcher::Launcher;
-use scanner::Scanner;
-use std::time::Instant;
+use launcher::{quote_path, Launcher};
+use quick_proj::config::{
+    canonicalize_tag, project_tags, Config, ConfigFormat, ImportMode, ImportedConfig, LaunchTarget,
+    Selector,
+};
+use quick_proj::history::{unix_now, History, DEFAULT_TOP_LIMIT};
+use quick_proj::scanner::{
+    CaseSensitivity, Project, ProjectOverride, ProjectType, ScanError, Scanner, SortCriterion,
+};
+use quick_proj::state::State;
+use quick_proj::{config, history, ranking, scanner};
+use shell_init::Shell;
+use stats::ProjectStats;
+use std::fs;
+use std::io::{BufRead, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use ui::{DisplayOptions, ListFormat};
+
+/// `open-all` でプロジェクトを順に開く間隔
+const OPEN_ALL_INTERVAL: Duration = Duration::from_millis(300);
 
 fn main() -> Result<()> {
     let args = Args::parse_args();
 
-    match args.command {
-        Some(Command::Add { path }) => cmd_add(&path),
-        Some(Command::Remove { path }) => cmd_remove(&path),
-        Some(Command::List) => cmd_list(),
-        Some(Command::Config) => cmd_config(),
-        Some(Command::Scan) => cmd_scan(args.max_depth),
-        Some(Command::SetEditor { editor }) => cmd_set_editor(&editor),
-        None => cmd_select(args.editor.as_deref(), args.max_depth),
+    ui::init_colors(args.color);
+    config::init_config_path(args.config.as_deref());
+    config::init_profile(args.profile.as_deref())?;
+
+    // 読み込み時に修正した設定を標準エラー出力で知らせる（機械向けの出力を行うコマンドでは表示しない）
+    // 読み込みエラーは各コマンドで報告する
+    let startup_config = match Config::load_checked() {
+        Ok((config, warnings)) => {
+            if !args.writes_machine_output() {
+                for warning in warnings {
+                    ui::print_warning(&format!("Config {}", warning));
+                }
+            }
+            config
+        }
+        Err(_) => Config::default(),
+    };
+    init_logging(&args, &startup_config);
+
+    match &args.command {
+        Some(Command::Add { paths }) => cmd_add(paths),
+        Some(Command::Remove { paths, index, all }) => cmd_remove(paths, *index, *all),
+        Some(Command::List) => cmd_list(),
+        Some(Command::Prune) => cmd_prune(args.dry_run),
+        Some(Command::Cleanup { apply }) => cmd_cleanup(*apply, args.dry_run),
+        Some(Command::Export { format, output }) => cmd_export(*format, output.as_deref()),
+        Some(Command::Import {
+            file,
+            overwrite,
+            replace,
+        }) => cmd_import(file, *overwrite, *replace),
+        Some(Command::Config { action }) => match action {
+            Some(action) => cmd_config_action(action),
+            None => cmd_config(),
+        },
+        Some(Command::EditConfig) => cmd_edit_config(&args),
+        Some(Command::Scan { format }) => cmd_scan(&args, *format),
+        Some(Command::Profile { action }) => cmd_profile(action),
+        Some(Command::Exclude { action }) => cmd_exclude(action),
+        Some(Command::Alias {
+            action,
+            path,
+            alias,
+        }) => cmd_alias(action.as_ref(), path.as_deref(), alias.as_deref()),
+        Some(Command::Tag { action }) => cmd_tag(action),
+        Some(Command::SetTerminal { command }) => cmd_set_terminal(command),
+        Some(Command::SetThreads { threads }) => cmd_set_threads(*threads),
+        Some(Command::Stats) => cmd_stats(&args),
+        Some(Command::Last) => cmd_last(&args),
+        Some(Command::OpenAll { query }) => cmd_open_all(&args, query),
+        Some(Command::Pin { query }) => cmd_pin(&args, query.as_deref()),
+        Some(Command::Unpin { query }) => cmd_unpin(&args, query),
+        Some(Command::History { action }) => cmd_history(action, &args),
+        Some(Command::Doctor) => cmd_doctor(),
+        Some(Command::Setup) => cmd_setup(),
+        Some(Command::Watch { exec }) => cmd_watch(&args, exec.as_deref()),
+        Some(Command::Init { shell }) => cmd_init(*shell),
+        Some(Command::SetEditor {
+            editor,
+            project_type,
+        }) => cmd_set_editor(editor, *project_type),
+        None => cmd_select(&args),
+    }
+}
+
+/// 初回起動として対話形式のセットアップを行うか
+///
+/// 設定ファイルがなく、端末から実行され、登録済みのルートパスを使う場合のみ対象にします。
+fn is_first_run(args: &Args) -> Result<bool> {
+    if args.stdin_roots || args.here || args.prints_path() {
+        return Ok(false);
+    }
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        return Ok(false);
+    }
+
+    if Config::config_path()?.exists() {
+        return Ok(false);
+    }
+
+    // 一度セットアップを断った場合は、毎回表示しない
+    Ok(!State::load().unwrap_or_default().setup_declined)
+}
+
+/// 初回起動時のセットアップ
+///
+/// 保存した場合はその設定を返します。断った場合は次回から自動で表示しないよう記録します。
+fn run_first_setup() -> Result<Option<Config>> {
+    let Some(mut config) = wizard::run()? else {
+        let mut state = State::load().unwrap_or_default();
+        state.setup_declined = true;
+        state.save()?;
+        println!("Run {} to set up later.", "quick-proj setup".cyan());
+        return Ok(None);
+    };
+
+    config.normalize();
+    Ok(Some(config))
+}
+
+/// セットアップコマンド
+fn cmd_setup() -> Result<()> {
+    if let Some(config) = wizard::run()? {
+        println!(
+            "Run {} to open one of the projects under {}.",
+            "quick-proj".cyan(),
+            ui::plural(config.root_paths.len(), "root path")
+        );
+    }
+
+    Ok(())
+}
+
+/// CLI引数で設定を上書き
+fn apply_cli_overrides(config: &mut Config, args: &Args) {
+    if let Some(depth) = args.max_depth {
+        config.max_depth = depth;
+    }
+
+    if args.no_icons {
+        config.show_icons = false;
+    }
+
+    if args.no_preview {
+        config.show_preview = false;
+    }
+
+    if args.show_age {
+        config.show_age = true;
+    }
+
+    if args.no_gitignore {
+        config.respect_gitignore = false;
+    }
+
+    if args.monorepo {
+        config.expand_cargo_workspaces = true;
+        config.expand_npm_workspaces = true;
+    }
+
+    if args.exact {
+        config.exact_match = true;
+    }
+
+    if args.case_sensitive {
+        config.case_sensitive = true;
+    }
+
+    if args.group_by.is_some() {
+        config.group_by = args.group_by;
+    }
+
+    if let Some(backend) = args.backend {
+        config.selector = backend;
+    }
+
+    if args.no_tui {
+        config.selector = Selector::Numbered;
+    }
+
+    if args.limit.is_some() {
+        config.max_results = args.limit;
+    }
+
+    if args.threads.is_some() {
+        config.scan_threads = args.threads;
+    }
+}
+
+/// ルートパスをスキャン
+///
+/// `incremental_scan` が有効な場合はキャッシュを利用します。
+/// `refresh` が `true` の場合はすべて走査し直してキャッシュを更新します。
+/// 読み込めなかったパスもあわせて返します。
+fn scan_projects(config: &Config, refresh: bool) -> Result<(Vec<Project>, Vec<ScanError>)> {
+    let (projects, errors, _) = scan_projects_cached(config, refresh)?;
+    Ok((projects, errors))
+}
+
+/// [`scan_projects`] に加えて、キャッシュを使ったか（読み直さなかったディレクトリがあるか）を返す
+fn scan_projects_cached(
+    config: &Config,
+    refresh: bool,
+) -> Result<(Vec<Project>, Vec<ScanError>, bool)> {
+    let scanner = Scanner::from_config(config);
+
+    let projects = scanner.scan_cached(&config.root_paths, refresh)?;
+
+    Ok((projects, scanner.take_errors(), scanner.cache_hits() > 0))
+}
+
+/// 改行区切りのルートパスを読み込む
+///
+/// 空行は無視し、`~` を展開します。
+fn read_roots(reader: impl BufRead) -> Result<Vec<PathBuf>> {
+    let mut roots = Vec::new();
+
+    for line in reader.lines() {
+        let line = line.context("Failed to read root paths from stdin")?;
+        let line = line.trim();
+        if !line.is_empty() {
+            roots.push(config::expand_path(Path::new(line))?);
+        }
+    }
+
+    Ok(roots)
+}
+
+/// その場限りのルートパス（`--stdin-roots` / `--here`）で登録済みのルートパスを置き換える
+///
+/// その場限りのルートパスではスキャンキャッシュを使いません。
+/// 置き換えた場合は `true` を返します。
+fn apply_temporary_roots(config: &mut Config, args: &Args) -> Result<bool> {
+    config.root_paths = if args.stdin_roots {
+        read_roots(std::io::stdin().lock())?
+    } else if args.here {
+        vec![std::env::current_dir().context("Failed to get current directory")?]
+    } else {
+        return Ok(false);
+    };
+    config.incremental_scan = false;
+
+    Ok(true)
+}
+
+/// `--only` / `--regex` / `--query` でスキャン結果を絞り込む
+fn apply_project_filters(projects: &mut Vec<Project>, config: &Config, args: &Args) -> Result<()> {
+    scanner::retain_marker(projects, args.only.as_deref());
+
+    let tags = args
+        .tag
+        .iter()
+        .map(|tag| canonicalize_tag(tag))
+        .collect::<Result<Vec<_>>>()?;
+    scanner::retain_tags(projects, &config.project_tags, &tags);
+
+    if let Some(query) = &args.query {
+        let case = CaseSensitivity::from_flag(config.case_sensitive);
+        *projects = scanner::filter_projects(projects, query, case)
+            .into_iter()
+            .cloned()
+            .collect();
+    }
+
+    if let Some(pattern) = &args.regex {
+        *projects = scanner::filter_by_regex(projects, pattern)?
+            .into_iter()
+            .cloned()
+            .collect();
+    }
+
+    Ok(())
+}
+
+/// 並び順を決定（CLI引数 > 設定ファイル）
+fn resolve_sort(config: &Config, args: &Args) -> Result<SortCriterion> {
+    match args.sort {
+        Some(sort) => Ok(sort),
+        None => config
+            .default_sort
+            .parse()
+            .map_err(|e| anyhow!("Invalid default_sort in config: {}", e)),
+    }
+}
+
+/// プロジェクト一覧を並び替える
+///
+/// `smart` の場合は起動履歴の頻度と新しさから算出したスコア順に並べます。
+fn sort_projects(projects: &mut Vec<Project>, config: &Config, args: &Args) -> Result<()> {
+    let sort = resolve_sort(config, args)?;
+    scanner::sort_projects(projects, sort);
+
+    match sort {
+        SortCriterion::Smart => {
+            let history = History::load().unwrap_or_default();
+            ranking::sort_by_rank(projects, &history, &config.ranking, unix_now());
+        }
+        SortCriterion::Frecency => {
+            let history = History::load().unwrap_or_default();
+            ranking::sort_by_frecency(projects, &history, unix_now());
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// 存在しないルートパスを警告し、スキャンを続けるか確認
+///
+/// 確認するのは端末から対話的に実行している場合のみで、中止した場合は `false` を返します。
+/// `warn_missing_paths` が無効な場合と、cd・コピーモードでは何もしません。
+fn warn_missing_roots(config: &Config, args: &Args) -> Result<bool> {
+    if !config.warn_missing_paths || args.prints_path() {
+        return Ok(true);
+    }
+
+    let missing = config.missing_root_paths();
+    if missing.is_empty() {
+        return Ok(true);
+    }
+
+    for path in &missing {
+        ui::print_warning(&format!("Root path does not exist: {}", path.display()));
+    }
+
+    if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+        return Ok(true);
+    }
+
+    ui::confirm("Continue scanning the remaining root paths?")
+}
+
+/// 警告済みの存在しないルートパスをスキャンエラーから除く
+fn retain_unwarned_errors(errors: &mut Vec<ScanError>, config: &Config) {
+    if !config.warn_missing_paths {
+        return;
+    }
+
+    errors.retain(|error| {
+        !error
+            .path
+            .as_ref()
+            .is_some_and(|path| config.root_paths.contains(path) && !path.exists())
+    });
+}
+
+/// プロジェクト選択モード（メイン機能）
+fn cmd_select(args: &Args) -> Result<()> {
+    // 設定ファイルがない初回起動では、対話形式のセットアップを行い、保存した設定を使う
+    let setup = if is_first_run(args)? {
+        run_first_setup()?
+    } else {
+        None
+    };
+    let mut config = match setup {
+        Some(config) => config,
+        None => Config::load()?,
+    };
+
+    // CLI引数で上書き
+    apply_cli_overrides(&mut config, args);
+
+    // 標準入力やカレントディレクトリを登録済みのルートパスの代わりに使う
+    let temporary_roots = apply_temporary_roots(&mut config, args)?;
+    if args.stdin_roots && config.root_paths.is_empty() {
+        ui::print_warning("No root paths given on stdin.");
+        return Ok(());
+    }
+
+    // ルートパスが未設定の場合
+    if config.root_paths.is_empty() {
+        ui::print_warning("No root paths configured.");
+        println!();
+        println!("Add a search path first:");
+        println!("  {} {}", "quick-proj add".cyan(), "~/src".dimmed());
+        println!("  {} {}", "quick-proj add".cyan(), "~/projects".dimmed());
+        return Ok(());
+    }
+
+    if !warn_missing_roots(&config, args)? {
+        return Ok(());
+    }
+
+    // スキャン開始
+    let start = Instant::now();
+    let (mut projects, mut errors) = scan_projects(&config, false)?;
+    let elapsed = start.elapsed().as_millis();
+
+    // 読み込めなかったパスを知らせる（cd・コピーモードでは標準出力を汚さない）
+    if !args.prints_path() {
+        retain_unwarned_errors(&mut errors, &config);
+        ui::print_scan_errors(&errors, args.verbose);
+    }
+
+    let state = State::load().unwrap_or_default();
+    prepare_projects(&mut projects, &config, args, &state, temporary_roots)?;
+
+    // --cd / --copy と --query を併用した場合は、選択UIを表示せずに選ぶ（一致しなければエラー）
+    let non_interactive_query = args.query.as_deref().filter(|_| args.prints_path());
+
+    if projects.is_empty() && non_interactive_query.is_none() {
+        ui::print_warning(&no_projects_message(&config, args));
+        println!();
+        println!("Check if your paths contain projects with markers like:");
+        println!("  .git, Cargo.toml, package.json, go.mod, etc.");
+        return Ok(());
+    }
+
+    // サマリー表示（cd・コピーモードでは標準出力を汚さない）
+    if !args.prints_path() {
+        ui::print_scan_summary(&projects, elapsed);
+    }
+
+    // 並び替えた後で上限を適用し、上位のプロジェクトだけを選択UIに渡す
+    if let Some(message) = truncate_to_limit(&mut projects, config.max_results) {
+        if !args.prints_path() {
+            ui::print_info(&message);
+        }
+    }
+
+    // Ctrl+R で選択UIを閉じずに再スキャンする（最初のスキャンと同じ絞り込み・並び替えを適用）
+    // 選択UIは再スキャンの終了を待たずに閉じるため、設定などの複製を持たせる
+    let rescan: ui::Rescan = {
+        let (config, args, state) = (config.clone(), args.clone(), state.clone());
+        Arc::new(move || {
+            let (mut projects, _) = scan_projects(&config, true)?;
+            prepare_projects(&mut projects, &config, &args, &state, temporary_roots)?;
+            truncate_to_limit(&mut projects, config.max_results);
+            Ok(projects)
+        })
+    };
+
+    // プロジェクト選択UI（--query で絞り込んでいなければ前回の検索クエリから始める）
+    let mut options = DisplayOptions::from_config(&config)
+        .with_sort(resolve_sort(&config, args)?)
+        .with_pinned(state.pinned.clone());
+    if options.needs_history() {
+        options = options.with_history(History::load().unwrap_or_default());
+    }
+    let selected: Vec<Project> = if args.multi {
+        ui::select_projects(&projects, &options)?
+            .into_iter()
+            .cloned()
+            .collect()
+    } else if let Some(query) = non_interactive_query {
+        vec![pick_match(&config, &projects, query, args.first)?]
+    } else if args.first {
+        projects.first().cloned().into_iter().collect()
+    } else {
+        let mut query = match &args.query {
+            None if config.remember_query => state.last_query.clone().unwrap_or_default(),
+            _ => String::new(),
+        };
+        let selected = select_single(&config, &projects, &options, &mut query, Some(rescan))?;
+        if config.remember_query {
+            record_last_query(&query);
+        }
+        selected.into_iter().collect()
+    };
+
+    if selected.is_empty() {
+        if !args.prints_path() {
+            println!();
+            println!("{}", "Selection cancelled.".dimmed());
+        }
+        return Ok(());
+    }
+
+    for project in &selected {
+        if options.is_stale(project) {
+            offer_unpin(args, project)?;
+            continue;
+        }
+
+        open_project(&config, args, project)?;
+        record_opened(args, project);
+    }
+
+    Ok(())
+}
+
+/// スキャン結果を選択UIに表示する一覧に整える
+///
+/// 移動・削除されたピン留めを加えて（選んだときに解除できるようにする）、
+/// `--only` などの絞り込みと並び替えを適用し、ピン留めを先頭に移動します。
+/// その場限りのルートパスでは、ピン留めを加えません。
+fn prepare_projects(
+    projects: &mut Vec<Project>,
+    config: &Config,
+    args: &Args,
+    state: &State,
+    temporary_roots: bool,
+) -> Result<()> {
+    if !temporary_roots {
+        projects.extend(
+            state
+                .stale_pins()
+                .iter()
+                .map(|path| Project::from_path(path)),
+        );
+    }
+
+    apply_project_filters(projects, config, args)?;
+    sort_projects(projects, config, args)?;
+    scanner::move_pinned_first(projects, &state.pinned);
+
+    Ok(())
+}
+
+/// プロジェクトが見つからなかった場合の警告メッセージ（スキャンしたルートパスの種類ごと）
+fn no_projects_message(config: &Config, args: &Args) -> String {
+    if args.here {
+        let dir = config.root_paths.first().map(|dir| dir.to_string_lossy());
+        format!(
+            "No projects found in {}.",
+            ui::shorten_home_path(&dir.unwrap_or_default())
+        )
+    } else if args.stdin_roots {
+        "No projects found in the root paths given on stdin.".to_string()
+    } else {
+        "No projects found in registered paths.".to_string()
+    }
+}
+
+/// 一覧を上限の件数までに切り詰める
+///
+/// 切り詰めた場合は、表示する件数と全体の件数を知らせるメッセージを返します。
+fn truncate_to_limit(projects: &mut Vec<Project>, limit: Option<usize>) -> Option<String> {
+    let limit = limit.filter(|&limit| limit < projects.len())?;
+    let message = format!("Showing {} of {} projects", limit, projects.len());
+    projects.truncate(limit);
+    Some(message)
+}
+
+/// 存在しなくなったピン留めプロジェクトが選ばれた場合に、ピン留めの解除を提案
+///
+/// cd・コピーモードでは標準出力を汚さないよう、確認のプロンプトだけを表示します。
+fn offer_unpin(args: &Args, project: &Project) -> Result<()> {
+    if !args.prints_path() {
+        ui::print_warning(&format!(
+            "Pinned project no longer exists: {}",
+            project.path.display()
+        ));
+    }
+
+    if !ui::confirm(&format!("Unpin {}?", project.name))? {
+        return Ok(());
+    }
+
+    let mut state = State::load()?;
+    state.unpin(&project.path);
+    state.save()?;
+
+    if !args.prints_path() {
+        ui::print_success(&format!("Unpinned: {}", project.path.display()));
+    }
+
+    Ok(())
+}
+
+/// 開いたプロジェクトを前回のプロジェクトと起動履歴に記録（ドライランでは記録しない）
+fn record_opened(args: &Args, project: &Project) {
+    if !args.dry_run {
+        record_last_project(project);
+        record_history(project);
+    }
+}
+
+/// 最後に開いたプロジェクトを状態ファイルに記録
+///
+/// 記録に失敗してもプロジェクトを開く処理には影響させません。
+fn record_last_project(project: &Project) {
+    if let Ok(mut state) = State::load() {
+        state.last_project = Some(project.clone());
+        let _ = state.save();
+    }
+}
+
+/// プロジェクトを開いたことを起動履歴に記録
+///
+/// 記録に失敗してもプロジェクトを開く処理には影響させません。
+fn record_history(project: &Project) {
+    if let Ok(mut history) = History::load() {
+        history.record(&project.path, unix_now());
+        let _ = history.save();
+    }
+}
+
+/// 選択UIの最後の検索クエリを状態ファイルに記録
+///
+/// 記録に失敗しても選択には影響させません。
+fn record_last_query(query: &str) {
+    if let Ok(mut state) = State::load() {
+        state.last_query = Some(query.to_string()).filter(|q| !q.is_empty());
+        let _ = state.save();
+    }
+}
+
+/// 最後に開いたプロジェクトを開くコマンド
+///
+/// 記録がない場合やディレクトリが存在しない場合は、通常の選択UIを表示します。
+fn cmd_last(args: &Args) -> Result<()> {
+    match State::load()?.last_project {
+        Some(project) if project.path.is_dir() => {
+            let mut config = Config::load()?;
+            apply_cli_overrides(&mut config, args);
+            open_project(&config, args, &project)?;
+            record_opened(args, &project);
+            Ok(())
+        }
+        Some(project) => {
+            ui::print_warning(&format!(
+                "Last project no longer exists: {}",
+                project.path.display()
+            ));
+            cmd_select(args)
+        }
+        None => {
+            ui::print_info("No project has been opened yet.");
+            cmd_select(args)
+        }
+    }
+}
+
+/// 検索語に一致するプロジェクトをすべて開くコマンド
+///
+/// 一致したプロジェクトを一覧表示してから、ウィンドウマネージャーに負荷をかけないよう
+/// 間隔を空けて順に開きます。`--dry-run` では実行されるコマンドを表示するだけです。
+fn cmd_open_all(args: &Args, query: &str) -> Result<()> {
+    let mut config = Config::load()?;
+
+    apply_cli_overrides(&mut config, args);
+    apply_temporary_roots(&mut config, args)?;
+
+    if config.root_paths.is_empty() {
+        ui::print_warning("No root paths configured.");
+        return Ok(());
+    }
+
+    if !warn_missing_roots(&config, args)? {
+        return Ok(());
+    }
+
+    let (mut projects, mut errors) = scan_projects(&config, false)?;
+    if !args.prints_path() {
+        retain_unwarned_errors(&mut errors, &config);
+        ui::print_scan_errors(&errors, args.verbose);
+    }
+
+    apply_project_filters(&mut projects, &config, args)?;
+    sort_projects(&mut projects, &config, args)?;
+
+    let case = CaseSensitivity::from_flag(config.case_sensitive);
+    let mut matched = scanner::filter_projects(&projects, query, case);
+    if let Some(limit) = config.max_results {
+        matched.truncate(limit);
+    }
+
+    if matched.is_empty() {
+        ui::print_warning(&format!("No projects match '{}'.", query));
+        return Ok(());
+    }
+
+    if !args.prints_path() {
+        ui::print_open_all(&matched, args.dry_run);
+    }
+
+    for (i, project) in matched.into_iter().enumerate() {
+        if i > 0 && !args.dry_run {
+            std::thread::sleep(OPEN_ALL_INTERVAL);
+        }
+
+        open_project(&config, args, project)?;
+        record_opened(args, project);
+    }
+
+    Ok(())
+}
+
+/// ピン留めコマンド
+///
+/// 検索語に一致するプロジェクトをピン留めし、選択UIで常に先頭に表示します。
+/// 検索語を省略した場合は、ピン留めしたプロジェクトを一覧表示します。
+fn cmd_pin(args: &Args, query: Option<&str>) -> Result<()> {
+    let mut state = State::load()?;
+
+    let Some(query) = query else {
+        if state.pinned.is_empty() {
+            ui::print_info("No pinned projects.");
+            return Ok(());
+        }
+
+        for path in &state.pinned {
+            if path.is_dir() {
+                println!("  {} {}", "★".yellow(), path.display());
+            } else {
+                println!(
+                    "  {} {}",
+                    "★".dimmed(),
+                    format!("{} (missing)", path.display()).dimmed()
+                );
+            }
+        }
+        return Ok(());
+    };
+
+    let mut config = Config::load()?;
+    apply_cli_overrides(&mut config, args);
+
+    if config.root_paths.is_empty() {
+        ui::print_warning("No root paths configured.");
+        return Ok(());
+    }
+
+    let (projects, _) = scan_projects(&config, false)?;
+    let options = DisplayOptions::from_config(&config).with_pinned(state.pinned.clone());
+    let Some(project) = select_match(&config, &projects, query, &options)? else {
+        return Ok(());
+    };
+
+    if state.pin(&project.path) {
+        state.save()?;
+        ui::print_success(&format!("Pinned: {}", project.path.display()));
+    } else {
+        ui::print_warning(&format!("Already pinned: {}", project.path.display()));
+    }
+
+    Ok(())
+}
+
+/// ピン留め解除コマンド
+///
+/// 移動・削除されたものも含め、ピン留めしたプロジェクトから検索語に一致するものを解除します。
+fn cmd_unpin(args: &Args, query: &str) -> Result<()> {
+    let mut state = State::load()?;
+    if state.pinned.is_empty() {
+        ui::print_info("No pinned projects.");
+        return Ok(());
+    }
+
+    let mut config = Config::load()?;
+    apply_cli_overrides(&mut config, args);
+
+    let pinned: Vec<Project> = state
+        .pinned
+        .iter()
+        .map(|path| Project::from_path(path))
+        .collect();
+    let options = DisplayOptions::from_config(&config).with_pinned(state.pinned.clone());
+    let Some(project) = select_match(&config, &pinned, query, &options)? else {
+        return Ok(());
+    };
+
+    state.unpin(&project.path);
+    state.save()?;
+    ui::print_success(&format!("Unpinned: {}", project.path.display()));
+
+    Ok(())
+}
+
+/// 検索語に最も一致するプロジェクトを1つ選ぶ
+///
+/// 一致したのが1つだけの場合や、最も一致するものが明らかな場合はそれを返します。
+/// 同程度に一致するものが複数ある場合は、一致度の高い順に選択UIに表示します。
+fn select_match(
+    config: &Config,
+    projects: &[Project],
+    query: &str,
+    options: &DisplayOptions,
+) -> Result<Option<Project>> {
+    let case = CaseSensitivity::from_flag(config.case_sensitive);
+    let ranked = scanner::rank_projects(projects, query, case);
+
+    if ranked.is_empty() {
+        ui::print_warning(&format!("No projects match '{}'.", query));
+        return Ok(None);
+    }
+    if let Some(project) = scanner::best_match(&ranked) {
+        return Ok(Some(project.clone()));
+    }
+
+    let matched: Vec<Project> = ranked
+        .into_iter()
+        .map(|(_, project)| project.clone())
+        .collect();
+    select_single(config, &matched, options, &mut String::new(), None)
+}
+
+/// 選択UIを表示せずに、検索語に最も一致するプロジェクトを1つ選ぶ（`--cd` / `--copy` 用）
+///
+/// 一致したのが1つだけの場合や、最も一致するものが明らかな場合はそれを返します。
+/// 同程度に一致するものが複数ある場合は、`first` なら最上位を選び、それ以外はエラーにします。
+fn pick_match(config: &Config, projects: &[Project], query: &str, first: bool) -> Result<Project> {
+    let case = CaseSensitivity::from_flag(config.case_sensitive);
+    let ranked = scanner::rank_projects(projects, query, case);
+
+    if let Some(project) = scanner::best_match(&ranked) {
+        return Ok(project.clone());
+    }
+    match ranked.first() {
+        None => Err(anyhow!("No projects match '{}'", query)),
+        Some((_, project)) if first => Ok((*project).clone()),
+        Some(_) => Err(anyhow!(
+            "{} projects match '{}'. Refine the query or pass --first to pick the best match",
+            ranked.len(),
+            query
+        )),
+    }
+}
+
+/// 起動履歴コマンド
+///
+/// `show` は開いた回数の多い順に表示します（件数は `--limit` で変更、デフォルトは20件）。
+fn cmd_history(action: &HistoryAction, args: &Args) -> Result<()> {
+    let history = History::load()?;
+
+    match action {
+        HistoryAction::Show => {
+            let limit = args.limit.unwrap_or(DEFAULT_TOP_LIMIT);
+            ui::print_history(&history.top(limit), std::time::SystemTime::now());
+        }
+        HistoryAction::Clear => {
+            if args.dry_run {
+                ui::print_info(&format!(
+                    "Would clear the history of {}.",
+                    ui::plural(history.projects.len(), "project")
+                ));
+                return Ok(());
+            }
+
+            History::default().save()?;
+            ui::print_success(&format!(
+                "Cleared the history of {}.",
+                ui::plural(history.projects.len(), "project")
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 設定された選択UIで1つのプロジェクトを選択
+///
+/// `rescan` は組み込みの選択UIの Ctrl+R で使います（外部コマンドと番号入力では使いません）。
+fn select_single(
+    config: &Config,
+    projects: &[Project],
+    options: &DisplayOptions,
+    query: &mut String,
+    rescan: Option<ui::Rescan>,
+) -> Result<Option<Project>> {
+    match config.selector {
+        Selector::Skim if launcher::is_command_available("sk") => {
+            return Ok(ui::select_project_skim(projects, options, query)?.cloned());
+        }
+        Selector::Skim => {
+            ui::print_warning("skim (sk) not found in PATH. Falling back to the built-in selector.")
+        }
+        Selector::Fzf if launcher::is_command_available("fzf") => {
+            return Ok(ui::select_project_fzf(projects, options, query)?.cloned());
+        }
+        Selector::Fzf => {
+            ui::print_warning("fzf not found in PATH. Falling back to the built-in selector.")
+        }
+        Selector::Numbered => {
+            return Ok(ui::select_project_numbered(projects, options)?.cloned());
+        }
+        Selector::Internal => {}
+    }
+
+    ui::select_project(projects, options, query, rescan)
+}
+
+/// プロジェクトを開く先を決定
+///
+/// `--shell` > エディタを指定するフラグ（`--editor`, `--tmux`）> 設定ファイルの順に優先します。
+fn resolve_launch_target(config: &Config, args: &Args) -> LaunchTarget {
+    if args.shell {
+        LaunchTarget::Shell
+    } else if args.editor.is_some() || args.tmux {
+        LaunchTarget::Editor
+    } else {
+        config.default_launch_target
+    }
+}
+
+/// 選択されたプロジェクトを開く（CLIフラグに応じて動作を切り替え）
+fn open_project(config: &Config, args: &Args, project: &Project) -> Result<()> {
+    // コピーモード: パスをクリップボードにコピーし、色なしで出力
+    if args.copy {
+        let path = project.path.to_string_lossy();
+        clipboard::copy(&path)?;
+        println!("{}", path);
+        return Ok(());
+    }
+
+    // cdモード: シェルで eval できるよう色なしで出力
+    if args.cd {
+        println!("cd {}", quote_path(&project.path));
+        return Ok(());
+    }
+
+    // リモートモード: リモート（origin）のWebページをブラウザで開く
+    if args.open_remote {
+        let Some(url) = launcher::remote_web_url(&project.path) else {
+            ui::print_warning(&format!("No remote 'origin' found for {}", project.name));
+            return Ok(());
+        };
+
+        if args.dry_run {
+            println!("{}", url);
+            return Ok(());
+        }
+
+        println!();
+        println!("Opening {}...", url.cyan().bold());
+        return launcher::open_url(&url);
+    }
+
+    // `.quick-proj.toml` のエディタは設定ファイルより優先する（--editor は除く）
+    let project_editor = trusted_overrides(config, project).and_then(|o| o.editor.as_deref());
+    let launcher = Launcher::with_fallbacks(config.get_editor(
+        args.editor.as_deref().or(project_editor),
+        Some(project.project_type),
+    ))
+    .with_hooks(
+        config.pre_launch_hook.clone(),
+        config.post_launch_hook.clone(),
+    )
+    .with_editor_aliases(config.editor_aliases.clone())
+    .with_terminal(config.terminal_command.clone())
+    .with_terminal_editors(config.terminal_editors.clone());
+
+    // シェルモード: エディタの代わりにプロジェクトのディレクトリでシェルを開く
+    if resolve_launch_target(config, args) == LaunchTarget::Shell {
+        if args.dry_run {
+            println!("{}", launcher.shell_command_line(&project.path)?);
+            return Ok(());
+        }
+
+        println!();
+        println!("Opening a shell in {}...", project.name.cyan().bold());
+        return launcher.launch_shell(&project.path);
+    }
+
+    // ドライラン: 実行予定のコマンドを表示するだけ
+    if args.dry_run {
+        println!("{}", launcher.command_line(&project.path)?);
+        return Ok(());
+    }
+
+    // tmuxモード: プロジェクトごとのセッションを作成・切り替え
+    if args.tmux {
+        return launcher.launch_tmux(
+            project,
+            config.tmux_session_prefix.as_deref(),
+            config.tmux_open_editor,
+        );
+    }
+
+    let editor = launcher.select_editor()?;
+
+    println!();
+    println!(
+        "Opening {} with {}...",
+        project.name.cyan().bold(),
+        editor.green()
+    );
+
+    launcher.launch(project)?;
+
+    // エディタと一緒にターミナルを開く
+    let open_terminal = trusted_overrides(config, project)
+        .and_then(|o| o.open_in_terminal)
+        .unwrap_or(config.open_terminal);
+    if args.terminal || open_terminal {
+        launcher.launch_with_terminal(&project.path)?;
+    }
+
+    Ok(())
+}
+
+/// 起動方法に使ってよい `.quick-proj.toml` の設定を返す
+///
+/// `editor` / `open_in_terminal` はリポジトリ側から任意のコマンドを実行させられるため、
+/// 設定の `trust_project_overrides` が有効な場合だけ使います。
+fn trusted_overrides<'a>(config: &Config, project: &'a Project) -> Option<&'a ProjectOverride> {
+    if !config.trust_project_overrides {
+        return None;
+    }
+    project.overrides.as_ref()
+}
+
+/// パス追加コマンド
+fn cmd_add(paths: &[PathBuf]) -> Result<()> {
+    let mut config = Config::load_raw()?;
+    let (mut added, mut duplicates, mut errors) = (0, 0, 0);
+
+    // 失敗したパスがあっても残りのパスの処理を続ける
+    for path in paths {
+        match config.add_root_path(path) {
+            Ok(true) => {
+                added += 1;
+                let expanded = config::expand_path(path)?;
+                ui::print_success(&format!("Added: {}", expanded.display()));
+
+                // 重なるルートはスキャンが重複するので知らせる
+                for root in config.overlapping_roots(&expanded) {
+                    ui::print_warning(&format!(
+                        "Overlaps with registered root: {}",
+                        root.display()
+                    ));
+                }
+            }
+            Ok(false) => {
+                duplicates += 1;
+                ui::print_warning(&format!("Path is already registered: {}", path.display()));
+            }
+            Err(e) => {
+                errors += 1;
+                ui::print_error(&format!("{}", e));
+            }
+        }
+    }
+
+    if added > 0 {
+        config.save()?;
+    }
+
+    if paths.len() > 1 {
+        println!();
+        ui::print_info(&ui::format_add_summary(added, duplicates, errors));
+    }
+
+    if errors > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// パス削除コマンド
+fn cmd_remove(paths: &[PathBuf], index: Option<usize>, all: bool) -> Result<()> {
+    let mut config = Config::load_raw()?;
+
+    if all {
+        if config.root_paths.is_empty() {
+            ui::print_warning("No root paths configured.");
+            return Ok(());
+        }
+
+        let prompt = format!("Remove all {} root paths?", config.root_paths.len());
+        if !ui::confirm(&prompt)? {
+            ui::print_info("Cancelled.");
+            return Ok(());
+        }
+
+        let count = config.root_paths.len();
+        config.root_paths.clear();
+        config.save()?;
+        ui::print_success(&format!("Removed {} paths", count));
+        return Ok(());
+    }
+
+    if let Some(index) = index {
+        let removed = config.remove_root_path_at(index)?;
+        config.save()?;
+        ui::print_success(&format!("Removed: {}", removed.display()));
+        return Ok(());
+    }
+
+    let (mut removed, mut not_found, mut errors) = (0, 0, 0);
+
+    // 失敗したパスがあっても残りのパスの処理を続ける
+    for path in paths {
+        match config.remove_root_path(path) {
+            Ok(true) => {
+                removed += 1;
+                ui::print_success(&format!("Removed: {}", path.display()));
+            }
+            Ok(false) => {
+                not_found += 1;
+                ui::print_warning(&format!(
+                    "Path not found in configuration: {}",
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                errors += 1;
+                ui::print_error(&format!("{}", e));
+            }
+        }
+    }
+
+    if removed > 0 {
+        config.save()?;
+    }
+
+    if paths.len() > 1 {
+        println!();
+        ui::print_info(&ui::format_remove_summary(removed, not_found, errors));
+    }
+
+    if errors > 0 {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// パス一覧コマンド
+fn cmd_list() -> Result<()> {
+    let config = Config::load()?;
+    ui::print_root_paths(&config.root_paths);
+    Ok(())
+}
+
+/// 存在しないルートパスの削除コマンド
+fn cmd_prune(dry_run: bool) -> Result<()> {
+    let mut config = Config::load_raw()?;
+    if prune_root_paths(&mut config, dry_run)? {
+        config.save()?;
+    }
+    Ok(())
+}
+
+/// 存在しないルートパスを削除して結果を表示（`dry_run` では削除するパスの表示のみ）
+///
+/// ルートパスを削除した場合は `true` を返します。設定の保存は呼び出し側で行います。
+fn prune_root_paths(config: &mut Config, dry_run: bool) -> Result<bool> {
+    if dry_run {
+        let missing = config.missing_root_paths();
+        if missing.is_empty() {
+            ui::print_success("No missing root paths.");
+            return Ok(false);
+        }
+
+        println!("Would remove {} missing root paths:", missing.len());
+        for path in &missing {
+            println!("  {} {}", "✗".red(), path.display());
+        }
+        return Ok(false);
+    }
+
+    let removed = config.prune_root_paths()?;
+    if removed.is_empty() {
+        ui::print_success("No missing root paths.");
+        return Ok(false);
+    }
+
+    for path in &removed {
+        println!("  {} {}", "✗".red(), path.display());
+    }
+    ui::print_success(&format!(
+        "Pruned {} missing root paths ({} remaining)",
+        removed.len(),
+        config.root_paths.len()
+    ));
+
+    Ok(true)
+}
+
+/// 存在しないパスの確認・削除コマンド
+///
+/// ルートパスと、別名・タグを設定したプロジェクト、ピン留めしたプロジェクトのパスを確認します。
+/// 名前変更・移動されたプロジェクトは、ルートパスをスキャンして別名の識別子から新しいパスを探します。
+/// `--apply` を指定した場合のみ、別名・タグを新しいパスに付け替え、存在しないパスを設定とピン留めから削除します。
+fn cmd_cleanup(apply: bool, dry_run: bool) -> Result<()> {
+    let mut config = Config::load_raw()?;
+    let mut state = State::load()?;
+
+    if config.root_paths.is_empty() {
+        ui::print_info("No root paths configured.");
+    } else {
+        println!("{}", "Root paths:".bold());
+        for path in &config.root_paths {
+            if path.exists() {
+                println!("  {} {}", "✓".green(), path.display());
+            } else {
+                println!("  {} {}", "✗".red(), path.display());
+            }
+        }
+    }
+
+    // 名前変更・移動されたプロジェクトは、別名・タグを新しいパスに付け替える（--apply で保存）
+    let renamed = if config.moved_alias_paths().is_empty() {
+        vec![]
+    } else {
+        let (projects, _) = scan_projects(&config, false)?;
+        config.renamed_project_paths(projects.iter().map(|p| p.path.as_path()))
+    };
+    if !renamed.is_empty() {
+        println!();
+        println!("{}", "Renamed projects:".bold());
+        for (old, new) in &renamed {
+            println!("  {} {} → {}", "→".cyan(), old.display(), new.display());
+            config.move_project_path(old, new);
+        }
+    }
+
+    let stale_projects = config.stale_project_paths();
+    if !stale_projects.is_empty() {
+        println!();
+        println!("{}", "Aliases and tags for missing projects:".bold());
+        for path in &stale_projects {
+            println!("  {} {}", "✗".red(), path.display());
+        }
+    }
+
+    let stale_pins = state.stale_pins();
+    if !stale_pins.is_empty() {
+        println!();
+        println!("{}", "Pinned projects that no longer exist:".bold());
+        for path in &stale_pins {
+            println!("  {} {}", "✗".red(), path.display());
+        }
+    }
+
+    let missing_roots = config.missing_root_paths().len();
+    println!();
+    if missing_roots + renamed.len() + stale_projects.len() + stale_pins.len() == 0 {
+        ui::print_success("No stale paths.");
+        return Ok(());
+    }
+
+    if !apply {
+        let summary = format!(
+            "{} missing root paths, {} renamed projects, {} projects with aliases or tags, {} pins",
+            missing_roots,
+            renamed.len(),
+            stale_projects.len(),
+            stale_pins.len()
+        );
+        if dry_run {
+            println!("Would update {}.", summary);
+        } else {
+            ui::print_info(&format!(
+                "Stale: {}. Run `quick-proj cleanup --apply` to update them.",
+                summary
+            ));
+        }
+        return Ok(());
+    }
+
+    // ルートパスは prune と同じ処理で削除し、別名・タグとピン留めは種類ごとに結果を表示する
+    let roots_pruned = prune_root_paths(&mut config, false)?;
+    let pruned_projects = config.prune_project_paths();
+    if roots_pruned || !renamed.is_empty() || !pruned_projects.is_empty() {
+        config.save()?;
+    }
+    if !renamed.is_empty() {
+        ui::print_success(&format!(
+            "Moved aliases and tags for {} renamed projects",
+            renamed.len()
+        ));
+    }
+    if !pruned_projects.is_empty() {
+        ui::print_success(&format!(
+            "Removed aliases and tags for {} missing projects",
+            pruned_projects.len()
+        ));
+    }
+
+    let pruned_pins = state.prune_pins();
+    if pruned_pins > 0 {
+        state.save()?;
+        ui::print_success(&format!("Unpinned {} missing projects", pruned_pins));
+    }
+
+    Ok(())
+}
+
+/// 設定ファイルパス表示コマンド
+fn cmd_config() -> Result<()> {
+    let path = Config::config_path()?;
+    ui::print_config_path(&path);
+
+    match config::config_path_override() {
+        Some((_, source)) => {
+            println!("  {}", format!("(overridden by {})", source).dimmed());
+        }
+        None => {
+            println!(
+                "  {}",
+                format!(
+                    "Set {} or pass --config to use a different file",
+                    config::CONFIG_ENV
+                )
+                .dimmed()
+            );
+        }
+    }
+    println!();
+
+    // 現在の設定を表示
+    let config = Config::load()?;
+    println!("{}", "Current settings:".bold());
+    println!();
+    println!("  Profile:     {}", config::active_profile().cyan());
+    println!(
+        "  Editor:      {}",
+        config
+            .editor
+            .as_ref()
+            .map(|e| e.to_string())
+            .unwrap_or_else(|| "(not set, using $EDITOR or 'code')".to_string())
+            .cyan()
+    );
+    let mut type_editors: Vec<_> = config.type_editors.iter().collect();
+    type_editors.sort();
+    for (project_type, editor) in type_editors {
+        println!("    {:<11}{}", project_type, editor.cyan());
     }
+    let mut editor_aliases: Vec<_> = config.editor_aliases.iter().collect();
+    editor_aliases.sort();
+    for (alias, command) in editor_aliases {
+        println!("    {:<11}{}", format!("{} =", alias), command.cyan());
+    }
+    println!("  Max depth:   {}", config.max_depth.to_string().cyan());
+    println!(
+        "  Markers:     {} items",
+        config.project_markers.len().to_string().cyan()
+    );
+    println!(
+        "  Exclude:     {} patterns, {} paths",
+        (config.exclude_dirs.len() + config.exclude_patterns.len())
+            .to_string()
+            .cyan(),
+        config.exclude_paths.len().to_string().cyan()
+    );
+    println!("  Icons:       {}", config.show_icons.to_string().cyan());
+    println!("  Sort:        {}", config.default_sort.cyan());
+    println!(
+        "  Selector:    {}",
+        format!("{:?}", config.selector).to_lowercase().cyan()
+    );
+    println!(
+        "  Format:      {}",
+        config
+            .display_format
+            .as_deref()
+            .unwrap_or("(not set, using \"{name} [marker] ({short_path})\")")
+            .cyan()
+    );
+    println!(
+        "    {}",
+        format!(
+            "Placeholders: {}",
+            config::format_placeholders(config::DISPLAY_FORMAT_PLACEHOLDERS)
+        )
+        .dimmed()
+    );
+    println!(
+        "  Data dir:    {}",
+        config::data_dir()?.display().to_string().cyan()
+    );
+    println!();
+
+    // シェル連携の設定方法
+    println!("{}", "Shell integration:".bold());
+    println!();
+    println!(
+        "  {}  {}",
+        r#"eval "$(quick-proj init bash)""#.cyan(),
+        "# ~/.bashrc (zsh: init zsh, fish: init fish | source)".dimmed()
+    );
+    println!(
+        "  {}  {}",
+        r#"eval "$(quick-proj --cd --query api)""#.cyan(),
+        "# no selector with --query; fails if nothing matches".dimmed()
+    );
+    println!();
+
+    Ok(())
 }
 
-/// プロジェクト選択モード（メイン機能）
-fn cmd_select(cli_editor: Option<&str>, cli_max_depth: Option<usize>) -> Result<()> {
-    let mut config = Config::load()?;
+/// シェル連携用の関数を出力するコマンド
+///
+/// 出力はそのまま eval / source されるため、色や装飾は付けません。
+fn cmd_init(shell: Shell) -> Result<()> {
+    print!("{}", shell_init::script(shell));
+    Ok(())
+}
 
-    // CLI引数で上書き
-    if let Some(depth) = cli_max_depth {
-        config.max_depth = depth;
+/// 設定ファイル編集コマンド
+fn cmd_edit_config(args: &Args) -> Result<()> {
+    let path = Config::config_path()?;
+    let config = Config::load()?;
+
+    if !path.exists() {
+        config.save()?;
+        ui::print_info(&format!("Created config file: {}", path.display()));
     }
 
-    // ルートパスが未設定の場合
+    Launcher::with_fallbacks(config.get_editor(args.editor.as_deref(), None))
+        .with_editor_aliases(config.editor_aliases.clone())
+        .edit_file(&path)?;
+
+    // エディタ終了後に読み込めるか確認する
+    match Config::load() {
+        Ok(_) => ui::print_success("Config file is valid"),
+        Err(e) => ui::print_warning(&format!("Config file has errors: {:#}", e)),
+    }
+
+    Ok(())
+}
+
+/// 設定のエクスポート・インポートコマンド
+fn cmd_config_action(action: &ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Export { format, output } => cmd_export(*format, output.as_deref()),
+        ConfigAction::Import {
+            file,
+            overwrite,
+            replace,
+        } => cmd_import(file, *overwrite, *replace),
+        ConfigAction::Validate => cmd_validate(),
+        ConfigAction::Migrate => cmd_migrate(),
+    }
+}
+
+/// 設定ファイル移行コマンド
+fn cmd_migrate() -> Result<()> {
+    let path = Config::config_path()?;
+    if !path.exists() {
+        ui::print_warning(&format!("No config file at {}", path.display()));
+        return Ok(());
+    }
+
+    match Config::migrate_file(&path)? {
+        Some(from) => ui::print_success(&format!(
+            "Migrated {} from version {} to {} (backup: {}.bak)",
+            path.display(),
+            from,
+            config::CONFIG_VERSION,
+            path.display()
+        )),
+        None => ui::print_info("Config is already up to date."),
+    }
+
+    Ok(())
+}
+
+/// 設定エクスポートコマンド
+fn cmd_export(format: ConfigFormat, output: Option<&Path>) -> Result<()> {
+    let content = Config::load()?.export(format)?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, &content)
+                .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+            ui::print_success(&format!("Exported config to {}", path.display()));
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// 設定インポートコマンド
+fn cmd_import(file: &Path, overwrite: bool, replace: bool) -> Result<()> {
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read config file: {}", file.display()))?;
+    let imported = ImportedConfig::parse(&content, ConfigFormat::from_path(file))
+        .with_context(|| format!("Invalid config file: {}", file.display()))?;
+
+    let mode = if replace {
+        ImportMode::Replace
+    } else if overwrite {
+        ImportMode::Overwrite
+    } else {
+        ImportMode::Merge
+    };
+
+    let mut config = Config::load_raw()?;
+    let report = config.merge(imported, mode);
+    config.save()?;
+
+    for path in &report.skipped {
+        ui::print_warning(&format!("Skipped missing root path: {}", path.display()));
+    }
+
+    ui::print_success(&format!(
+        "Imported {} ({} new root paths{})",
+        file.display(),
+        report.added,
+        match mode {
+            ImportMode::Merge => "",
+            ImportMode::Overwrite => ", settings overwritten",
+            ImportMode::Replace => ", config replaced",
+        }
+    ));
+
+    Ok(())
+}
+
+/// 設定検証コマンド
+fn cmd_validate() -> Result<()> {
+    let config = Config::load_raw()?;
+    let diagnostics = doctor::validate_config(&config);
+    ui::print_diagnostics(&diagnostics);
+
+    if doctor::has_errors(&diagnostics) {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// スキャンコマンド（デバッグ用）
+fn cmd_scan(args: &Args, format: ListFormat) -> Result<()> {
+    let mut config = Config::load()?;
+
+    apply_cli_overrides(&mut config, args);
+    apply_temporary_roots(&mut config, args)?;
+
     if config.root_paths.is_empty() {
         ui::print_warning("No root paths configured.");
-        println!();
-        println!("Add a search path first:");
-        println!("  {} {}", "quick-proj add".cyan(), "~/src".dimmed());
-        println!("  {} {}", "quick-proj add".cyan(), "~/projects".dimmed());
         return Ok(());
     }
 
-    // スキャン開始
+    if !warn_missing_roots(&config, args)? {
+        return Ok(());
+    }
+
+    // キャッシュを使わずにすべて走査し直す（キャッシュは最新の結果で更新）
     let start = Instant::now();
-    let scanner = Scanner::from_config(&config);
-    let projects = scanner.scan(&config.root_paths)?;
+    let (mut projects, mut errors) = scan_projects(&config, true)?;
     let elapsed = start.elapsed().as_millis();
+    retain_unwarned_errors(&mut errors, &config);
 
-    if projects.is_empty() {
-        ui::print_warning("No projects found in registered paths.");
-        println!();
-        println!("Check if your paths contain projects with markers like:");
-        println!("  .git, Cargo.toml, package.json, go.mod, etc.");
-        return Ok(());
-    }
+    apply_project_filters(&mut projects, &config, args)?;
 
-    // サマリー表示
-    ui::print_scan_summary(&projects, elapsed);
+    sort_projects(&mut projects, &config, args)?;
 
-    // プロジェクト選択UI
-    match ui::select_project(&projects)? {
-        Some(project) => {
-            let editor = config.get_editor(cli_editor);
-            let launcher = Launcher::new(&editor);
+    match format {
+        ListFormat::List => {
+            ui::print_project_list(&projects, &DisplayOptions::from_config(&config));
+            println!("Scan completed in {}ms", elapsed.to_string().green());
+        }
+        // 表はそのまま貼り付けやパイプで使えるよう、前後に何も出力しない
+        ListFormat::Table => {
+            ui::print_project_table(&projects);
+            return Ok(());
+        }
+        // CSVを壊さないよう、スキャンエラーの警告も出力しない
+        ListFormat::Csv => {
+            ui::print_project_csv(&projects);
+            return Ok(());
+        }
+    }
+    ui::print_scan_errors(&errors, args.verbose);
 
-            println!();
+    Ok(())
+}
+
+/// プロファイル管理コマンド
+fn cmd_profile(action: &ProfileAction) -> Result<()> {
+    match action {
+        ProfileAction::List => {
+            let active = config::active_profile();
+            ui::print_profiles(&config::list_profiles()?, &active);
+        }
+        ProfileAction::Create { name } => {
+            let path = config::create_profile(name)?;
+            ui::print_success(&format!("Created profile '{}': {}", name, path.display()));
             println!(
-                "Opening {} with {}...",
-                project.name.cyan().bold(),
-                editor.green()
+                "Use it with: {}",
+                format!("quick-proj --profile {}", name).cyan()
             );
+        }
+        ProfileAction::Delete { name } => {
+            config::delete_profile(name)?;
+            ui::print_success(&format!("Deleted profile '{}'", name));
+        }
+    }
+
+    Ok(())
+}
 
-            launcher.launch(&project.path)?;
+/// 除外パターン管理コマンド
+fn cmd_exclude(action: &ExcludeAction) -> Result<()> {
+    let mut config = Config::load_raw()?;
+
+    match action {
+        ExcludeAction::Add { pattern } => {
+            if config.add_exclude_pattern(pattern)? {
+                config.save()?;
+                ui::print_success(&format!("Added exclude pattern: {}", pattern));
+            } else {
+                ui::print_warning("Pattern is already registered.");
+            }
         }
-        None => {
-            println!();
-            println!("{}", "Selection cancelled.".dimmed());
+        ExcludeAction::Remove { pattern } => {
+            if config.remove_exclude_pattern(pattern) {
+                config.save()?;
+                ui::print_success(&format!("Removed exclude pattern: {}", pattern));
+            } else {
+                ui::print_warning("Pattern not found in configuration.");
+            }
         }
     }
 
     Ok(())
 }
 
-/// パス追加コマンド
-fn cmd_add(path: &std::path::Path) -> Result<()> {
-    let mut config = Config::load()?;
+/// 別名管理コマンド
+///
+/// 引数を省略した場合は登録済みの別名を一覧表示します。
+fn cmd_alias(action: Option<&AliasAction>, path: Option<&Path>, alias: Option<&str>) -> Result<()> {
+    let mut config = Config::load_raw()?;
 
-    match config.add_root_path(path) {
-        Ok(true) => {
+    match (action, path, alias) {
+        (Some(AliasAction::Remove { path }), _, _) => {
+            if config.remove_project_alias(path)? {
+                config.save()?;
+                ui::print_success(&format!("Removed alias for: {}", path.display()));
+            } else {
+                ui::print_warning("Alias not found in configuration.");
+            }
+        }
+        (None, Some(path), Some(alias)) => {
+            let key = config.set_project_alias(path, alias)?;
             config.save()?;
-            let expanded = config::expand_path(path)?;
-            ui::print_success(&format!("Added: {}", expanded.display()));
+            ui::print_success(&format!(
+                "Set alias '{}' for: {}",
+                alias.trim(),
+                key.display()
+            ));
+        }
+        _ => {
+            if config.project_aliases.is_empty() {
+                ui::print_info("No aliases configured.");
+                return Ok(());
+            }
+
+            let mut aliases: Vec<_> = config.project_aliases.iter().collect();
+            aliases.sort();
+            for (path, alias) in aliases {
+                println!("  {} {}", alias.cyan(), path.display().to_string().dimmed());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// タグ管理コマンド
+fn cmd_tag(action: &TagAction) -> Result<()> {
+    let mut config = Config::load_raw()?;
+
+    match action {
+        TagAction::Add { path, tag } => {
+            let tag = canonicalize_tag(tag)?;
+            if config.add_project_tag(path, &tag)? {
+                config.save()?;
+                ui::print_success(&format!("Added tag '{}' to: {}", tag, path.display()));
+            } else {
+                ui::print_warning(&format!(
+                    "Tag '{}' already set for: {}",
+                    tag,
+                    path.display()
+                ));
+            }
         }
-        Ok(false) => {
-            ui::print_warning("Path is already registered.");
+        TagAction::Remove { path, tag } => {
+            let tag = canonicalize_tag(tag)?;
+            if config.remove_project_tag(path, &tag)? {
+                config.save()?;
+                ui::print_success(&format!("Removed tag '{}' from: {}", tag, path.display()));
+            } else {
+                ui::print_warning("Tag not found in configuration.");
+            }
         }
-        Err(e) => {
-            ui::print_error(&format!("{}", e));
-            return Err(e);
+        TagAction::List { path: Some(path) } => {
+            let tags = project_tags(&config.project_tags, &config::expand_path(path)?);
+            if tags.is_empty() {
+                ui::print_info(&format!("No tags for: {}", path.display()));
+                return Ok(());
+            }
+
+            for tag in tags {
+                println!("  {}", tag.cyan());
+            }
+        }
+        TagAction::List { path: None } => {
+            if config.project_tags.is_empty() {
+                ui::print_info("No tags configured.");
+                return Ok(());
+            }
+
+            let mut tagged: Vec<_> = config.project_tags.iter().collect();
+            tagged.sort();
+            for (path, tags) in tagged {
+                println!(
+                    "  {} {}",
+                    format!("[{}]", tags.join(", ")).cyan(),
+                    path.display().to_string().dimmed()
+                );
+            }
         }
     }
 
     Ok(())
 }
 
-/// パス削除コマンド
-fn cmd_remove(path: &std::path::Path) -> Result<()> {
+/// 統計表示コマンド
+fn cmd_stats(args: &Args) -> Result<()> {
     let mut config = Config::load()?;
 
-    if config.remove_root_path(path)? {
-        config.save()?;
-        ui::print_success(&format!("Removed: {}", path.display()));
-    } else {
-        ui::print_warning("Path not found in configuration.");
+    apply_cli_overrides(&mut config, args);
+    apply_temporary_roots(&mut config, args)?;
+
+    if config.root_paths.is_empty() {
+        ui::print_warning("No root paths configured.");
+        return Ok(());
     }
 
+    let start = Instant::now();
+    let (mut projects, errors, from_cache) = scan_projects_cached(&config, false)?;
+    let elapsed = start.elapsed().as_millis();
+
+    apply_project_filters(&mut projects, &config, args)?;
+
+    let stats = ProjectStats::compute(&projects, &config.root_paths);
+    ui::print_stats(&stats, elapsed, from_cache);
+    ui::print_scan_errors(&errors, args.verbose);
+
     Ok(())
 }
 
-/// パス一覧コマンド
-fn cmd_list() -> Result<()> {
-    let config = Config::load()?;
-    ui::print_root_paths(&config.root_paths);
+/// ターミナル設定コマンド
+fn cmd_set_terminal(command: &str) -> Result<()> {
+    let mut config = Config::load_raw()?;
+
+    config.terminal_command = Some(command.to_string());
+    config.save()?;
+
+    ui::print_success(&format!("Terminal command set to: {}", command.cyan()));
+
     Ok(())
 }
 
-/// 設定ファイルパス表示コマンド
-fn cmd_config() -> Result<()> {
-    let path = Config::config_path()?;
-    ui::print_config_path(&path);
+/// スキャンスレッド数設定コマンド
+fn cmd_set_threads(threads: usize) -> Result<()> {
+    let mut config = Config::load_raw()?;
 
-    // 現在の設定を表示
-    let config = Config::load()?;
-    println!("{}", "Current settings:".bold());
-    println!();
-    println!(
-        "  Editor:      {}",
-        config
-            .editor
-            .as_deref()
-            .unwrap_or("(not set, using $EDITOR or 'code')")
-            .cyan()
-    );
-    println!("  Max depth:   {}", config.max_depth.to_string().cyan());
-    println!(
-        "  Markers:     {} items",
-        config.project_markers.len().to_string().cyan()
-    );
-    println!(
-        "  Exclude:     {} patterns",
-        config.exclude_dirs.len().to_string().cyan()
-    );
-    println!();
+    config.scan_threads = (threads > 0).then_some(threads);
+    config.save()?;
+
+    match config.scan_threads {
+        Some(threads) => ui::print_success(&format!(
+            "Scan threads set to: {}",
+            threads.to_string().cyan()
+        )),
+        None => ui::print_success("Scan threads reset to default (all cores)"),
+    }
 
     Ok(())
 }
 
-/// スキャンコマンド（デバッグ用）
-fn cmd_scan(cli_max_depth: Option<usize>) -> Result<()> {
-    let mut config = Config::load()?;
+/// `--log-file` または設定の `log_file` が指定されていればログ出力を開始する
+///
+/// ログを出力できなくてもコマンド自体は続行します。
+/// 失敗は標準エラー出力で知らせ、cd・コピーモードでは表示しません。
+fn init_logging(args: &Args, config: &Config) {
+    let Some(path) = args.log_file.as_ref().or(config.log_file.as_ref()) else {
+        return;
+    };
 
-    if let Some(depth) = cli_max_depth {
-        config.max_depth = depth;
+    if let Err(e) = logging::init(path, &config.log_level) {
+        if !args.prints_path() {
+            ui::print_warning(&format!("{:#}", e));
+        }
+    }
+}
+
+/// 設定診断コマンド
+///
+/// エラーが1つでもあれば終了コード1で終了します。
+fn cmd_doctor() -> Result<()> {
+    let diagnostics = doctor::run_checks();
+    ui::print_diagnostics(&diagnostics);
+
+    if doctor::has_errors(&diagnostics) {
+        std::process::exit(1);
     }
 
+    Ok(())
+}
+
+/// 監視コマンド
+///
+/// ルートパスを監視し、プロジェクトの追加・削除を表示し続けます。
+fn cmd_watch(args: &Args, exec: Option<&str>) -> Result<()> {
+    let mut config = Config::load()?;
+
+    apply_cli_overrides(&mut config, args);
+    apply_temporary_roots(&mut config, args)?;
+
     if config.root_paths.is_empty() {
         ui::print_warning("No root paths configured.");
         return Ok(());
     }
 
-    let start = Instant::now();
-    let scanner = Scanner::from_config(&config);
-    let projects = scanner.scan(&config.root_paths)?;
-    let elapsed = start.elapsed().as_millis();
-
-    ui::print_project_list(&projects);
-    println!("Scan completed in {}ms", elapsed.to_string().green());
+    ui::print_success(&format!(
+        "Watching {} root paths for project changes (Ctrl+C to stop)",
+        config.root_paths.len()
+    ));
 
-    Ok(())
+    watch::run(&config, exec, ui::print_project_diff)
 }
 
 /// エディタ設定コマンド
-fn cmd_set_editor(editor: &str) -> Result<()> {
-    let mut config = Config::load()?;
+fn cmd_set_editor(editor: &str, project_type: Option<ProjectType>) -> Result<()> {
+    let mut config = Config::load_raw()?;
 
     // エディタの存在チェック
-    let launcher = Launcher::new(editor);
+    let launcher = Launcher::new(editor).with_editor_aliases(config.editor_aliases.clone());
     if !launcher.check_editor_available() {
         ui::print_warning(&format!(
             "Editor '{}' not found in PATH. Setting anyway.",
@@ -207,18 +1859,122 @@ fn cmd_set_editor(editor: &str) -> Result<()> {
         ));
     }
 
-    config.set_editor(editor);
-    config.save()?;
+    match project_type {
+        Some(project_type) => {
+            config.set_type_editor(project_type, editor);
+            config.save()?;
+
+            ui::print_success(&format!(
+                "Editor for {} projects set to: {}",
+                project_type.as_str(),
+                editor.cyan()
+            ));
+        }
+        None => {
+            config.set_editor(editor);
+            config.save()?;
 
-    ui::print_success(&format!("Default editor set to: {}", editor.cyan()));
+            ui::print_success(&format!("Default editor set to: {}", editor.cyan()));
+        }
+    }
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_main_exists() {
         // main関数が存在することを確認
     }
+
+    #[test]
+    fn test_read_roots() {
+        let input = "/tmp/a\n\n  /tmp/b  \n";
+        let roots = read_roots(input.as_bytes()).unwrap();
+        assert_eq!(
+            roots,
+            vec![PathBuf::from("/tmp/a"), PathBuf::from("/tmp/b")]
+        );
+    }
+
+    #[test]
+    fn test_trusted_overrides_requires_opt_in() {
+        let mut project = Project::from_path(Path::new("/tmp/app"));
+        project.overrides = Some(ProjectOverride {
+            editor: Some("evil".to_string()),
+            ..Default::default()
+        });
+        let mut config = Config::default();
+        assert!(trusted_overrides(&config, &project).is_none());
+
+        config.trust_project_overrides = true;
+        let overrides = trusted_overrides(&config, &project).unwrap();
+        assert_eq!(overrides.editor.as_deref(), Some("evil"));
+    }
+
+    #[test]
+    fn test_no_projects_message() {
+        use clap::Parser;
+
+        let config = Config {
+            root_paths: vec![PathBuf::from("/tmp/work")],
+            ..Config::default()
+        };
+        let message = |argv: &[&str]| no_projects_message(&config, &Args::parse_from(argv));
+
+        assert_eq!(
+            message(&["quick-proj", "--here"]),
+            "No projects found in /tmp/work."
+        );
+        assert_eq!(
+            message(&["quick-proj", "--stdin-roots"]),
+            "No projects found in the root paths given on stdin."
+        );
+        assert_eq!(
+            message(&["quick-proj"]),
+            "No projects found in registered paths."
+        );
+    }
+
+    #[test]
+    fn test_pick_match() {
+        let config = Config::default();
+        let projects: Vec<Project> = ["/src/api-server", "/src/api-client", "/src/web"]
+            .iter()
+            .map(|path| Project::from_path(Path::new(path)))
+            .collect();
+
+        let project = pick_match(&config, &projects, "web", false).unwrap();
+        assert_eq!(project.name, "web");
+
+        // 同程度に一致するものが複数ある場合は --first がなければエラー
+        let err = pick_match(&config, &projects, "api", false).unwrap_err();
+        assert!(err.to_string().contains("--first"));
+        assert!(pick_match(&config, &projects, "api", true).is_ok());
+
+        let err = pick_match(&config, &projects, "zzz", true).unwrap_err();
+        assert_eq!(err.to_string(), "No projects match 'zzz'");
+    }
+
+    #[test]
+    fn test_truncate_to_limit() {
+        let mut projects: Vec<Project> = ["/tmp/a", "/tmp/b", "/tmp/c"]
+            .iter()
+            .map(|path| Project::from_path(Path::new(path)))
+            .collect();
+
+        // 上限がない、または件数が上限以下なら切り詰めない
+        assert_eq!(truncate_to_limit(&mut projects, None), None);
+        assert_eq!(truncate_to_limit(&mut projects, Some(3)), None);
+        assert_eq!(projects.len(), 3);
+
+        // 並び順の上位だけを残す
+        let message = truncate_to_limit(&mut projects, Some(2));
+        assert_eq!(message.as_deref(), Some("Showing 2 of 3 projects"));
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
 }
@@ -0,0 +1,171 @@
+//! READMEプレビューモジュール
+//!
+//! 選択UIのプレビューパネルに表示するため、プロジェクトのREADMEを読み込み、
+//! 端末で読みやすいようにMarkdownの装飾を取り除きます。
+//! Nix flake の場合は、flake の説明も取得します。
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// READMEとして扱うファイル名（優先順）
+const README_NAMES: &[&str] = &["README.md", "README.rst", "README.txt", "README"];
+
+/// プレビューに表示する最大行数
+const PREVIEW_LINES: usize = 40;
+
+/// プロジェクトのREADMEの先頭を読み込む
+///
+/// READMEが見つからない場合や読み込めない場合は `None` を返します。
+pub fn read_readme(project_path: &Path) -> Option<String> {
+    let path = README_NAMES
+        .iter()
+        .map(|name| project_path.join(name))
+        .find(|path| path.is_file())?;
+
+    let file = File::open(path).ok()?;
+    let lines: Vec<String> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .take(PREVIEW_LINES)
+        .map(|line| strip_markdown(&line))
+        .collect();
+
+    Some(lines.join("\n"))
+}
+
+/// Nix flake の説明（`nix flake metadata --json` の `description`）を取得する
+///
+/// `flake.nix` がない場合や、nix が見つからない・評価に失敗した場合は `None` を返します。
+/// 時間がかかることがあるため、選択UIでは別スレッドで呼び出します。
+pub fn flake_description(project_path: &Path) -> Option<String> {
+    if !project_path.join("flake.nix").is_file() {
+        return None;
+    }
+
+    let output = Command::new("nix")
+        .args(["--extra-experimental-features", "nix-command flakes"])
+        .args(["flake", "metadata", "--json", "--no-write-lock-file"])
+        .arg(project_path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    parse_flake_description(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// `nix flake metadata --json` の出力から説明を取り出す
+fn parse_flake_description(json: &str) -> Option<String> {
+    let metadata: serde_json::Value = serde_json::from_str(json).ok()?;
+    let description = metadata.get("description")?.as_str()?.trim();
+    (!description.is_empty()).then(|| description.to_string())
+}
+
+/// 1行からMarkdownの見出し記号と強調記号を取り除く
+///
+/// 単語中の `_`（snake_case など）や行頭の箇条書きの `*` は残します。
+fn strip_markdown(line: &str) -> String {
+    let trimmed = line.trim_start();
+
+    // 見出し（"# Title" → "Title"）
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    let line =
+        if (1..=6).contains(&hashes) && trimmed[hashes..].chars().next().is_none_or(|c| c == ' ') {
+            trimmed[hashes..].trim_start()
+        } else {
+            line
+        };
+
+    // 箇条書きの記号は強調記号として扱わない
+    let (bullet, body) = match line.trim_start().strip_prefix("* ") {
+        Some(rest) => (&line[..line.len() - rest.len()], rest),
+        None => ("", line),
+    };
+
+    let chars: Vec<char> = body.chars().collect();
+    let mut result = String::from(bullet);
+
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '`' => continue,
+            '*' | '_' => {
+                let prev = i.checked_sub(1).map(|j| chars[j]);
+                let next = chars.get(i + 1).copied();
+                let inside_word = prev.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric);
+                if inside_word {
+                    result.push(c);
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_strip_markdown() {
+        assert_eq!(strip_markdown("# quick-proj"), "quick-proj");
+        assert_eq!(strip_markdown("### Usage"), "Usage");
+        assert_eq!(strip_markdown("#hashtag"), "#hashtag");
+        assert_eq!(strip_markdown("**fast** and _simple_"), "fast and simple");
+        assert_eq!(strip_markdown("run `cargo build`"), "run cargo build");
+        assert_eq!(
+            strip_markdown("uses snake_case names"),
+            "uses snake_case names"
+        );
+        assert_eq!(
+            strip_markdown("* item with *emphasis*"),
+            "* item with emphasis"
+        );
+    }
+
+    #[test]
+    fn test_read_readme_priority_and_limit() {
+        let dir = tempdir().unwrap();
+        assert!(read_readme(dir.path()).is_none());
+
+        fs::write(dir.path().join("README"), "plain").unwrap();
+        assert_eq!(read_readme(dir.path()).as_deref(), Some("plain"));
+
+        let long: String = (1..=100).map(|i| format!("line {}\n", i)).collect();
+        fs::write(dir.path().join("README.md"), format!("# Title\n{}", long)).unwrap();
+
+        let preview = read_readme(dir.path()).unwrap();
+        let lines: Vec<&str> = preview.lines().collect();
+        assert_eq!(lines.len(), PREVIEW_LINES);
+        assert_eq!(lines[0], "Title");
+    }
+
+    #[test]
+    fn test_parse_flake_description() {
+        let json = r#"{"description":"  A dev shell for the API  ","path":"/nix/store/abc"}"#;
+        assert_eq!(
+            parse_flake_description(json).as_deref(),
+            Some("A dev shell for the API")
+        );
+        assert_eq!(parse_flake_description(r#"{"description":""}"#), None);
+        assert_eq!(
+            parse_flake_description(r#"{"path":"/nix/store/abc"}"#),
+            None
+        );
+        assert_eq!(parse_flake_description("not json"), None);
+    }
+
+    #[test]
+    fn test_flake_description_requires_flake() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("shell.nix"), "{}").unwrap();
+        assert_eq!(flake_description(dir.path()), None);
+    }
+}
@@ -0,0 +1,252 @@
+//! ランキングモジュール
+//!
+//! 起動履歴の頻度と新しさを組み合わせたスコアで、プロジェクトを並び替えます。
+//!
+//! スコアは `frequency_weight * ln(1 + 開いた回数) + 0.5 ^ (経過日数 / recency_half_life_days)` です。
+//! 開いた直後は新しさの項（最大 1.0）で上位に来て、数日かけて薄れます。
+//! よく開くプロジェクトは頻度の項で継続的に上位に残ります。同点は名前順です。
+//!
+//! `frecency` の並び順は `開いた回数 / 最後に開いてからの経過秒数` の単純なスコアで並べます。
+
+use crate::config::RankingConfig;
+use crate::history::{History, ProjectHistory};
+use crate::scanner::Project;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 1日の秒数
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// スコアを付けたプロジェクト
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredProject<'a> {
+    /// 対象のプロジェクト
+    pub project: &'a Project,
+    /// スコア（大きいほど上位。履歴がなければ 0）
+    pub score: f64,
+}
+
+/// 起動履歴からスコアを算出
+pub fn score(entry: Option<&ProjectHistory>, weights: &RankingConfig, now: i64) -> f64 {
+    let Some(entry) = entry else {
+        return 0.0;
+    };
+
+    let frequency = weights.frequency_weight * (entry.open_count as f64).ln_1p();
+
+    let age_days = (now - entry.last_opened).max(0) as f64 / SECONDS_PER_DAY;
+    let recency = if weights.recency_half_life_days > 0.0 {
+        0.5_f64.powf(age_days / weights.recency_half_life_days)
+    } else {
+        0.0
+    };
+
+    frequency + recency
+}
+
+/// 起動回数を最後に開いてからの経過秒数（最小 1）で割ったスコアを算出
+pub fn frecency_score(entry: Option<&ProjectHistory>, now: i64) -> f64 {
+    entry.map_or(0.0, |entry| {
+        entry.open_count as f64 / (now - entry.last_opened).max(1) as f64
+    })
+}
+
+/// プロジェクトにスコアを付け、スコアの高い順（同点は名前順）に並べる
+pub fn rank<'a>(
+    projects: &'a [Project],
+    history: &History,
+    weights: &RankingConfig,
+    now: i64,
+) -> Vec<ScoredProject<'a>> {
+    rank_by(projects, history, |entry| score(entry, weights, now))
+}
+
+/// `frecency_score` でスコアを付け、スコアの高い順（同点は名前順）に並べる
+pub fn rank_frecency<'a>(
+    projects: &'a [Project],
+    history: &History,
+    now: i64,
+) -> Vec<ScoredProject<'a>> {
+    rank_by(projects, history, |entry| frecency_score(entry, now))
+}
+
+/// 起動履歴から算出したスコアの高い順（同点は名前順）に並べる
+fn rank_by<'a>(
+    projects: &'a [Project],
+    history: &History,
+    score: impl Fn(Option<&ProjectHistory>) -> f64,
+) -> Vec<ScoredProject<'a>> {
+    let entries: HashMap<&Path, &ProjectHistory> = history
+        .projects
+        .iter()
+        .map(|entry| (entry.path.as_path(), entry))
+        .collect();
+
+    let mut scored: Vec<ScoredProject> = projects
+        .iter()
+        .map(|project| ScoredProject {
+            project,
+            score: score(entries.get(project.path.as_path()).copied()),
+        })
+        .collect();
+
+    scored.sort_by(|a, b| {
+        b.score.total_cmp(&a.score).then_with(|| {
+            a.project
+                .name
+                .to_lowercase()
+                .cmp(&b.project.name.to_lowercase())
+        })
+    });
+    scored
+}
+
+/// プロジェクト一覧をスコア順に並び替える
+pub fn sort_by_rank(
+    projects: &mut Vec<Project>,
+    history: &History,
+    weights: &RankingConfig,
+    now: i64,
+) {
+    let ranked = into_projects(rank(projects, history, weights, now));
+    *projects = ranked;
+}
+
+/// プロジェクト一覧を `frecency_score` の順に並び替える
+pub fn sort_by_frecency(projects: &mut Vec<Project>, history: &History, now: i64) {
+    let ranked = into_projects(rank_frecency(projects, history, now));
+    *projects = ranked;
+}
+
+/// スコア順に並べたプロジェクトを複製して取り出す
+fn into_projects(scored: Vec<ScoredProject>) -> Vec<Project> {
+    scored
+        .into_iter()
+        .map(|scored| scored.project.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::ProjectType;
+    use std::path::PathBuf;
+
+    const NOW: i64 = 1_700_000_000;
+    const DAY: i64 = 86_400;
+
+    fn project(name: &str) -> Project {
+        Project {
+            path: PathBuf::from("/src").join(name),
+            root: PathBuf::from("/src"),
+            name: name.to_string(),
+            marker: ".git".to_string(),
+            project_type: ProjectType::Generic,
+            modified: None,
+            overrides: None,
+        }
+    }
+
+    fn opened(name: &str, open_count: u64, days_ago: i64) -> ProjectHistory {
+        ProjectHistory {
+            path: PathBuf::from("/src").join(name),
+            open_count,
+            last_opened: NOW - days_ago * DAY,
+        }
+    }
+
+    fn ranked_names(history: &History, now: i64) -> Vec<String> {
+        let projects = vec![
+            project("delta"),
+            project("Alpha"),
+            project("charlie"),
+            project("bravo"),
+        ];
+        rank(&projects, history, &RankingConfig::default(), now)
+            .into_iter()
+            .map(|scored| scored.project.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_without_history_is_alphabetical() {
+        assert_eq!(
+            ranked_names(&History::default(), NOW),
+            vec!["Alpha", "bravo", "charlie", "delta"]
+        );
+    }
+
+    #[test]
+    fn test_rank_recent_access_boost_fades() {
+        // charlie は1か月前までに何度か開いた。delta は今日1回だけ開いた
+        let history = History {
+            projects: vec![opened("charlie", 5, 30), opened("delta", 1, 0)],
+        };
+
+        assert_eq!(
+            ranked_names(&history, NOW),
+            vec!["delta", "charlie", "Alpha", "bravo"]
+        );
+
+        // 1週間たつと新しさの効果は薄れ、頻度の高い charlie が上に来る
+        assert_eq!(
+            ranked_names(&history, NOW + 7 * DAY),
+            vec!["charlie", "delta", "Alpha", "bravo"]
+        );
+    }
+
+    #[test]
+    fn test_rank_frequency_breaks_equal_recency() {
+        let history = History {
+            projects: vec![
+                opened("bravo", 3, 2),
+                opened("delta", 10, 2),
+                opened("Alpha", 3, 2),
+            ],
+        };
+
+        assert_eq!(
+            ranked_names(&history, NOW),
+            vec!["delta", "Alpha", "bravo", "charlie"]
+        );
+    }
+
+    #[test]
+    fn test_rank_frecency() {
+        let history = History {
+            projects: vec![
+                opened("charlie", 10, 5),
+                opened("bravo", 3, 1),
+                opened("delta", 1, 1),
+            ],
+        };
+        let projects = vec![
+            project("delta"),
+            project("Alpha"),
+            project("charlie"),
+            project("bravo"),
+        ];
+
+        let names: Vec<&str> = rank_frecency(&projects, &history, NOW)
+            .into_iter()
+            .map(|scored| scored.project.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["bravo", "charlie", "delta", "Alpha"]);
+
+        // 開いた直後でも 0 で割らない
+        assert_eq!(frecency_score(Some(&opened("app", 2, 0)), NOW), 2.0);
+        assert_eq!(frecency_score(None, NOW), 0.0);
+    }
+
+    #[test]
+    fn test_score_ignores_recency_without_half_life() {
+        let weights = RankingConfig {
+            frequency_weight: 1.0,
+            recency_half_life_days: 0.0,
+        };
+        let entry = opened("app", 1, 0);
+
+        assert_eq!(score(None, &weights, NOW), 0.0);
+        assert!((score(Some(&entry), &weights, NOW) - 2.0_f64.ln()).abs() < 1e-9);
+    }
+}
@@ -4,107 +4,968 @@
 //! `ignore` クレートを使用して .gitignore を考慮し、
 //! `rayon` で並列処理を行います。
 
-use crate::config::Config;
-use anyhow::Result;
-use ignore::WalkBuilder;
+use crate::config::{self, canonicalize_tag, data_path, is_symlink, project_tags, Config};
+use anyhow::{Context, Result};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use glob::Pattern;
+use ignore::{DirEntry, WalkBuilder};
 use rayon::prelude::*;
-use std::collections::HashSet;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+
+/// スキャンキャッシュのファイル名
+const SCAN_CACHE_FILE_NAME: &str = "scan-cache.json";
+
+/// 検索語がプロジェクト名に一致した場合の加点（パスだけの一致より上位にする）
+const NAME_MATCH_BONUS: i64 = 50;
+/// 検索語がプロジェクト名と完全に一致した場合の加点
+const EXACT_NAME_BONUS: i64 = 1000;
+/// 最も一致度の高いプロジェクトを選択UIなしで選ぶのに必要な、2番目との一致度の差
+const CLEAR_WIN_MARGIN: i64 = NAME_MATCH_BONUS;
+
+/// プロジェクトごとの設定ファイル名（プロジェクトのルートに置く）
+pub const PROJECT_OVERRIDE_FILE_NAME: &str = ".quick-proj.toml";
+
+/// Cargoワークスペースのメンバークレートを示すマーカー
+pub const CARGO_WORKSPACE_MEMBER_MARKER: &str = "Cargo.toml (workspace member)";
+/// CI設定のマーカーと、その存在を確認するglobパターン（ディレクトリからの相対パス）
+pub const CI_MARKERS: &[(&str, &str)] = &[
+    (".github/workflows", ".github/workflows/*.y*ml"),
+    (".gitlab-ci.yml", ".gitlab-ci.yml"),
+    (".circleci/config.yml", ".circleci/config.yml"),
+    ("Jenkinsfile", "Jenkinsfile"),
+];
+
+/// npm / pnpm / Yarn ワークスペースのメンバーパッケージを示すマーカー
+pub const NPM_WORKSPACE_MEMBER_MARKER: &str = "package.json (workspace member)";
+
+/// git worktree で作成した作業ツリーを示すマーカー
+pub const GIT_WORKTREE_MARKER: &str = ".git (worktree)";
 
 /// スキャンされたプロジェクト情報
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Project {
     /// プロジェクトのパス
     pub path: PathBuf,
+    /// 検出元のルートパス
+    pub root: PathBuf,
     /// プロジェクト名（ディレクトリ名）
     pub name: String,
     /// 検出されたマーカー
     pub marker: String,
+    /// マーカーから判定したプロジェクト種別
+    pub project_type: ProjectType,
+    /// ディレクトリ自体の最終更新日時（配下のファイルまでは見ない。取得できない場合は `None`）
+    #[serde(default)]
+    pub modified: Option<SystemTime>,
+    /// プロジェクトに置かれた `.quick-proj.toml` の設定
+    #[serde(default)]
+    pub overrides: Option<ProjectOverride>,
+}
+
+/// プロジェクトごとの設定（`.quick-proj.toml`）
+///
+/// リポジトリにコミットしてチームで共有できます。指定した項目は自動検出した値や
+/// 設定ファイルの値より優先します（`--editor` などのCLIオプションと、別名は除く）。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectOverride {
+    /// 表示名
+    pub name: Option<String>,
+    /// プロジェクトの説明（表示テンプレートの `{description}`）
+    pub description: Option<String>,
+    /// タグ（設定ファイルのタグに追加される。正規化済み）
+    pub tags: Vec<String>,
+    /// このプロジェクトを開くエディタ
+    pub editor: Option<String>,
+    /// エディタと一緒にターミナルを開くか（設定の `open_terminal` より優先）
+    pub open_in_terminal: Option<bool>,
+}
+
+/// プロジェクトの `.quick-proj.toml` を読み込む
+///
+/// ファイルがない場合や読み込めない場合は `None` を返します。
+/// 不正なタグは取り除きます。
+pub fn read_project_override(path: &Path) -> Option<ProjectOverride> {
+    let content = fs::read_to_string(path.join(PROJECT_OVERRIDE_FILE_NAME)).ok()?;
+    let mut overrides: ProjectOverride = toml::from_str(&content).ok()?;
+
+    let mut tags = Vec::new();
+    for tag in overrides
+        .tags
+        .iter()
+        .filter_map(|tag| canonicalize_tag(tag).ok())
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    overrides.tags = tags;
+    overrides.name = overrides.name.filter(|name| !name.trim().is_empty());
+
+    Some(overrides)
+}
+
+/// スキャン中に読み込めなかったパス
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    /// 読み込めなかったパス（特定できない場合は `None`）
+    pub path: Option<PathBuf>,
+    /// エラーの内容
+    pub message: String,
+    /// 権限不足によるエラーか
+    pub permission_denied: bool,
+}
+
+impl From<&ignore::Error> for ScanError {
+    fn from(err: &ignore::Error) -> Self {
+        let io_error = err.io_error();
+        Self {
+            path: ignore_error_path(err),
+            // 入出力エラーのメッセージはパスを含むことがあるため、種類だけを使う
+            message: match io_error {
+                Some(e) if e.kind() != std::io::ErrorKind::Other => e.kind().to_string(),
+                Some(e) => e.to_string(),
+                None => err.to_string(),
+            },
+            permission_denied: io_error
+                .is_some_and(|e| e.kind() == std::io::ErrorKind::PermissionDenied),
+        }
+    }
+}
+
+/// 走査エラーの対象パスを取り出す
+fn ignore_error_path(err: &ignore::Error) -> Option<PathBuf> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path.clone()),
+        ignore::Error::Loop { child, .. } => Some(child.clone()),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            ignore_error_path(err)
+        }
+        _ => None,
+    }
+}
+
+/// プロジェクト種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ProjectType {
+    Rust,
+    Node,
+    Go,
+    Python,
+    Java,
+    Ruby,
+    Elixir,
+    CMake,
+    Dart,
+    DotNet,
+    Swift,
+    Zig,
+    Nix,
+    CI,
+    Infrastructure,
+    Generic,
+}
+
+impl ProjectType {
+    /// すべての種別
+    pub const ALL: [ProjectType; 16] = [
+        ProjectType::Rust,
+        ProjectType::Node,
+        ProjectType::Go,
+        ProjectType::Python,
+        ProjectType::Java,
+        ProjectType::Ruby,
+        ProjectType::Elixir,
+        ProjectType::CMake,
+        ProjectType::Dart,
+        ProjectType::DotNet,
+        ProjectType::Swift,
+        ProjectType::Zig,
+        ProjectType::Nix,
+        ProjectType::CI,
+        ProjectType::Infrastructure,
+        ProjectType::Generic,
+    ];
+
+    /// 種別名（小文字）を取得
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectType::Rust => "rust",
+            ProjectType::Node => "node",
+            ProjectType::Go => "go",
+            ProjectType::Python => "python",
+            ProjectType::Java => "java",
+            ProjectType::Ruby => "ruby",
+            ProjectType::Elixir => "elixir",
+            ProjectType::CMake => "cmake",
+            ProjectType::Dart => "dart",
+            ProjectType::DotNet => "dotnet",
+            ProjectType::Swift => "swift",
+            ProjectType::Zig => "zig",
+            ProjectType::Nix => "nix",
+            ProjectType::CI => "ci",
+            ProjectType::Infrastructure => "infrastructure",
+            ProjectType::Generic => "generic",
+        }
+    }
+
+    /// 表示用のアイコンを取得
+    pub fn icon(&self) -> &'static str {
+        match self {
+            ProjectType::Rust => "🦀",
+            ProjectType::Node => "🟢",
+            ProjectType::Go => "🐹",
+            ProjectType::Python => "🐍",
+            ProjectType::Java => "☕",
+            ProjectType::Ruby => "💎",
+            ProjectType::Elixir => "💧",
+            ProjectType::CMake => "⚙",
+            ProjectType::Dart => "🎯",
+            ProjectType::DotNet => "🟣",
+            ProjectType::Swift => "🐦",
+            ProjectType::Zig => "⚡",
+            ProjectType::Nix => "❄",
+            ProjectType::CI => "🔧",
+            ProjectType::Infrastructure => "🏗",
+            ProjectType::Generic => "📁",
+        }
+    }
+}
+
+impl std::str::FromStr for ProjectType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ProjectType::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s.to_lowercase())
+            .ok_or_else(|| {
+                let names: Vec<&str> = ProjectType::ALL.iter().map(|t| t.as_str()).collect();
+                format!(
+                    "unknown project type '{}' (expected: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// ワークスペースのメンバーや worktree を示すマーカーを元のマーカーに戻す
+pub fn base_marker(marker: &str) -> &str {
+    match marker {
+        CARGO_WORKSPACE_MEMBER_MARKER => "Cargo.toml",
+        NPM_WORKSPACE_MEMBER_MARKER => "package.json",
+        GIT_WORKTREE_MARKER => ".git",
+        other => other,
+    }
+}
+
+/// マーカーからプロジェクト種別を判定
+pub fn detect_type(marker: &str) -> ProjectType {
+    match base_marker(marker) {
+        "Cargo.toml" => ProjectType::Rust,
+        "package.json" | "deno.json" => ProjectType::Node,
+        "go.mod" => ProjectType::Go,
+        "pyproject.toml" | "setup.py" => ProjectType::Python,
+        "pom.xml" | "build.gradle" => ProjectType::Java,
+        "Gemfile" => ProjectType::Ruby,
+        "mix.exs" => ProjectType::Elixir,
+        "CMakeLists.txt" => ProjectType::CMake,
+        "pubspec.yaml" => ProjectType::Dart,
+        "Package.swift" => ProjectType::Swift,
+        "build.zig" => ProjectType::Zig,
+        "flake.nix" | "flake.lock" | "default.nix" | "shell.nix" => ProjectType::Nix,
+        m if CI_MARKERS.iter().any(|(marker, _)| *marker == m) => ProjectType::CI,
+        "Pulumi.yaml" | "playbook.yml" | "ansible.cfg" => ProjectType::Infrastructure,
+        m if m.ends_with(".tf") => ProjectType::Infrastructure,
+        m if [".sln", ".csproj", ".fsproj"]
+            .iter()
+            .any(|ext| m.ends_with(ext)) =>
+        {
+            ProjectType::DotNet
+        }
+        _ => ProjectType::Generic,
+    }
 }
 
 impl Project {
-    /// 表示用の文字列を生成
-    #[allow(dead_code)]
+    /// スキャンせずにパスだけからプロジェクト情報を作成
+    ///
+    /// 移動・削除されたピン留めプロジェクトの表示に使います。マーカーは空で、種別は `Generic` です。
+    pub fn from_path(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            root: path.parent().unwrap_or(path).to_path_buf(),
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            marker: String::new(),
+            project_type: ProjectType::Generic,
+            modified: None,
+            overrides: None,
+        }
+    }
+
+    /// 設定ファイルのタグと `.quick-proj.toml` のタグを合わせたタグ一覧
+    pub fn tags(&self, tags: &HashMap<PathBuf, Vec<String>>) -> Vec<String> {
+        let mut result = project_tags(tags, &self.path).to_vec();
+        if let Some(overrides) = &self.overrides {
+            for tag in &overrides.tags {
+                if !result.contains(tag) {
+                    result.push(tag.clone());
+                }
+            }
+        }
+        result
+    }
+
+    /// 表示用の文字列を `名前 (パス)` の形式で生成
     pub fn display_string(&self) -> String {
         format!("{} ({})", self.name, self.path.display())
     }
 
     /// 短い表示用文字列（パスのみ）
-    #[allow(dead_code)]
     pub fn short_display(&self) -> String {
         self.path.display().to_string()
     }
 }
 
+/// プロジェクト一覧の並び順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortCriterion {
+    /// プロジェクト名（大文字小文字を区別しない）
+    #[default]
+    Name,
+    /// フルパス
+    Path,
+    /// プロジェクト種別（同じ種別内は名前順）
+    Type,
+    /// 開いた回数を最後に開いてからの経過時間で割ったスコア順（`ranking` モジュールで並べる。ここでは名前順）
+    Frecency,
+    /// 最終更新日時の新しい順
+    Modified,
+    /// 最終更新日時の古い順（放置しているプロジェクトが先頭）
+    Age,
+    /// 起動の頻度と新しさを組み合わせたスコア順（`ranking` モジュールで並べる。ここでは名前順）
+    Smart,
+}
+
+impl SortCriterion {
+    /// 並び順の名前（`--sort` に指定する値）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortCriterion::Name => "name",
+            SortCriterion::Path => "path",
+            SortCriterion::Type => "type",
+            SortCriterion::Frecency => "frecency",
+            SortCriterion::Modified => "modified",
+            SortCriterion::Age => "age",
+            SortCriterion::Smart => "smart",
+        }
+    }
+}
+
+impl std::str::FromStr for SortCriterion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(SortCriterion::Name),
+            "path" => Ok(SortCriterion::Path),
+            "type" => Ok(SortCriterion::Type),
+            "frecency" => Ok(SortCriterion::Frecency),
+            "modified" => Ok(SortCriterion::Modified),
+            "age" => Ok(SortCriterion::Age),
+            "smart" => Ok(SortCriterion::Smart),
+            _ => Err(format!(
+                "unknown sort criterion '{}' (expected: name, path, type, frecency, modified, age, smart)",
+                s
+            )),
+        }
+    }
+}
+
+/// プロジェクト一覧を指定した基準で並び替える
+pub fn sort_projects(projects: &mut [Project], by: SortCriterion) {
+    match by {
+        SortCriterion::Name | SortCriterion::Frecency | SortCriterion::Smart => {
+            projects.sort_by_key(|p| p.name.to_lowercase());
+        }
+        SortCriterion::Path => {
+            projects.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        SortCriterion::Type => {
+            projects.sort_by_key(|p| (p.project_type, p.name.to_lowercase()));
+        }
+        SortCriterion::Modified => {
+            // 取得できないものは末尾に並べる
+            projects.sort_by_key(|p| std::cmp::Reverse(p.modified));
+        }
+        SortCriterion::Age => {
+            // 取得できないものは末尾に並べる
+            projects.sort_by_key(|p| (p.modified.is_none(), p.modified));
+        }
+    }
+}
+
+/// ピン留めしたプロジェクトを先頭に移動する
+///
+/// ピン留めしたもの・していないもののそれぞれで、元の並び順を保ちます。
+pub fn move_pinned_first(projects: &mut [Project], pinned: &BTreeSet<PathBuf>) {
+    projects.sort_by_key(|p| !pinned.contains(&p.path));
+}
+
+/// スキャン結果のキャッシュ
+///
+/// 走査したディレクトリごとに更新時刻と子ディレクトリ・検出したプロジェクトを保持し、
+/// 次回のスキャンでは更新時刻が変わったディレクトリだけを読み直します。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanCache {
+    /// ルートパスごとのキャッシュ
+    roots: Vec<RootCache>,
+}
+
+/// ルートパス単位のキャッシュ
+///
+/// `(root, max_depth, settings)` が一致する場合のみ再利用します。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RootCache {
+    /// ルートパス
+    root: PathBuf,
+    /// 走査時の最大深度
+    max_depth: usize,
+    /// 走査時のマーカー・除外設定のハッシュ
+    settings: u64,
+    /// ディレクトリごとのスナップショット
+    dirs: HashMap<PathBuf, DirSnapshot>,
+}
+
+/// ディレクトリのスナップショット
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DirSnapshot {
+    /// 走査時の更新時刻（直下のエントリが増減すると変わる）
+    mtime: SystemTime,
+    /// ディレクトリ自体がプロジェクトの場合はその情報（配下は探索しない）
+    project: Option<Project>,
+    /// 探索対象の子ディレクトリ
+    children: Vec<PathBuf>,
+}
+
+impl ScanCache {
+    /// キャッシュファイルを読み込む
+    ///
+    /// ファイルが存在しない場合は空のキャッシュを返します。
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::cache_path()?)
+    }
+
+    /// 指定したキャッシュファイルを読み込む
+    fn load_from(cache_path: &Path) -> Result<Self> {
+        if !cache_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(cache_path)
+            .with_context(|| format!("Failed to read scan cache: {}", cache_path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse scan cache: {}", cache_path.display()))
+    }
+
+    /// キャッシュファイルに保存する
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::cache_path()?)
+    }
+
+    /// 指定したキャッシュファイルに保存する
+    ///
+    /// 選択UIの再スキャンは終了を待たずにプロセスが終わることがあるため、
+    /// 同じディレクトリの一時ファイルに書き込んでから置き換え、書きかけのファイルを残しません。
+    fn save_to(&self, cache_path: &Path) -> Result<()> {
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create data directory: {}", parent.display())
+            })?;
+        }
+
+        let content = serde_json::to_string(self).context("Failed to serialize scan cache")?;
+
+        // 同じプロセス内で保存が重なっても一時ファイルを共有しないよう、連番を付ける
+        static SAVE_COUNT: AtomicUsize = AtomicUsize::new(0);
+        let mut temp_path = cache_path.as_os_str().to_owned();
+        temp_path.push(format!(
+            ".{}-{}.tmp",
+            std::process::id(),
+            SAVE_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let temp_path = PathBuf::from(temp_path);
+
+        fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write scan cache: {}", temp_path.display()))?;
+        if let Err(e) = fs::rename(&temp_path, cache_path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e)
+                .with_context(|| format!("Failed to write scan cache: {}", cache_path.display()));
+        }
+
+        Ok(())
+    }
+
+    /// キャッシュファイルのパスを取得
+    pub fn cache_path() -> Result<PathBuf> {
+        data_path(SCAN_CACHE_FILE_NAME)
+    }
+}
+
 /// プロジェクトスキャナー
 pub struct Scanner {
-    /// プロジェクトマーカー
-    markers: HashSet<String>,
+    /// プロジェクトマーカー（優先順）
+    markers: Vec<String>,
     /// 除外ディレクトリ
     exclude_dirs: HashSet<String>,
+    /// 除外パターン（exclude_dirs と exclude_patterns の和集合）
+    exclude_patterns: Vec<Pattern>,
+    /// 除外パス（配下も除外。展開・解決済み）
+    exclude_paths: Vec<PathBuf>,
     /// 最大深度
     max_depth: usize,
+    /// シンボリックリンクをたどるか
+    follow_symlinks: bool,
+    /// .gitignore に従うか
+    respect_gitignore: bool,
+    /// 隠しディレクトリも探索するか
+    scan_hidden: bool,
+    /// 前回のスキャン結果をキャッシュして再利用するか
+    incremental: bool,
+    /// 並列スキャンのスレッド数（None の場合はグローバルなスレッドプールを使用。git worktree の展開にも適用）
+    threads: Option<usize>,
+    /// Cargoワークスペースのメンバーを展開するか
+    expand_cargo_workspaces: bool,
+    /// npm / pnpm / Yarn ワークスペースのメンバーを展開するか
+    expand_npm_workspaces: bool,
+    /// git worktree を展開するか
+    expand_git_worktrees: bool,
+    /// 他のマーカーがない場合に確認するIaCのマーカー（globパターン可）
+    iac_markers: Vec<String>,
+    /// 他のマーカーがない場合にCI設定ファイルでプロジェクトを検出するか
+    detect_ci_markers: bool,
+    /// プロジェクトの表示名の別名（キーは実体パス）
+    aliases: HashMap<PathBuf, String>,
+    /// 元のパスが存在しない別名（キーはディレクトリの識別子、値は別名のキー）
+    moved_aliases: HashMap<String, PathBuf>,
+    /// サブディレクトリを走査した回数
+    walks: AtomicUsize,
+    /// キャッシュから再利用したディレクトリの数
+    cache_hits: AtomicUsize,
+    /// スキャン中に読み込めなかったパス
+    errors: Mutex<Vec<ScanError>>,
 }
 
 impl Scanner {
     /// 設定からスキャナーを作成
     pub fn from_config(config: &Config) -> Self {
         Self {
-            markers: config.project_markers.iter().cloned().collect(),
+            markers: config
+                .active_markers()
+                .into_iter()
+                .filter(|marker| !config.exclude_markers.contains(marker))
+                .collect(),
             exclude_dirs: config.exclude_dirs.iter().cloned().collect(),
+            exclude_patterns: build_exclude_patterns(config),
+            exclude_paths: config.resolved_exclude_paths(),
             max_depth: config.max_depth,
+            follow_symlinks: config.follow_symlinks,
+            respect_gitignore: config.respect_gitignore,
+            scan_hidden: config.scan_hidden,
+            incremental: config.incremental_scan,
+            threads: config.scan_threads.filter(|&n| n > 0),
+            expand_cargo_workspaces: config.expand_cargo_workspaces,
+            expand_npm_workspaces: config.expand_npm_workspaces,
+            expand_git_worktrees: config.expand_git_worktrees,
+            iac_markers: if config.detect_iac {
+                config.iac_markers.clone()
+            } else {
+                vec![]
+            },
+            detect_ci_markers: config.detect_ci_markers,
+            aliases: config.project_aliases.clone(),
+            moved_aliases: config.moved_alias_paths(),
+            walks: AtomicUsize::new(0),
+            cache_hits: AtomicUsize::new(0),
+            errors: Mutex::new(Vec::new()),
         }
     }
 
+    /// これまでのスキャンで読み込めなかったパスを取り出す
+    ///
+    /// ルートパスが重なっている場合に同じエラーが重複しないよう、パス順に並べて重複を除きます。
+    pub fn take_errors(&self) -> Vec<ScanError> {
+        let mut errors = std::mem::take(&mut *self.errors.lock().unwrap());
+        errors.sort_by(|a, b| a.path.cmp(&b.path).then(a.message.cmp(&b.message)));
+        errors.dedup();
+        errors
+    }
+
+    /// これまでのスキャンでキャッシュから再利用した（読み直さなかった）ディレクトリの数
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// スキャン中のエラーを記録
+    fn record_error(&self, error: ScanError) {
+        self.errors.lock().unwrap().push(error);
+    }
+
     /// 指定されたルートパスからプロジェクトをスキャン
     pub fn scan(&self, root_paths: &[PathBuf]) -> Result<Vec<Project>> {
-        let projects = Arc::new(Mutex::new(Vec::new()));
-        let seen_paths = Arc::new(Mutex::new(HashSet::new()));
-
-        // 各ルートパスを並列処理
-        root_paths.par_iter().for_each(|root| {
-            if let Ok(found) = self.scan_root(root) {
-                let mut projects_lock = projects.lock().unwrap();
-                let mut seen_lock = seen_paths.lock().unwrap();
-
-                for project in found {
-                    // 重複を排除
-                    if seen_lock.insert(project.path.clone()) {
-                        projects_lock.push(project);
-                    }
-                }
-            }
-        });
+        self.scan_with_cache(root_paths, &mut ScanCache::default())
+    }
+
+    /// 保存されたキャッシュを使ってスキャンし、結果をキャッシュに保存
+    ///
+    /// CLIのコマンドはすべてこの関数でスキャンし、同じキャッシュを共有します
+    /// （`quick-proj scan` の直後の選択UIは走査し直さずに表示できます）。
+    /// `refresh` が `true` の場合はキャッシュを使わずにすべて走査し直し、結果で更新します。
+    /// `incremental_scan` が無効な場合はキャッシュを読み書きせず、`scan` と同じ動作です。
+    /// キャッシュの読み書きに失敗しても、スキャン自体は続行します。
+    pub fn scan_cached(&self, root_paths: &[PathBuf], refresh: bool) -> Result<Vec<Project>> {
+        if !self.incremental {
+            return self.scan(root_paths);
+        }
+
+        match ScanCache::cache_path() {
+            Ok(cache_path) => self.scan_cached_at(root_paths, refresh, &cache_path),
+            Err(_) => self.scan(root_paths),
+        }
+    }
+
+    /// 指定したキャッシュファイルを使ってスキャン
+    fn scan_cached_at(
+        &self,
+        root_paths: &[PathBuf],
+        refresh: bool,
+        cache_path: &Path,
+    ) -> Result<Vec<Project>> {
+        let mut cache = if refresh {
+            ScanCache::default()
+        } else {
+            ScanCache::load_from(cache_path).unwrap_or_default()
+        };
+
+        let projects = self.scan_with_cache(root_paths, &mut cache)?;
+        let _ = cache.save_to(cache_path);
+
+        Ok(projects)
+    }
+
+    /// キャッシュを使ってスキャンし、キャッシュを最新の状態に更新
+    ///
+    /// 更新時刻が変わっていないディレクトリは読み直さず、キャッシュ済みの子ディレクトリと
+    /// プロジェクトを再利用します。`.gitignore` の内容の変更は検出しません。
+    pub fn scan_with_cache(
+        &self,
+        root_paths: &[PathBuf],
+        cache: &mut ScanCache,
+    ) -> Result<Vec<Project>> {
+        let settings = self.settings_hash();
+        let previous = std::mem::take(&mut cache.roots);
+        let pool = self.thread_pool()?;
 
-        let mut result = Arc::try_unwrap(projects).unwrap().into_inner().unwrap();
+        // 各ルートパスを並列処理（ルートごとに結果を返し、ロックを共有しない）
+        let scan_all = || {
+            root_paths
+                .par_iter()
+                .map(|root| {
+                    let cached = previous
+                        .iter()
+                        .find(|c| {
+                            c.root == *root
+                                && c.max_depth == self.max_depth
+                                && c.settings == settings
+                        })
+                        .map(|c| &c.dirs);
+
+                    let (found, dirs) = self.scan_root(root, cached);
+                    let root_cache = RootCache {
+                        root: root.clone(),
+                        max_depth: self.max_depth,
+                        settings,
+                        dirs,
+                    };
+                    (found, root_cache)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let scanned = run_in_pool(pool.as_ref(), scan_all);
+
+        let (found, root_caches): (Vec<_>, Vec<_>) = scanned.into_iter().unzip();
+        cache.roots = root_caches;
+        let mut result = dedup_projects(found.into_iter().flatten());
+        if self.expand_cargo_workspaces || self.expand_npm_workspaces {
+            add_workspace_members(
+                &mut result,
+                self.expand_cargo_workspaces,
+                self.expand_npm_workspaces,
+            );
+        }
+        if self.expand_git_worktrees {
+            run_in_pool(pool.as_ref(), || add_git_worktrees(&mut result));
+        }
+        self.apply_aliases(&mut result);
 
         // プロジェクト名でソート
-        result.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        sort_projects(&mut result, SortCriterion::Name);
 
         Ok(result)
     }
 
+    /// スレッド数が指定されている場合に、スキャン専用のスレッドプールを作成
+    ///
+    /// グローバルなスレッドプールは変更しないため、同じプロセスの他の並列処理には影響しません。
+    fn thread_pool(&self) -> Result<Option<rayon::ThreadPool>> {
+        self.threads
+            .map(|threads| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .context("Failed to create scan thread pool")
+            })
+            .transpose()
+    }
+
+    /// 別名が設定されたプロジェクトの表示名を置き換える
+    ///
+    /// 別名を設定した後に名前変更・移動されたディレクトリは、識別子から元の別名を探します。
+    fn apply_aliases(&self, projects: &mut [Project]) {
+        if self.aliases.is_empty() {
+            return;
+        }
+
+        for project in projects {
+            let key = fs::canonicalize(&project.path).unwrap_or_else(|_| project.path.clone());
+            let alias = self.aliases.get(&key).or_else(|| {
+                if self.moved_aliases.is_empty() {
+                    return None;
+                }
+                let old = self.moved_aliases.get(&config::dir_id(&key)?)?;
+                self.aliases.get(old)
+            });
+            if let Some(alias) = alias {
+                project.name = alias.clone();
+            }
+        }
+    }
+
     /// 単一のルートパスをスキャン
-    fn scan_root(&self, root: &Path) -> Result<Vec<Project>> {
+    ///
+    /// キャッシュがあれば更新時刻の変わったディレクトリだけを読み直します。
+    /// 新しいスナップショットも合わせて返します。
+    fn scan_root(
+        &self,
+        root: &Path,
+        cached: Option<&HashMap<PathBuf, DirSnapshot>>,
+    ) -> (Vec<Project>, HashMap<PathBuf, DirSnapshot>) {
+        let _span = tracing::debug_span!("scan_root", root = %root.display()).entered();
+        let started = Instant::now();
+        let mut dirs = HashMap::new();
+
         if !root.exists() {
-            return Ok(vec![]);
+            self.record_error(ScanError {
+                path: Some(root.to_path_buf()),
+                message: "root path does not exist".to_string(),
+                permission_denied: false,
+            });
+            return (vec![], dirs);
+        }
+
+        // 除外パス以下のルートは走査しない
+        if self.is_excluded_path(root) {
+            return (vec![], dirs);
+        }
+
+        // シンボリックリンクをたどる場合、実体が同じディレクトリは一度だけ探索する
+        // （循環リンクによる無限ループと、別名経由の重複検出を防ぐ）
+        let real_dirs = Arc::new(Mutex::new(HashSet::new()));
+        if self.follow_symlinks {
+            if let Ok(real) = fs::canonicalize(root) {
+                real_dirs.lock().unwrap().insert(real);
+            }
+        }
+
+        let projects = self.scan_dir(root, root, 0, cached, &mut dirs, &real_dirs);
+        tracing::debug!(
+            projects = projects.len(),
+            dirs = dirs.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "scanned root"
+        );
+        (projects, dirs)
+    }
+
+    /// ディレクトリを（キャッシュを使って）スキャン
+    ///
+    /// 更新時刻がキャッシュと一致すれば直下のエントリは変わっていないため、
+    /// キャッシュ済みの子ディレクトリだけを確認します。
+    fn scan_dir(
+        &self,
+        root: &Path,
+        dir: &Path,
+        depth: usize,
+        cached: Option<&HashMap<PathBuf, DirSnapshot>>,
+        dirs: &mut HashMap<PathBuf, DirSnapshot>,
+        real_dirs: &Arc<Mutex<HashSet<PathBuf>>>,
+    ) -> Vec<Project> {
+        // スパンの終了時にディレクトリごとの所要時間がログに記録される
+        let _span = tracing::debug_span!("scan_dir", dir = %dir.display(), depth).entered();
+
+        // たどらないシンボリックリンクは、それ自体のみを確認する
+        if depth > 0 && !self.follow_symlinks && is_symlink(dir) {
+            return self.walk_subtree(root, dir, 0, real_dirs, dirs);
+        }
+
+        let mtime = fs::metadata(dir).and_then(|m| m.modified()).ok();
+        let snapshot = cached.and_then(|c| c.get(dir));
+
+        if let (Some(mtime), Some(snapshot)) = (mtime, snapshot) {
+            if snapshot.mtime == mtime {
+                self.cache_hits.fetch_add(1, Ordering::Relaxed);
+                dirs.insert(dir.to_path_buf(), snapshot.clone());
+
+                if let Some(ref project) = snapshot.project {
+                    return vec![project.clone()];
+                }
+
+                return snapshot
+                    .children
+                    .iter()
+                    .flat_map(|child| {
+                        self.scan_dir(root, child, depth + 1, cached, dirs, real_dirs)
+                    })
+                    .collect();
+            }
         }
 
+        // 初めて見るディレクトリは配下をまとめて走査する
+        if depth > 0 && snapshot.is_none() {
+            return self.walk_subtree(root, dir, self.max_depth - depth, real_dirs, dirs);
+        }
+
+        // ルート、または更新されたディレクトリは直下を読み直す
+        let mut snapshot = DirSnapshot {
+            mtime: mtime.unwrap_or(SystemTime::UNIX_EPOCH),
+            project: None,
+            children: vec![],
+        };
+
+        // 除外対象のルートはプロジェクトとして扱わず、配下だけを探索する
+        if depth > 0 || !self.is_excluded(dir) {
+            if let Some(marker) = self.detect_marker(dir) {
+                snapshot.project = Some(make_project(root, dir, marker));
+            }
+        }
+
+        if snapshot.project.is_none() && depth < self.max_depth {
+            snapshot.children = self.child_dirs(dir);
+            if self.follow_symlinks {
+                let mut seen = real_dirs.lock().unwrap();
+                snapshot.children.retain(|child| {
+                    fs::canonicalize(child)
+                        .map(|real| seen.insert(real))
+                        .unwrap_or(false)
+                });
+            }
+        }
+
+        let projects: Vec<Project> = match snapshot.project {
+            Some(ref project) => vec![project.clone()],
+            None => snapshot
+                .children
+                .iter()
+                .flat_map(|child| self.scan_dir(root, child, depth + 1, cached, dirs, real_dirs))
+                .collect(),
+        };
+
+        if mtime.is_some() {
+            dirs.insert(dir.to_path_buf(), snapshot);
+        }
+
+        projects
+    }
+
+    /// 直下の探索対象サブディレクトリを列挙
+    fn child_dirs(&self, dir: &Path) -> Vec<PathBuf> {
+        self.walk_builder(dir, 1)
+            .build()
+            .filter_map(|entry| self.ok_entry(entry))
+            .filter(|entry| entry.depth() == 1)
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_dir() && !self.is_excluded(path))
+            .collect()
+    }
+
+    /// ディレクトリ配下をまとめて走査してプロジェクトを検出
+    ///
+    /// 走査したディレクトリのスナップショットを `dirs` に記録します。
+    fn walk_subtree(
+        &self,
+        root: &Path,
+        start: &Path,
+        max_depth: usize,
+        real_dirs: &Arc<Mutex<HashSet<PathBuf>>>,
+        dirs: &mut HashMap<PathBuf, DirSnapshot>,
+    ) -> Vec<Project> {
+        self.walks.fetch_add(1, Ordering::Relaxed);
+
         let mut projects = Vec::new();
         let mut visited = HashSet::new();
 
-        // ignore クレートを使用してウォーク
-        let walker = WalkBuilder::new(root)
-            .max_depth(Some(self.max_depth))
-            .hidden(false) // 隠しディレクトリも探索（.git検出のため）
-            .git_ignore(true)
-            .git_global(true)
-            .git_exclude(true)
-            .follow_links(false)
-            .build();
-
-        for entry in walker.flatten() {
+        let mut builder = self.walk_builder(start, max_depth);
+
+        // 除外ディレクトリの配下には降りない
+        let exclude_dirs = self.exclude_dirs.clone();
+        let exclude_patterns = self.exclude_patterns.clone();
+        let exclude_paths = self.exclude_paths.clone();
+        let follow_symlinks = self.follow_symlinks;
+        let real_dirs = Arc::clone(real_dirs);
+        builder.filter_entry(move |entry| {
+            if !entry.file_type().is_some_and(|t| t.is_dir()) {
+                return true;
+            }
+
+            let path = entry.path();
+            let excluded = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| exclude_dirs.contains(name))
+                || exclude_patterns.iter().any(|p| p.matches_path(path))
+                || exclude_paths.iter().any(|p| path.starts_with(p));
+            if excluded {
+                return false;
+            }
+
+            if !follow_symlinks {
+                return true;
+            }
+            match fs::canonicalize(path) {
+                Ok(real) => real_dirs.lock().unwrap().insert(real),
+                Err(_) => false,
+            }
+        });
+
+        for entry in builder.build().filter_map(|entry| self.ok_entry(entry)) {
             let path = entry.path();
 
             // ディレクトリのみ対象
@@ -112,11 +973,9 @@ impl Scanner {
                 continue;
             }
 
-            // 除外ディレクトリをスキップ
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if self.exclude_dirs.contains(name) {
-                    continue;
-                }
+            // 除外ディレクトリ・除外パターンに一致するパスをスキップ
+            if self.is_excluded(path) {
+                continue;
             }
 
             // 既にプロジェクトとして検出された親ディレクトリの子はスキップ
@@ -125,34 +984,115 @@ impl Scanner {
             }
 
             // マーカーをチェック
-            if let Some(marker) = self.detect_marker(path) {
-                let name = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-
-                projects.push(Project {
-                    path: path.to_path_buf(),
-                    name,
-                    marker,
-                });
-
+            let project = self.detect_marker(path).map(|marker| {
                 visited.insert(path.to_path_buf());
+                make_project(root, path, marker)
+            });
+            if let Some(ref project) = project {
+                projects.push(project.clone());
+            }
+
+            // 次回のスキャンのためにスナップショットを記録
+            let Ok(mtime) = fs::metadata(path).and_then(|m| m.modified()) else {
+                continue;
+            };
+            if entry.depth() > 0 {
+                if let Some(parent) = path.parent().and_then(|p| dirs.get_mut(p)) {
+                    parent.children.push(path.to_path_buf());
+                }
             }
+            dirs.insert(
+                path.to_path_buf(),
+                DirSnapshot {
+                    mtime,
+                    project,
+                    children: vec![],
+                },
+            );
         }
 
-        Ok(projects)
+        projects
+    }
+
+    /// 走査の共通設定を適用したウォーカーを作成
+    fn walk_builder(&self, start: &Path, max_depth: usize) -> WalkBuilder {
+        // ignore クレートを使用してウォーク
+        let mut builder = WalkBuilder::new(start);
+        builder
+            .max_depth(Some(max_depth))
+            .hidden(!self.scan_hidden)
+            .git_ignore(self.respect_gitignore)
+            .git_global(self.respect_gitignore)
+            .git_exclude(self.respect_gitignore)
+            .follow_links(self.follow_symlinks);
+        builder
+    }
+
+    /// 走査結果のエントリを取り出し、エラーの場合は記録して読み飛ばす
+    fn ok_entry(&self, entry: Result<DirEntry, ignore::Error>) -> Option<DirEntry> {
+        entry
+            .map_err(|err| self.record_error(ScanError::from(&err)))
+            .ok()
+    }
+
+    /// パスが除外ディレクトリ・除外パターン・除外パスに一致するか
+    fn is_excluded(&self, path: &Path) -> bool {
+        let excluded_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|name| self.exclude_dirs.contains(name));
+
+        excluded_name
+            || self.exclude_patterns.iter().any(|p| p.matches_path(path))
+            || self.is_excluded_path(path)
+    }
+
+    /// パスが除外パスそのもの、またはその配下か
+    fn is_excluded_path(&self, path: &Path) -> bool {
+        self.exclude_paths.iter().any(|p| path.starts_with(p))
+    }
+
+    /// キャッシュの有効性に影響するスキャン設定のハッシュ
+    fn settings_hash(&self) -> u64 {
+        let mut exclude_dirs: Vec<&String> = self.exclude_dirs.iter().collect();
+        exclude_dirs.sort();
+
+        let mut hasher = DefaultHasher::new();
+        self.markers.hash(&mut hasher);
+        exclude_dirs.hash(&mut hasher);
+        for pattern in &self.exclude_patterns {
+            pattern.as_str().hash(&mut hasher);
+        }
+        self.exclude_paths.hash(&mut hasher);
+        self.max_depth.hash(&mut hasher);
+        self.follow_symlinks.hash(&mut hasher);
+        self.respect_gitignore.hash(&mut hasher);
+        self.scan_hidden.hash(&mut hasher);
+        self.iac_markers.hash(&mut hasher);
+        self.detect_ci_markers.hash(&mut hasher);
+        hasher.finish()
     }
 
     /// ディレクトリがプロジェクトかどうかを判定
+    ///
+    /// マーカーは設定の順に確認し、最初に見つかったものを返します。
+    /// 続いてIaCのマーカー（`detect_iac` が有効な場合）、最後にCI設定ファイル
+    /// （`detect_ci_markers` が有効な場合）を確認します。
     fn detect_marker(&self, dir: &Path) -> Option<String> {
-        for marker in &self.markers {
-            let marker_path = dir.join(marker);
-            if marker_path.exists() {
-                return Some(marker.clone());
-            }
+        // globパターンのマーカー（例: "*.sln"）はディレクトリの中身と照合する
+        let entries = DirEntries::new(dir);
+        if let Some(marker) = self.markers.iter().find(|m| entries.contains(m)) {
+            return Some(marker.clone());
         }
+
+        if let Some(marker) = self.iac_markers.iter().find(|m| entries.contains(m)) {
+            return Some(marker.clone());
+        }
+
+        if self.detect_ci_markers {
+            return detect_ci_marker(&entries);
+        }
+
         None
     }
 
@@ -169,58 +1109,602 @@ impl Scanner {
     }
 }
 
-/// プロジェクト一覧を検索クエリでフィルタリング
-#[allow(dead_code)]
-pub fn filter_projects<'a>(projects: &'a [Project], query: &str) -> Vec<&'a Project> {
-    if query.is_empty() {
-        return projects.iter().collect();
-    }
-
-    let query_lower = query.to_lowercase();
-    let query_parts: Vec<&str> = query_lower.split_whitespace().collect();
-
-    projects
+/// CI設定ファイルのマーカーを検出
+fn detect_ci_marker(entries: &DirEntries) -> Option<String> {
+    CI_MARKERS
         .iter()
-        .filter(|p| {
-            let name_lower = p.name.to_lowercase();
-            let path_lower = p.path.to_string_lossy().to_lowercase();
+        .find(|(_, pattern)| entries.contains(pattern))
+        .map(|(marker, _)| marker.to_string())
+}
 
-            // すべてのクエリパートがマッチする必要がある
-            query_parts
-                .iter()
-                .all(|part| name_lower.contains(part) || path_lower.contains(part))
-        })
-        .collect()
+/// マーカーがglobパターン（`*`, `?`, `[` を含む）かどうか
+fn is_glob(marker: &str) -> bool {
+    marker.contains(['*', '?', '['])
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs::{self, File};
-    use tempfile::tempdir;
+/// マーカーの照合に使うディレクトリの中身
+///
+/// globパターンのマーカーごとにディレクトリを読み直さないよう、
+/// 最初に必要になったときに一度だけエントリ名を読み込みます。
+struct DirEntries<'a> {
+    dir: &'a Path,
+    names: OnceCell<Vec<String>>,
+}
 
-    fn create_test_project(dir: &Path, marker: &str) {
-        fs::create_dir_all(dir).unwrap();
-        if marker.contains('.') || marker == "Makefile" || marker == "Gemfile" {
-            // ファイルマーカー
-            File::create(dir.join(marker)).unwrap();
-        } else {
-            // ディレクトリマーカー
-            fs::create_dir_all(dir.join(marker)).unwrap();
+impl<'a> DirEntries<'a> {
+    fn new(dir: &'a Path) -> Self {
+        Self {
+            dir,
+            names: OnceCell::new(),
         }
     }
 
-    #[test]
-    fn test_scan_finds_projects() {
-        let root = tempdir().unwrap();
-
-        // プロジェクトを作成
-        create_test_project(&root.path().join("project-a"), ".git");
-        create_test_project(&root.path().join("project-b"), "Cargo.toml");
-        create_test_project(&root.path().join("project-c"), "package.json");
+    /// ディレクトリにマーカーが存在するかチェック
+    ///
+    /// globパターンのマーカーはエントリ名と照合します。`.github/workflows/*.yml` のように
+    /// 最後の要素だけがglobの場合はそのサブディレクトリを読み、
+    /// それ以外の要素にもglobを含む場合は一致するパスを探します。
+    fn contains(&self, marker: &str) -> bool {
+        if !is_glob(marker) {
+            return self.dir.join(marker).exists();
+        }
 
-        let config = Config::default();
-        let scanner = Scanner::from_config(&config);
+        match marker.rsplit_once('/') {
+            None => Pattern::new(marker).is_ok_and(|pattern| {
+                self.names
+                    .get_or_init(|| read_entry_names(self.dir))
+                    .iter()
+                    .any(|name| pattern.matches(name))
+            }),
+            Some((parent, name)) if !is_glob(parent) => Pattern::new(name).is_ok_and(|pattern| {
+                read_entry_names(&self.dir.join(parent))
+                    .iter()
+                    .any(|name| pattern.matches(name))
+            }),
+            Some(_) => {
+                let pattern = format!(
+                    "{}/{}",
+                    Pattern::escape(&self.dir.to_string_lossy()),
+                    marker
+                );
+                glob::glob(&pattern)
+                    .map(|mut paths| paths.any(|path| path.is_ok()))
+                    .unwrap_or(false)
+            }
+        }
+    }
+}
+
+/// ディレクトリのエントリ名を読み込む（読めない場合は空）
+fn read_entry_names(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `pool` が指定されている場合はそのスレッドプールで `f` を実行
+///
+/// `None` の場合はグローバルなスレッドプールで実行します。
+fn run_in_pool<R: Send>(pool: Option<&rayon::ThreadPool>, f: impl FnOnce() -> R + Send) -> R {
+    match pool {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
+/// 検出したディレクトリからプロジェクト情報を作成
+///
+/// `.quick-proj.toml` があれば読み込み、表示名を置き換えます。
+fn make_project(root: &Path, path: &Path, marker: String) -> Project {
+    let overrides = read_project_override(path);
+    let name = overrides
+        .as_ref()
+        .and_then(|o| o.name.clone())
+        .or_else(|| path.file_name().and_then(|n| n.to_str()).map(String::from))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    Project {
+        path: path.to_path_buf(),
+        root: root.to_path_buf(),
+        name,
+        project_type: detect_type(&marker),
+        marker,
+        modified: fs::metadata(path).and_then(|m| m.modified()).ok(),
+        overrides,
+    }
+}
+
+/// 重なり合うルートパスで重複して検出されたプロジェクトを1つにまとめる
+///
+/// 同じパスのプロジェクトは最も深いルートの検出結果を残します。
+fn dedup_projects(projects: impl IntoIterator<Item = Project>) -> Vec<Project> {
+    let mut result: Vec<Project> = Vec::new();
+    let mut seen: HashMap<PathBuf, usize> = HashMap::new();
+
+    for project in projects {
+        match seen.get(&project.path) {
+            Some(&idx) => {
+                if project.root.components().count() > result[idx].root.components().count() {
+                    result[idx] = project;
+                }
+            }
+            None => {
+                seen.insert(project.path.clone(), result.len());
+                result.push(project);
+            }
+        }
+    }
+
+    result
+}
+
+/// ワークスペースのメンバーをプロジェクトとして追加
+///
+/// `cargo` / `npm` がそれぞれ有効な場合に、`Cargo.toml` / `package.json` で検出した
+/// プロジェクトのメンバーを展開します。既に一覧にあるパス（ワークスペース自体を含む）は追加しません。
+fn add_workspace_members(projects: &mut Vec<Project>, cargo: bool, npm: bool) {
+    let mut seen: HashSet<PathBuf> = projects.iter().map(|p| p.path.clone()).collect();
+
+    let members: Vec<Project> = projects
+        .iter()
+        .flat_map(|project| {
+            let (paths, marker) = match project.marker.as_str() {
+                "Cargo.toml" if cargo => (
+                    cargo_workspace_members(&project.path),
+                    CARGO_WORKSPACE_MEMBER_MARKER,
+                ),
+                "package.json" if npm => (
+                    npm_workspace_members(&project.path),
+                    NPM_WORKSPACE_MEMBER_MARKER,
+                ),
+                _ => return vec![],
+            };
+
+            paths
+                .into_iter()
+                .map(|path| make_project(&project.root, &path, marker.to_string()))
+                .collect()
+        })
+        .filter(|member| seen.insert(member.path.clone()))
+        .collect();
+
+    projects.extend(members);
+}
+
+/// git worktree の作業ツリーをプロジェクトとして追加
+///
+/// 検出に使ったマーカーによらず、`.git/worktrees` があるプロジェクトだけ
+/// `git worktree list --porcelain` を実行します。既に一覧にあるパスは追加しません。
+fn add_git_worktrees(projects: &mut Vec<Project>) {
+    let mut seen: HashSet<PathBuf> = projects.iter().map(|p| p.path.clone()).collect();
+
+    let worktrees: Vec<Project> = projects
+        .par_iter()
+        .filter(|project| project.path.join(".git/worktrees").is_dir())
+        .flat_map_iter(|project| {
+            git_worktrees(&project.path)
+                .into_iter()
+                .map(|path| make_project(&project.root, &path, GIT_WORKTREE_MARKER.to_string()))
+        })
+        .collect();
+
+    projects.extend(
+        worktrees
+            .into_iter()
+            .filter(|worktree| seen.insert(worktree.path.clone())),
+    );
+}
+
+/// `git worktree list --porcelain` を実行してメイン以外の作業ツリーを取得
+///
+/// 存在しない（prune されていない）作業ツリーと、リポジトリ自身は除きます。
+fn git_worktrees(dir: &Path) -> Vec<PathBuf> {
+    let Ok(output) = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["worktree", "list", "--porcelain"])
+        .output()
+    else {
+        return vec![];
+    };
+    if !output.status.success() {
+        return vec![];
+    }
+
+    parse_worktree_list(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .filter(|path| path != dir && path.is_dir())
+        .collect()
+}
+
+/// `git worktree list --porcelain` の出力からメイン以外の作業ツリーのパスを取り出す
+///
+/// 最初のエントリはメインの作業ツリーなので除きます。
+fn parse_worktree_list(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("worktree "))
+        .skip(1)
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// `Cargo.toml` の `workspace.members` を解決してメンバーのディレクトリを取得
+///
+/// `workspace.exclude` に含まれるディレクトリは除きます。
+fn cargo_workspace_members(dir: &Path) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) else {
+        return vec![];
+    };
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return vec![];
+    };
+    let Some(workspace) = manifest.get("workspace").and_then(|w| w.as_table()) else {
+        return vec![];
+    };
+
+    let entries = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut patterns = entries("members");
+    patterns.extend(entries("exclude").into_iter().map(|e| format!("!{}", e)));
+    resolve_workspace_members(dir, &patterns, "Cargo.toml")
+}
+
+/// `package.json` の `workspaces` と `pnpm-workspace.yaml` の `packages` を解決して
+/// メンバーのディレクトリを取得
+///
+/// `workspaces` は配列と `{ "packages": [...] }` 形式の両方に対応します。
+fn npm_workspace_members(dir: &Path) -> Vec<PathBuf> {
+    let mut patterns = Vec::new();
+
+    if let Some(manifest) = fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    {
+        let workspaces = manifest.get("workspaces");
+        let list = workspaces
+            .and_then(|w| w.as_array())
+            .or_else(|| workspaces.and_then(|w| w.get("packages")?.as_array()));
+
+        if let Some(list) = list {
+            patterns.extend(list.iter().filter_map(|p| p.as_str()).map(String::from));
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(dir.join("pnpm-workspace.yaml")) {
+        patterns.extend(pnpm_workspace_packages(&content));
+    }
+
+    resolve_workspace_members(dir, &patterns, "package.json")
+}
+
+/// `pnpm-workspace.yaml` から `packages:` のリストを読み取る
+///
+/// YAMLの完全な解析は行わず、ブロック形式のリストのみに対応します。
+fn pnpm_workspace_packages(content: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // インデントのない行は次のキー
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+
+        if let (true, Some(item)) = (in_packages, trimmed.strip_prefix('-')) {
+            let item = item.split(" #").next().unwrap_or_default().trim();
+            let item = item.trim_matches(|c| c == '\'' || c == '"');
+            if !item.is_empty() {
+                packages.push(item.to_string());
+            }
+        }
+    }
+
+    packages
+}
+
+/// ワークスペースのメンバーのglobパターンを解決
+///
+/// `!` で始まるパターンは除外として扱います。`manifest` を持たないディレクトリ、
+/// ワークスペース自体、`node_modules` 配下は除きます。
+fn resolve_workspace_members(dir: &Path, patterns: &[String], manifest: &str) -> Vec<PathBuf> {
+    let expand = |pattern: &str| -> Vec<PathBuf> {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        glob::glob(&dir.join(pattern).to_string_lossy())
+            .map(|paths| paths.flatten().collect())
+            .unwrap_or_default()
+    };
+
+    let excluded: HashSet<PathBuf> = patterns
+        .iter()
+        .filter_map(|p| p.strip_prefix('!'))
+        .flat_map(expand)
+        .collect();
+
+    let mut members = Vec::new();
+    for path in patterns
+        .iter()
+        .filter(|p| !p.starts_with('!'))
+        .flat_map(|p| expand(p))
+    {
+        if path != dir
+            && path.join(manifest).is_file()
+            && !excluded.contains(&path)
+            && !path.components().any(|c| c.as_os_str() == "node_modules")
+            && !members.contains(&path)
+        {
+            members.push(path);
+        }
+    }
+
+    members
+}
+
+/// 除外パターンを構築
+///
+/// `exclude_dirs` の各名前は `**/<name>` として扱い、`exclude_patterns` と合わせます。
+/// 不正なパターンは無視します。
+fn build_exclude_patterns(config: &Config) -> Vec<Pattern> {
+    config
+        .exclude_dirs
+        .iter()
+        .map(|name| format!("**/{}", Pattern::escape(name)))
+        .chain(config.exclude_patterns.iter().cloned())
+        .filter_map(|pattern| Pattern::new(&pattern).ok())
+        .collect()
+}
+
+/// 指定したマーカーで検出したプロジェクトだけを残す
+///
+/// `Cargo.toml` や `package.json` を指定した場合はワークスペースのメンバーも残します。
+/// `marker` が `None` の場合は何もしません。
+pub fn retain_marker(projects: &mut Vec<Project>, marker: Option<&str>) {
+    let Some(marker) = marker else {
+        return;
+    };
+
+    projects.retain(|project| base_marker(&project.marker) == marker);
+}
+
+/// 指定したタグをすべて持つプロジェクトだけを残す
+///
+/// `required` は正規化済みのタグです。空の場合は何もしません。
+pub fn retain_tags(
+    projects: &mut Vec<Project>,
+    tags: &HashMap<PathBuf, Vec<String>>,
+    required: &[String],
+) {
+    if required.is_empty() {
+        return;
+    }
+
+    projects.retain(|project| {
+        let tags = project.tags(tags);
+        required.iter().all(|tag| tags.contains(tag))
+    });
+}
+
+/// 検索で大文字と小文字を区別するか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseSensitivity {
+    /// 区別しない
+    #[default]
+    Insensitive,
+    /// 区別する
+    Sensitive,
+}
+
+impl CaseSensitivity {
+    /// 設定値（`true` なら区別する）から作成
+    pub fn from_flag(case_sensitive: bool) -> Self {
+        if case_sensitive {
+            CaseSensitivity::Sensitive
+        } else {
+            CaseSensitivity::Insensitive
+        }
+    }
+
+    /// もう一方の設定を返す
+    pub fn toggle(self) -> Self {
+        match self {
+            CaseSensitivity::Insensitive => CaseSensitivity::Sensitive,
+            CaseSensitivity::Sensitive => CaseSensitivity::Insensitive,
+        }
+    }
+
+    /// 表示用の名前
+    pub fn label(self) -> &'static str {
+        match self {
+            CaseSensitivity::Insensitive => "case-insensitive",
+            CaseSensitivity::Sensitive => "case-sensitive",
+        }
+    }
+
+    /// 比較用に文字列を正規化（区別しない場合は小文字に変換）
+    pub fn normalize(self, s: &str) -> String {
+        match self {
+            CaseSensitivity::Insensitive => s.to_lowercase(),
+            CaseSensitivity::Sensitive => s.to_string(),
+        }
+    }
+
+    /// あいまい検索に使うマッチャー
+    pub fn fuzzy_matcher(self) -> SkimMatcherV2 {
+        match self {
+            CaseSensitivity::Insensitive => SkimMatcherV2::default().ignore_case(),
+            CaseSensitivity::Sensitive => SkimMatcherV2::default().respect_case(),
+        }
+    }
+}
+
+/// プロジェクト一覧を検索クエリでフィルタリング
+///
+/// 空白で区切ったすべての語が、プロジェクト名またはパスに含まれるものを元の順序のまま返します。
+pub fn filter_projects<'a>(
+    projects: &'a [Project],
+    query: &str,
+    case: CaseSensitivity,
+) -> Vec<&'a Project> {
+    let query = case.normalize(query);
+    let query_parts: Vec<&str> = query.split_whitespace().collect();
+
+    projects
+        .iter()
+        .filter(|p| contains_all(p, &query_parts, case))
+        .collect()
+}
+
+/// すべての語をプロジェクト名またはパスに部分文字列として含むか
+fn contains_all(project: &Project, query_parts: &[&str], case: CaseSensitivity) -> bool {
+    let name = case.normalize(&project.name);
+    let path = case.normalize(&project.path.to_string_lossy());
+    query_parts
+        .iter()
+        .all(|part| name.contains(part) || path.contains(part))
+}
+
+/// 検索クエリに一致するプロジェクトを、一致度の高い順に一致度と合わせて返す
+///
+/// 同じ一致度では元の順序を保ちます。
+/// [`filter_projects`] で一致するプロジェクトがあればそれだけを対象にし、
+/// ない場合はプロジェクト名へのあいまい検索で一致したものを返します。
+/// 一致度はあいまい検索のスコアで、プロジェクト名に一致した語はパスだけに一致した語より高くなります。
+pub fn rank_projects<'a>(
+    projects: &'a [Project],
+    query: &str,
+    case: CaseSensitivity,
+) -> Vec<(i64, &'a Project)> {
+    let query = case.normalize(query);
+    let query_parts: Vec<&str> = query.split_whitespace().collect();
+    if query_parts.is_empty() {
+        return projects.iter().map(|project| (0, project)).collect();
+    }
+
+    // すべての語を部分文字列として含むものを優先する
+    let substring_matches: Vec<&Project> = projects
+        .iter()
+        .filter(|p| contains_all(p, &query_parts, case))
+        .collect();
+    let fuzzy = substring_matches.is_empty();
+    let candidates = if fuzzy {
+        projects.iter().collect()
+    } else {
+        substring_matches
+    };
+
+    let matcher = case.fuzzy_matcher();
+    let mut scored: Vec<(i64, &Project)> = candidates
+        .into_iter()
+        .filter_map(|project| {
+            query_parts
+                .iter()
+                .map(|part| match_score(&matcher, project, part, case, !fuzzy))
+                .sum::<Option<i64>>()
+                .map(|score| (score, project))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+    scored
+}
+
+/// [`rank_projects`] の結果から、選択UIなしで選べる最も一致するプロジェクトを返す
+///
+/// 一致したのが1つだけの場合か、最上位が2番目より十分に高い一致度の場合だけ返します。
+pub fn best_match<'a>(ranked: &[(i64, &'a Project)]) -> Option<&'a Project> {
+    match ranked {
+        [(_, project)] => Some(project),
+        [(top, project), (second, _), ..] if top - second >= CLEAR_WIN_MARGIN => Some(project),
+        _ => None,
+    }
+}
+
+/// 検索語1つに対するプロジェクトの一致度（一致しない場合は `None`）
+///
+/// `match_path` が `false` の場合はプロジェクト名だけを対象にします。
+fn match_score(
+    matcher: &SkimMatcherV2,
+    project: &Project,
+    part: &str,
+    case: CaseSensitivity,
+    match_path: bool,
+) -> Option<i64> {
+    let name_score = matcher.fuzzy_match(&project.name, part).map(|score| {
+        let exact = case.normalize(&project.name) == part;
+        score + NAME_MATCH_BONUS + if exact { EXACT_NAME_BONUS } else { 0 }
+    });
+    let path_score = match_path
+        .then(|| matcher.fuzzy_match(&project.path.to_string_lossy(), part))
+        .flatten();
+
+    name_score.max(path_score)
+}
+
+/// プロジェクト一覧を正規表現でフィルタリング
+///
+/// プロジェクト名とフルパスのどちらかに一致するプロジェクトを返します。
+pub fn filter_by_regex<'a>(projects: &'a [Project], pattern: &str) -> Result<Vec<&'a Project>> {
+    let regex =
+        Regex::new(pattern).with_context(|| format!("Invalid regular expression '{}'", pattern))?;
+
+    Ok(projects
+        .iter()
+        .filter(|p| regex.is_match(&p.name) || regex.is_match(p.path.to_string_lossy().as_ref()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn create_test_project(dir: &Path, marker: &str) {
+        fs::create_dir_all(dir).unwrap();
+        if marker.contains('.') || marker == "Makefile" || marker == "Gemfile" {
+            // ファイルマーカー
+            File::create(dir.join(marker)).unwrap();
+        } else {
+            // ディレクトリマーカー
+            fs::create_dir_all(dir.join(marker)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_scan_finds_projects() {
+        let root = tempdir().unwrap();
+
+        // プロジェクトを作成
+        create_test_project(&root.path().join("project-a"), ".git");
+        create_test_project(&root.path().join("project-b"), "Cargo.toml");
+        create_test_project(&root.path().join("project-c"), "package.json");
+
+        let config = Config::default();
+        let scanner = Scanner::from_config(&config);
         let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
 
         assert_eq!(projects.len(), 3);
@@ -245,27 +1729,1081 @@ mod tests {
     }
 
     #[test]
-    fn test_filter_projects() {
-        let projects = vec![
+    fn test_detect_marker_priority() {
+        let root = tempdir().unwrap();
+        let project = root.path().join("app");
+        create_test_project(&project, ".git");
+        create_test_project(&project, "Cargo.toml");
+
+        // デフォルトでは言語固有のマーカーが .git より優先
+        let scanner = Scanner::from_config(&Config::default());
+        assert_eq!(
+            scanner.detect_marker(&project).as_deref(),
+            Some("Cargo.toml")
+        );
+
+        // 設定の順序に従う
+        let config = Config {
+            project_markers: vec![".git".to_string(), "Cargo.toml".to_string()],
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        assert_eq!(scanner.detect_marker(&project).as_deref(), Some(".git"));
+    }
+
+    #[test]
+    fn test_scan_records_missing_root() {
+        let root = tempdir().unwrap();
+        create_test_project(&root.path().join("app"), "Cargo.toml");
+        let missing = root.path().join("missing");
+
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner
+            .scan(&[root.path().to_path_buf(), missing.clone()])
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+
+        let errors = scanner.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path.as_deref(), Some(missing.as_path()));
+        assert!(!errors[0].permission_denied);
+
+        // 取り出したエラーは残らない
+        assert!(scanner.take_errors().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_records_broken_symlink() {
+        use std::os::unix::fs::symlink;
+
+        let root = tempdir().unwrap();
+        create_test_project(&root.path().join("app"), "Cargo.toml");
+        fs::create_dir_all(root.path().join("group")).unwrap();
+        let link = root.path().join("group").join("dangling");
+        symlink(root.path().join("nowhere"), &link).unwrap();
+
+        let config = Config {
+            follow_symlinks: true,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        assert_eq!(scanner.scan(&[root.path().to_path_buf()]).unwrap().len(), 1);
+
+        let errors = scanner.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path.as_deref(), Some(link.as_path()));
+    }
+
+    #[test]
+    fn test_scan_respect_gitignore() {
+        let repo = tempdir().unwrap();
+        fs::create_dir_all(repo.path().join(".git")).unwrap();
+        fs::write(repo.path().join(".gitignore"), "checkouts/\n").unwrap();
+
+        let root = repo.path().join("src");
+        create_test_project(&root.join("app"), "Cargo.toml");
+        create_test_project(&root.join("checkouts").join("lib"), "Cargo.toml");
+        create_test_project(&root.join(".hidden").join("tool"), "Cargo.toml");
+
+        let scan = |config: &Config| -> Vec<String> {
+            Scanner::from_config(config)
+                .scan(std::slice::from_ref(&root))
+                .unwrap()
+                .into_iter()
+                .map(|p| p.name)
+                .collect()
+        };
+
+        assert_eq!(scan(&Config::default()), vec!["app", "tool"]);
+
+        let config = Config {
+            respect_gitignore: false,
+            scan_hidden: false,
+            ..Config::default()
+        };
+        assert_eq!(scan(&config), vec!["app", "lib"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_follow_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let root = tempdir().unwrap();
+        let other = tempdir().unwrap();
+
+        create_test_project(&other.path().join("linked"), ".git");
+        symlink(other.path().join("linked"), root.path().join("linked")).unwrap();
+
+        // 循環するシンボリックリンク
+        fs::create_dir_all(root.path().join("group")).unwrap();
+        symlink(root.path(), root.path().join("group").join("loop")).unwrap();
+        create_test_project(&root.path().join("group").join("app"), "Cargo.toml");
+
+        let config = Config {
+            follow_symlinks: true,
+            max_depth: 10,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        let mut names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["app", "linked"]);
+    }
+
+    #[test]
+    fn test_scan_exclude_patterns() {
+        let root = tempdir().unwrap();
+
+        create_test_project(&root.path().join("app"), ".git");
+        create_test_project(&root.path().join("build-old"), ".git");
+        create_test_project(
+            &root.path().join("out").join("dist").join("pkg"),
+            "package.json",
+        );
+
+        let config = Config {
+            exclude_patterns: vec!["**/build-*".to_string(), "**/dist/**".to_string()],
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name, "app");
+    }
+
+    #[test]
+    fn test_scan_exclude_paths() {
+        let root = tempdir().unwrap();
+
+        create_test_project(&root.path().join("app").join("out").join("gen"), ".git");
+        create_test_project(&root.path().join("tool").join("out").join("gen"), ".git");
+        create_test_project(&root.path().join("third_party").join("lib"), "go.mod");
+
+        let config = Config {
+            root_paths: vec![root.path().to_path_buf()],
+            exclude_paths: vec![
+                root.path().join("app").join("out"),
+                root.path().join("third_party"),
+            ],
+            max_depth: 5,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&config.root_paths).unwrap();
+
+        assert_eq!(projects.len(), 1);
+        assert!(projects[0].path.starts_with(root.path().join("tool")));
+
+        // 除外パス以下のルートはそれ自体も走査しない
+        let projects = scanner.scan(&[root.path().join("third_party")]).unwrap();
+        assert!(projects.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_exclude_paths_through_symlink() {
+        use std::os::unix::fs::symlink;
+
+        let real = tempdir().unwrap();
+        let links = tempdir().unwrap();
+        let link = links.path().join("src");
+        symlink(real.path(), &link).unwrap();
+        create_test_project(&real.path().join("app").join("out"), ".git");
+        create_test_project(&real.path().join("tool"), ".git");
+
+        // 実体パスのルートをリンク経由の除外パスで、リンク経由のルートを同じ書き方の除外パスで除外できる
+        for (root, exclude) in [
+            (real.path().to_path_buf(), link.join("app")),
+            (link.clone(), link.join("app")),
+        ] {
+            let config = Config {
+                root_paths: vec![root.clone()],
+                exclude_paths: vec![exclude],
+                max_depth: 5,
+                ..Config::default()
+            };
+            let projects = Scanner::from_config(&config).scan(&[root]).unwrap();
+            assert_eq!(projects.len(), 1, "{:?}", projects);
+            assert!(projects[0].path.ends_with("tool"));
+        }
+    }
+
+    #[test]
+    fn test_scan_with_cache_rescans_only_changed_subtree() {
+        let root = tempdir().unwrap();
+        let roots = [root.path().to_path_buf()];
+
+        create_test_project(&root.path().join("project-a"), ".git");
+        create_test_project(&root.path().join("project-b"), "Cargo.toml");
+        create_test_project(&root.path().join("group").join("app"), "go.mod");
+
+        let mut cache = ScanCache::default();
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner.scan_with_cache(&roots, &mut cache).unwrap();
+        assert_eq!(projects.len(), 3);
+        assert_eq!(scanner.walks.load(Ordering::Relaxed), 3);
+        assert_eq!(scanner.cache_hits(), 0);
+
+        // 新しいプロジェクトを1つ追加すると、そのサブディレクトリだけを走査する
+        create_test_project(&root.path().join("project-new"), "package.json");
+
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner.scan_with_cache(&roots, &mut cache).unwrap();
+        assert_eq!(projects.len(), 4);
+        assert!(projects.iter().any(|p| p.name == "project-new"));
+        assert_eq!(scanner.walks.load(Ordering::Relaxed), 1);
+        assert!(scanner.cache_hits() > 0);
+
+        // スキャン設定が変わった場合はキャッシュを使わない
+        let config = Config {
+            max_depth: 5,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        scanner.scan_with_cache(&roots, &mut cache).unwrap();
+        assert_eq!(scanner.walks.load(Ordering::Relaxed), 4);
+        assert_eq!(scanner.cache_hits(), 0);
+    }
+
+    #[test]
+    fn test_scan_cache_save_replaces_file() {
+        let dir = tempdir().unwrap();
+        let cache_path = dir.path().join("data").join(SCAN_CACHE_FILE_NAME);
+        fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+        fs::write(&cache_path, "stale").unwrap();
+
+        ScanCache::default().save_to(&cache_path).unwrap();
+
+        // 一時ファイルを残さず、読み込めるキャッシュに置き換わる
+        let content = fs::read_to_string(&cache_path).unwrap();
+        assert!(serde_json::from_str::<ScanCache>(&content).is_ok());
+        let names: Vec<_> = fs::read_dir(cache_path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![SCAN_CACHE_FILE_NAME]);
+    }
+
+    #[test]
+    fn test_scan_then_select_shares_cache() {
+        let root = tempdir().unwrap();
+        let data = tempdir().unwrap();
+        let roots = [root.path().to_path_buf()];
+        let cache_path = data.path().join(SCAN_CACHE_FILE_NAME);
+
+        create_test_project(&root.path().join("project-a"), ".git");
+        create_test_project(&root.path().join("group").join("app"), "go.mod");
+
+        let config = Config {
+            incremental_scan: true,
+            ..Config::default()
+        };
+
+        // `quick-proj scan` はすべて走査し直してキャッシュを更新する
+        let scan = Scanner::from_config(&config);
+        let scanned = scan.scan_cached_at(&roots, true, &cache_path).unwrap();
+        assert_eq!(scan.walks.load(Ordering::Relaxed), 2);
+
+        // 続けて実行した選択UIはキャッシュを読むだけで、ディレクトリを走査しない
+        let select = Scanner::from_config(&config);
+        let selected = select.scan_cached_at(&roots, false, &cache_path).unwrap();
+        assert_eq!(select.walks.load(Ordering::Relaxed), 0);
+        assert_eq!(selected, scanned);
+    }
+
+    #[test]
+    fn test_scan_cached_without_incremental_scan() {
+        let root = tempdir().unwrap();
+        create_test_project(&root.path().join("project-a"), ".git");
+
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner
+            .scan_cached(&[root.path().to_path_buf()], false)
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+        assert_eq!(scanner.walks.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_scan_with_cache_detects_nested_changes() {
+        let root = tempdir().unwrap();
+        let roots = [root.path().to_path_buf()];
+        let team = root.path().join("group").join("team");
+
+        create_test_project(&team.join("app1"), ".git");
+
+        let mut cache = ScanCache::default();
+        let scanner = Scanner::from_config(&Config::default());
+        assert_eq!(
+            scanner.scan_with_cache(&roots, &mut cache).unwrap().len(),
+            1
+        );
+
+        // 深い階層に追加されたプロジェクトも、追加されたディレクトリだけの走査で検出する
+        create_test_project(&team.join("app2"), "Cargo.toml");
+
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner.scan_with_cache(&roots, &mut cache).unwrap();
+        assert_eq!(projects.len(), 2);
+        assert!(projects.iter().any(|p| p.name == "app2"));
+        assert_eq!(scanner.walks.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_scan_expands_cargo_workspaces() {
+        let root = tempdir().unwrap();
+        let workspace = root.path().join("workspace");
+        fs::create_dir_all(&workspace).unwrap();
+        fs::write(
+            workspace.join("Cargo.toml"),
+            "[workspace]\nmembers = [\".\", \"cli\", \"crates/*\"]\nexclude = [\"crates/old\"]\n",
+        )
+        .unwrap();
+        for member in ["cli", "crates/core", "crates/old"] {
+            create_test_project(&workspace.join(member), "Cargo.toml");
+        }
+        fs::create_dir_all(workspace.join("crates").join("docs")).unwrap();
+
+        let roots = [root.path().to_path_buf()];
+
+        // デフォルトではワークスペース自体のみ
+        let scanner = Scanner::from_config(&Config::default());
+        assert_eq!(scanner.scan(&roots).unwrap().len(), 1);
+
+        let config = Config {
+            expand_cargo_workspaces: true,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&roots).unwrap();
+
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["cli", "core", "workspace"]);
+        assert_eq!(projects[0].marker, CARGO_WORKSPACE_MEMBER_MARKER);
+        assert_eq!(projects[0].project_type, ProjectType::Rust);
+        assert_eq!(projects[2].marker, "Cargo.toml");
+    }
+
+    #[test]
+    fn test_scan_expands_npm_workspaces() {
+        let root = tempdir().unwrap();
+
+        let npm = root.path().join("npm-repo");
+        fs::create_dir_all(&npm).unwrap();
+        fs::write(
+            npm.join("package.json"),
+            r#"{ "workspaces": { "packages": ["packages/*", "!packages/legacy"] } }"#,
+        )
+        .unwrap();
+        for member in ["packages/web", "packages/legacy"] {
+            create_test_project(&npm.join(member), "package.json");
+        }
+
+        let pnpm = root.path().join("pnpm-repo");
+        create_test_project(&pnpm, "package.json");
+        fs::write(
+            pnpm.join("pnpm-workspace.yaml"),
+            "packages:\n  # apps\n  - 'apps/*'\n  - \"tools/cli\"\ncatalog:\n  - ignored\n",
+        )
+        .unwrap();
+        for member in ["apps/site", "tools/cli"] {
+            create_test_project(&pnpm.join(member), "package.json");
+        }
+
+        let config = Config {
+            expand_npm_workspaces: true,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["cli", "npm-repo", "pnpm-repo", "site", "web"]);
+        assert_eq!(projects[0].marker, NPM_WORKSPACE_MEMBER_MARKER);
+        assert_eq!(projects[0].project_type, ProjectType::Node);
+    }
+
+    #[test]
+    fn test_scan_expands_worktrees_of_language_projects() {
+        let root = tempdir().unwrap();
+        let elsewhere = tempdir().unwrap();
+        let repo = root.path().join("app");
+        let worktree = elsewhere.path().join("app-feature");
+        create_test_project(&repo, "Cargo.toml");
+
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .is_ok_and(|output| output.status.success())
+        };
+        // git がない環境では確認できない
+        if !git(&["init", "-q"]) {
+            return;
+        }
+        assert!(git(&["commit", "-q", "--allow-empty", "-m", "init"]));
+        assert!(git(&[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature",
+            worktree.to_str().unwrap()
+        ]));
+
+        let config = Config {
+            expand_git_worktrees: true,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        // Cargo.toml で検出されたリポジトリの作業ツリーも追加される
+        assert_eq!(projects.len(), 2);
+        let added = projects
+            .iter()
+            .find(|p| p.marker == GIT_WORKTREE_MARKER)
+            .unwrap();
+        assert_eq!(
+            fs::canonicalize(&added.path).unwrap(),
+            fs::canonicalize(&worktree).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let output = "worktree /src/app\nHEAD 1234\nbranch refs/heads/main\n\n\
+                      worktree /src/app-feature\nHEAD 5678\nbranch refs/heads/feature\n\n\
+                      worktree /tmp/app hotfix\nHEAD 9abc\ndetached\n";
+
+        assert_eq!(
+            parse_worktree_list(output),
+            vec![
+                PathBuf::from("/src/app-feature"),
+                PathBuf::from("/tmp/app hotfix")
+            ]
+        );
+        assert!(parse_worktree_list("worktree /src/app\nbare\n").is_empty());
+        assert_eq!(base_marker(GIT_WORKTREE_MARKER), ".git");
+    }
+
+    #[test]
+    fn test_scan_nix_markers() {
+        let root = tempdir().unwrap();
+        create_test_project(&root.path().join("flake"), "flake.nix");
+        create_test_project(&root.path().join("shell"), "shell.nix");
+
+        // shell.nix は .git より優先度が低い
+        let repo = root.path().join("repo");
+        create_test_project(&repo, ".git");
+        File::create(repo.join("shell.nix")).unwrap();
+
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        let markers: Vec<&str> = projects.iter().map(|p| p.marker.as_str()).collect();
+        assert_eq!(markers, vec!["flake.nix", ".git", "shell.nix"]);
+        assert_eq!(projects[0].project_type, ProjectType::Nix);
+
+        let config = Config {
+            nix_markers: false,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+        assert_eq!(projects.len(), 2);
+    }
+
+    #[test]
+    fn test_scan_ci_markers() {
+        let root = tempdir().unwrap();
+        let workflows = root
+            .path()
+            .join("actions")
+            .join(".github")
+            .join("workflows");
+        fs::create_dir_all(&workflows).unwrap();
+        File::create(workflows.join("ci.yaml")).unwrap();
+        create_test_project(&root.path().join("jenkins"), "Jenkinsfile");
+
+        // ワークフローファイルのない .github/workflows は対象外
+        fs::create_dir_all(root.path().join("empty").join(".github").join("workflows")).unwrap();
+
+        let roots = [root.path().to_path_buf()];
+        let scanner = Scanner::from_config(&Config::default());
+        assert!(scanner.scan(&roots).unwrap().is_empty());
+
+        let config = Config {
+            detect_ci_markers: true,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&roots).unwrap();
+
+        let markers: Vec<&str> = projects.iter().map(|p| p.marker.as_str()).collect();
+        assert_eq!(markers, vec![".github/workflows", "Jenkinsfile"]);
+        assert!(projects.iter().all(|p| p.project_type == ProjectType::CI));
+    }
+
+    #[test]
+    fn test_scan_iac_markers() {
+        let root = tempdir().unwrap();
+        create_test_project(&root.path().join("network"), "vpc.tf");
+        create_test_project(&root.path().join("stack"), "Pulumi.yaml");
+        create_test_project(&root.path().join("app"), "Cargo.toml");
+
+        let roots = [root.path().to_path_buf()];
+        let scanner = Scanner::from_config(&Config::default());
+        assert_eq!(scanner.scan(&roots).unwrap().len(), 1);
+
+        let config = Config {
+            detect_iac: true,
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&roots).unwrap();
+
+        let markers: Vec<&str> = projects.iter().map(|p| p.marker.as_str()).collect();
+        assert_eq!(markers, vec!["Cargo.toml", "*.tf", "Pulumi.yaml"]);
+        assert_eq!(projects[1].project_type, ProjectType::Infrastructure);
+        assert_eq!(projects[2].project_type, ProjectType::Infrastructure);
+    }
+
+    #[test]
+    fn test_scan_glob_markers() {
+        let root = tempdir().unwrap();
+        create_test_project(&root.path().join("desktop"), "Desktop.sln");
+        create_test_project(&root.path().join("flutter_app"), "pubspec.yaml");
+        create_test_project(&root.path().join("zig-tool"), "build.zig");
+
+        // 名前の一部だけが一致するファイルは対象外
+        create_test_project(&root.path().join("notes"), "sln.txt");
+
+        let roots = [root.path().to_path_buf()];
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner.scan(&roots).unwrap();
+
+        let markers: Vec<&str> = projects.iter().map(|p| p.marker.as_str()).collect();
+        assert_eq!(markers, vec!["*.sln", "pubspec.yaml", "build.zig"]);
+        assert_eq!(projects[0].project_type, ProjectType::DotNet);
+        assert_eq!(projects[1].project_type, ProjectType::Dart);
+        assert_eq!(projects[2].project_type, ProjectType::Zig);
+    }
+
+    #[test]
+    fn test_dir_entries_contains() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("App.sln"), "").unwrap();
+        fs::create_dir_all(dir.path().join(".github/workflows")).unwrap();
+        fs::write(dir.path().join(".github/workflows/ci.yml"), "").unwrap();
+        fs::create_dir_all(dir.path().join("crates/core")).unwrap();
+        fs::write(dir.path().join("crates/core/Cargo.toml"), "").unwrap();
+
+        let entries = DirEntries::new(dir.path());
+        assert!(entries.contains("App.sln"));
+        assert!(entries.contains("*.sln"));
+        assert!(!entries.contains("*.csproj"));
+        assert!(entries.contains(".github/workflows/*.y*ml"));
+        assert!(entries.contains("crates/*/Cargo.toml"));
+        assert!(!entries.contains("crates/*/go.mod"));
+    }
+
+    #[test]
+    fn test_scan_exclude_markers() {
+        let root = tempdir().unwrap();
+        create_test_project(&root.path().join("repo"), ".git");
+        create_test_project(&root.path().join("package"), "package.json");
+
+        // .git と package.json の両方を持つものはリポジトリとして検出する
+        let both = root.path().join("web");
+        create_test_project(&both, ".git");
+        File::create(both.join("package.json")).unwrap();
+
+        let config = Config {
+            exclude_markers: vec!["package.json".to_string()],
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["repo", "web"]);
+        assert_eq!(projects[1].marker, ".git");
+    }
+
+    #[test]
+    fn test_retain_marker() {
+        let mut projects = vec![
+            make_project(Path::new("/r"), Path::new("/r/a"), ".git".to_string()),
+            make_project(Path::new("/r"), Path::new("/r/b"), "Cargo.toml".to_string()),
+            make_project(
+                Path::new("/r"),
+                Path::new("/r/b/c"),
+                CARGO_WORKSPACE_MEMBER_MARKER.to_string(),
+            ),
+        ];
+
+        retain_marker(&mut projects, None);
+        assert_eq!(projects.len(), 3);
+
+        retain_marker(&mut projects, Some("Cargo.toml"));
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_retain_tags() {
+        let mut projects = vec![
+            make_project(Path::new("/r"), Path::new("/r/a"), ".git".to_string()),
+            make_project(Path::new("/r"), Path::new("/r/b"), ".git".to_string()),
+            make_project(Path::new("/r"), Path::new("/r/c"), ".git".to_string()),
+        ];
+        let tags = HashMap::from([
+            (
+                PathBuf::from("/r/a"),
+                vec!["active".to_string(), "work".to_string()],
+            ),
+            (PathBuf::from("/r/b"), vec!["work".to_string()]),
+        ]);
+
+        retain_tags(&mut projects, &tags, &[]);
+        assert_eq!(projects.len(), 3);
+
+        retain_tags(&mut projects, &tags, &["work".to_string()]);
+        assert_eq!(projects.len(), 2);
+
+        retain_tags(
+            &mut projects,
+            &tags,
+            &["work".to_string(), "active".to_string()],
+        );
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["a"]);
+    }
+
+    #[test]
+    fn test_read_project_override() {
+        let dir = tempdir().unwrap();
+        assert_eq!(read_project_override(dir.path()), None);
+
+        fs::write(
+            dir.path().join(PROJECT_OVERRIDE_FILE_NAME),
+            "name = \"Billing API\"\ndescription = \"Payments backend\"\n\
+             tags = [\"Team Pay\", \"bad/tag\", \"team-pay\"]\neditor = \"goland\"\n\
+             open_in_terminal = true\n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_project_override(dir.path()),
+            Some(ProjectOverride {
+                name: Some("Billing API".to_string()),
+                description: Some("Payments backend".to_string()),
+                tags: vec!["team-pay".to_string()],
+                editor: Some("goland".to_string()),
+                open_in_terminal: Some(true),
+            })
+        );
+
+        // 読み込めないファイルは無視する
+        fs::write(dir.path().join(PROJECT_OVERRIDE_FILE_NAME), "name = [").unwrap();
+        assert_eq!(read_project_override(dir.path()), None);
+    }
+
+    #[test]
+    fn test_scan_applies_project_override() {
+        let root = tempdir().unwrap();
+        let project_dir = root.path().join("billing");
+        create_test_project(&project_dir, "go.mod");
+        fs::write(
+            project_dir.join(PROJECT_OVERRIDE_FILE_NAME),
+            "name = \"Billing API\"\ntags = [\"team\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::default();
+        let mut projects = Scanner::from_config(&config)
+            .scan(&[root.path().to_path_buf()])
+            .unwrap();
+        assert_eq!(projects[0].name, "Billing API");
+        assert_eq!(projects[0].project_type, ProjectType::Go);
+
+        // ファイルのタグは設定ファイルのタグと合わせて絞り込みに使う
+        let tags = HashMap::from([(project_dir.clone(), vec!["work".to_string()])]);
+        assert_eq!(projects[0].tags(&tags), vec!["work", "team"]);
+        retain_tags(
+            &mut projects,
+            &tags,
+            &["team".to_string(), "work".to_string()],
+        );
+        assert_eq!(projects.len(), 1);
+    }
+
+    #[test]
+    fn test_scan_applies_aliases() {
+        let root = tempdir().unwrap();
+        let project_dir = root.path().join("my-company-internal-tool-v2");
+        create_test_project(&project_dir, ".git");
+
+        let mut config = Config::default();
+        config.set_project_alias(&project_dir, "tool").unwrap();
+
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name, "tool");
+        assert_eq!(projects[0].path, project_dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_applies_aliases_after_rename() {
+        let root = tempdir().unwrap();
+        let project_dir = root.path().join("my-company-internal-tool-v2");
+        create_test_project(&project_dir, ".git");
+        create_test_project(&root.path().join("other"), ".git");
+
+        let mut config = Config::default();
+        config.set_project_alias(&project_dir, "tool").unwrap();
+
+        // 名前を変更しても同じディレクトリに別名が付く
+        let renamed = root.path().join("internal-tool-v3");
+        fs::rename(&project_dir, &renamed).unwrap();
+
+        let scanner = Scanner::from_config(&config);
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        let tool = projects.iter().find(|p| p.path == renamed).unwrap();
+        assert_eq!(tool.name, "tool");
+        assert!(projects.iter().any(|p| p.name == "other"));
+    }
+
+    #[test]
+    fn test_scan_overlapping_roots_prefers_deepest() {
+        let root = tempdir().unwrap();
+        let nested = root.path().join("group");
+        create_test_project(&nested.join("app"), ".git");
+
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner
+            .scan(&[root.path().to_path_buf(), nested.clone()])
+            .unwrap();
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].root, nested);
+    }
+
+    #[test]
+    fn test_scan_with_threads() {
+        let roots: Vec<_> = (0..4).map(|_| tempdir().unwrap()).collect();
+        for (i, root) in roots.iter().enumerate() {
+            create_test_project(&root.path().join(format!("project-{}", i)), ".git");
+            create_test_project(&root.path().join("group").join("api"), "Cargo.toml");
+            create_test_project(&root.path().join("group").join("web"), "package.json");
+        }
+        let root_paths: Vec<PathBuf> = roots.iter().map(|r| r.path().to_path_buf()).collect();
+
+        let parallel = Scanner::from_config(&Config::default())
+            .scan(&root_paths)
+            .unwrap();
+
+        // 1スレッドでは完全に直列で走査し、並列の場合と同じ結果になる
+        let config = Config {
+            scan_threads: Some(1),
+            ..Config::default()
+        };
+        let serial = Scanner::from_config(&config).scan(&root_paths).unwrap();
+
+        assert_eq!(serial.len(), 12);
+        assert_eq!(serial, parallel);
+
+        // 0 は未指定と同じくグローバルなスレッドプールを使う
+        let config = Config {
+            scan_threads: Some(0),
+            ..Config::default()
+        };
+        let scanner = Scanner::from_config(&config);
+        assert_eq!(scanner.threads, None);
+        assert_eq!(scanner.scan(&root_paths).unwrap(), parallel);
+    }
+
+    #[test]
+    fn test_scan_root_is_project() {
+        let root = tempdir().unwrap();
+        create_test_project(root.path(), "Cargo.toml");
+        create_test_project(&root.path().join("nested"), ".git");
+
+        let scanner = Scanner::from_config(&Config::default());
+        let projects = scanner.scan(&[root.path().to_path_buf()]).unwrap();
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].path, root.path());
+    }
+
+    /// フィルタのテストに使うプロジェクト一覧
+    fn filter_test_projects() -> Vec<Project> {
+        vec![
             Project {
                 path: PathBuf::from("/home/user/rust-project"),
+                root: PathBuf::from("/home/user"),
                 name: "rust-project".to_string(),
                 marker: "Cargo.toml".to_string(),
+                project_type: ProjectType::Rust,
+                modified: None,
+                overrides: None,
             },
             Project {
                 path: PathBuf::from("/home/user/node-app"),
+                root: PathBuf::from("/home/user"),
                 name: "node-app".to_string(),
                 marker: "package.json".to_string(),
+                project_type: ProjectType::Node,
+                modified: None,
+                overrides: None,
             },
-        ];
+        ]
+    }
+
+    #[test]
+    fn test_filter_projects() {
+        let projects = filter_test_projects();
 
         // "rust" でフィルタ
-        let filtered = filter_projects(&projects, "rust");
+        let filtered = filter_projects(&projects, "rust", CaseSensitivity::Insensitive);
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].name, "rust-project");
 
         // 空クエリは全件
-        let all = filter_projects(&projects, "");
+        let all = filter_projects(&projects, "", CaseSensitivity::Insensitive);
         assert_eq!(all.len(), 2);
     }
+
+    #[test]
+    fn test_filter_projects_case_sensitive() {
+        let projects = filter_test_projects();
+
+        // 大文字小文字の区別は名前とパスの両方に適用される
+        let insensitive = filter_projects(&projects, "NODE", CaseSensitivity::Insensitive);
+        assert_eq!(insensitive.len(), 1);
+        assert!(filter_projects(&projects, "NODE", CaseSensitivity::Sensitive).is_empty());
+        assert!(filter_projects(&projects, "/HOME", CaseSensitivity::Sensitive).is_empty());
+        let sensitive = filter_projects(&projects, "/home node", CaseSensitivity::Sensitive);
+        assert_eq!(sensitive.len(), 1);
+    }
+
+    #[test]
+    fn test_filter_by_regex() {
+        let projects = filter_test_projects();
+
+        // 正規表現は名前とフルパスのどちらにも一致する
+        let filtered = filter_by_regex(&projects, "^node-").unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name, "node-app");
+        assert_eq!(filter_by_regex(&projects, "^/home/user/").unwrap().len(), 2);
+
+        let err = filter_by_regex(&projects, "rust(").unwrap_err();
+        assert!(err.to_string().contains("rust("));
+    }
+
+    #[test]
+    fn test_sort_projects() {
+        let make = |name: &str, path: &str, project_type: ProjectType| Project {
+            path: PathBuf::from(path),
+            root: PathBuf::from("/"),
+            name: name.to_string(),
+            marker: String::new(),
+            project_type,
+            modified: None,
+            overrides: None,
+        };
+        let mut projects = vec![
+            make("beta", "/a/beta", ProjectType::Rust),
+            make("Alpha", "/z/alpha", ProjectType::Node),
+            make("gamma", "/m/gamma", ProjectType::Rust),
+        ];
+
+        sort_projects(&mut projects, SortCriterion::Name);
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Alpha", "beta", "gamma"]);
+
+        sort_projects(&mut projects, SortCriterion::Path);
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["beta", "gamma", "Alpha"]);
+
+        sort_projects(&mut projects, SortCriterion::Type);
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["beta", "gamma", "Alpha"]);
+
+        projects[0].modified = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(200));
+        projects[2].modified = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(100));
+
+        sort_projects(&mut projects, SortCriterion::Modified);
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["beta", "Alpha", "gamma"]);
+
+        sort_projects(&mut projects, SortCriterion::Age);
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn test_filter_projects_keeps_order_without_fuzzy_fallback() {
+        let projects: Vec<Project> = ["/src/zeta-api", "/src/api", "/src/tools/web-app"]
+            .iter()
+            .map(|path| Project::from_path(Path::new(path)))
+            .collect();
+        let names = |query: &str| -> Vec<String> {
+            filter_projects(&projects, query, CaseSensitivity::Insensitive)
+                .into_iter()
+                .map(|p| p.name.clone())
+                .collect()
+        };
+
+        // 並び順（--sort）は一致度で変えない
+        assert_eq!(names("api"), ["zeta-api", "api"]);
+        // 部分文字列で一致しない語はあいまい検索で補わない
+        assert!(names("wbap").is_empty());
+    }
+
+    #[test]
+    fn test_best_match() {
+        let projects: Vec<Project> = ["/src/api", "/src/apiary", "/src/legacy-api-gateway"]
+            .iter()
+            .map(|path| Project::from_path(Path::new(path)))
+            .collect();
+        let best = |query: &str| {
+            best_match(&rank_projects(
+                &projects,
+                query,
+                CaseSensitivity::Insensitive,
+            ))
+            .map(|p| p.name.clone())
+        };
+
+        // 名前の完全一致は他より十分に高い
+        assert_eq!(best("api").as_deref(), Some("api"));
+        // 一致が1つだけ
+        assert_eq!(best("gateway").as_deref(), Some("legacy-api-gateway"));
+        // 同程度に一致するものが複数ある場合は選ばない
+        assert_eq!(best("ap"), None);
+        assert_eq!(best("missing"), None);
+    }
+
+    #[test]
+    fn test_rank_projects_best_match_first() {
+        let projects: Vec<Project> = [
+            "/src/legacy/legacy-api-gateway",
+            "/src/apiary",
+            "/src/api/server",
+            "/src/api",
+            "/src/tools/web-app",
+        ]
+        .iter()
+        .map(|path| Project::from_path(Path::new(path)))
+        .collect();
+        let names = |query: &str| -> Vec<String> {
+            rank_projects(&projects, query, CaseSensitivity::Insensitive)
+                .into_iter()
+                .map(|(_, p)| p.name.clone())
+                .collect()
+        };
+
+        // 名前の完全一致 > 名前の先頭に一致 > 名前の途中に一致 > パスだけに一致
+        assert_eq!(
+            names("api"),
+            ["api", "apiary", "legacy-api-gateway", "server"]
+        );
+        // 複数の語はすべて一致する必要がある
+        assert_eq!(names("api server"), ["server"]);
+        // 部分文字列で一致しない場合は名前へのあいまい検索で最も近いものを上位にする
+        assert_eq!(names("lgcyapi"), ["legacy-api-gateway"]);
+        assert_eq!(names("wbap"), ["web-app"]);
+        // あいまい検索はパスには適用しない
+        assert!(names("srctls").is_empty());
+    }
+
+    #[test]
+    fn test_move_pinned_first() {
+        let mut projects: Vec<Project> = ["/a/alpha", "/b/beta", "/c/gamma", "/d/delta"]
+            .iter()
+            .map(|path| Project::from_path(Path::new(path)))
+            .collect();
+        let pinned = BTreeSet::from([PathBuf::from("/d/delta"), PathBuf::from("/b/beta")]);
+
+        move_pinned_first(&mut projects, &pinned);
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["beta", "delta", "alpha", "gamma"]);
+        assert_eq!(projects[0].root, PathBuf::from("/b"));
+        assert_eq!(projects[0].project_type, ProjectType::Generic);
+    }
+
+    #[test]
+    fn test_sort_criterion_from_str() {
+        assert_eq!("name".parse(), Ok(SortCriterion::Name));
+        assert_eq!("Modified".parse(), Ok(SortCriterion::Modified));
+        assert!("size".parse::<SortCriterion>().is_err());
+        assert_eq!(
+            SortCriterion::Frecency.as_str().parse(),
+            Ok(SortCriterion::Frecency)
+        );
+    }
+
+    #[test]
+    fn test_detect_type() {
+        assert_eq!(detect_type("Cargo.toml"), ProjectType::Rust);
+        assert_eq!(detect_type("package.json"), ProjectType::Node);
+        assert_eq!(detect_type("go.mod"), ProjectType::Go);
+        assert_eq!(detect_type("pyproject.toml"), ProjectType::Python);
+        assert_eq!(detect_type("build.gradle"), ProjectType::Java);
+        assert_eq!(detect_type("Gemfile"), ProjectType::Ruby);
+        assert_eq!(detect_type("mix.exs"), ProjectType::Elixir);
+        assert_eq!(detect_type("CMakeLists.txt"), ProjectType::CMake);
+        assert_eq!(detect_type("flake.nix"), ProjectType::Nix);
+        assert_eq!(detect_type("shell.nix"), ProjectType::Nix);
+        assert_eq!(detect_type("pubspec.yaml"), ProjectType::Dart);
+        assert_eq!(detect_type("*.sln"), ProjectType::DotNet);
+        assert_eq!(detect_type("App.csproj"), ProjectType::DotNet);
+        assert_eq!(detect_type("Package.swift"), ProjectType::Swift);
+        assert_eq!(detect_type("build.zig"), ProjectType::Zig);
+        assert_eq!(detect_type(".git"), ProjectType::Generic);
+    }
+
+    #[test]
+    fn test_project_type_from_str() {
+        for project_type in ProjectType::ALL {
+            assert_eq!(project_type.as_str().parse(), Ok(project_type));
+        }
+        assert_eq!("Rust".parse(), Ok(ProjectType::Rust));
+        assert!("cobol".parse::<ProjectType>().is_err());
+    }
+
+    #[test]
+    fn test_project_type_icons() {
+        let expected = [
+            (ProjectType::Rust, "🦀"),
+            (ProjectType::Node, "🟢"),
+            (ProjectType::Go, "🐹"),
+            (ProjectType::Python, "🐍"),
+            (ProjectType::Java, "☕"),
+            (ProjectType::Ruby, "💎"),
+            (ProjectType::Elixir, "💧"),
+            (ProjectType::CMake, "⚙"),
+            (ProjectType::Nix, "❄"),
+            (ProjectType::CI, "🔧"),
+            (ProjectType::Infrastructure, "🏗"),
+            (ProjectType::Generic, "📁"),
+        ];
+
+        for (project_type, icon) in expected {
+            assert_eq!(project_type.icon(), icon);
+        }
+    }
 }
@@ -0,0 +1,143 @@
+//! シェル連携モジュール
+//!
+//! `quick-proj init <SHELL>` で出力する、選択したプロジェクトへ cd する
+//! シェル関数とキーバインドを生成します。
+
+/// シェル関数を生成する対象のシェル
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
+}
+
+impl std::str::FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "nushell" | "nu" => Ok(Shell::Nushell),
+            _ => Err(format!(
+                "unknown shell '{}' (expected: bash, zsh, fish, nushell)",
+                s
+            )),
+        }
+    }
+}
+
+/// bash / zsh 共通の `qp` 関数
+///
+/// 引数は `--query` と `--first` で渡して選択UIなしで移動し、一致するプロジェクトが
+/// ない場合や引数がない場合は選択UIで選びます。`--cd` が出力した cd コマンドを eval します。
+const POSIX_FUNCTION: &str = r#"qp() {
+    local cmd
+    if [ "$#" -eq 0 ] || ! cmd="$(command quick-proj --cd --query "$*" --first 2>/dev/null)" || [ -z "$cmd" ]; then
+        cmd="$(command quick-proj --cd)"
+    fi
+    [ -n "$cmd" ] && eval "$cmd"
+}
+"#;
+
+/// bash のキーバインド（Ctrl+G）
+const BASH_BINDING: &str = r#"if [[ $- == *i* ]]; then
+    bind -x '"\C-g": qp'
+fi
+"#;
+
+/// zsh のキーバインド（Ctrl+G）
+const ZSH_BINDING: &str = r#"_quick_proj_widget() {
+    qp </dev/tty
+    zle reset-prompt
+}
+zle -N _quick_proj_widget
+bindkey '^G' _quick_proj_widget
+"#;
+
+/// fish の `qp` 関数とキーバインド（Ctrl+G）
+///
+/// 引数の扱いは bash / zsh の `qp` と同じです。
+const FISH_SCRIPT: &str = r#"function qp --description 'Select a project with quick-proj and cd into it'
+    set -l cmd
+    if test (count $argv) -gt 0
+        set cmd (command quick-proj --cd --query "$argv" --first 2>/dev/null)
+    end
+    if test -z "$cmd"
+        set cmd (command quick-proj --cd)
+    end
+    test -n "$cmd"
+    and eval $cmd
+end
+
+bind \cg 'qp; commandline -f repaint'
+"#;
+
+/// nushell の `qp` コマンドとキーバインド（Ctrl+G）
+///
+/// 引数の扱いは bash / zsh の `qp` と同じです。nushell は文字列を eval できないため、
+/// `--cd` の出力からパスを取り出して cd します。
+const NUSHELL_SCRIPT: &str = r#"def --env qp [...query: string] {
+    mut out = ""
+    if not ($query | is-empty) {
+        $out = (^quick-proj --cd --query ($query | str join ' ') --first | complete | get stdout | str trim)
+    }
+    if ($out | is-empty) {
+        $out = (^quick-proj --cd | str trim)
+    }
+    if ($out | is-empty) { return }
+    mut dir = ($out | str replace -r '^cd ' '')
+    if ($dir | str starts-with "'") {
+        $dir = ($dir | str substring 1..-2 | str replace -a "'\\''" "'")
+    }
+    cd $dir
+}
+
+$env.config = ($env.config | upsert keybindings ($env.config.keybindings | append {
+    name: quick_proj
+    modifier: control
+    keycode: char_g
+    mode: [emacs vi_normal vi_insert]
+    event: { send: executehostcommand cmd: "qp" }
+}))
+"#;
+
+/// 指定したシェル向けの初期化スクリプトを生成
+pub fn script(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => format!("{}\n{}", POSIX_FUNCTION, BASH_BINDING),
+        Shell::Zsh => format!("{}\n{}", POSIX_FUNCTION, ZSH_BINDING),
+        Shell::Fish => FISH_SCRIPT.to_string(),
+        Shell::Nushell => NUSHELL_SCRIPT.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_parse() {
+        assert_eq!("bash".parse::<Shell>(), Ok(Shell::Bash));
+        assert_eq!("ZSH".parse::<Shell>(), Ok(Shell::Zsh));
+        assert_eq!("nu".parse::<Shell>(), Ok(Shell::Nushell));
+        assert!("powershell".parse::<Shell>().is_err());
+    }
+
+    #[test]
+    fn test_script_uses_cd_mode() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::Nushell] {
+            let script = script(shell);
+            assert!(script.contains("qp"), "{:?}", shell);
+            // 引数があれば選択UIなしで先頭の一致に移動する
+            assert!(script.contains("quick-proj --cd --query"), "{:?}", shell);
+            assert!(script.contains("--first"), "{:?}", shell);
+            // 一致しなければ選択UIで選ぶ
+            assert_eq!(script.matches("quick-proj --cd").count(), 2, "{:?}", shell);
+        }
+        assert!(script(Shell::Bash).contains("bind -x"));
+        assert!(script(Shell::Zsh).contains("bindkey"));
+    }
+}
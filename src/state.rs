@@ -0,0 +1,163 @@
+//! 状態管理モジュール
+//!
+//! 実行をまたいで引き継ぐ情報（最後に使用したプロファイルやプロジェクトなど）を
+//! データディレクトリ（既定はOS標準、`data_dir` で変更可能）にTOMLで保存します。
+
+use crate::config::data_path;
+use crate::scanner::Project;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 状態ファイル名
+const STATE_FILE_NAME: &str = "state.toml";
+
+/// 実行をまたいで保持する状態
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct State {
+    /// 最後に使用したプロファイル名
+    #[serde(default)]
+    pub last_profile: Option<String>,
+
+    /// 最後に開いたプロジェクト
+    #[serde(default)]
+    pub last_project: Option<Project>,
+
+    /// 選択UIで最後に入力した検索クエリ
+    #[serde(default)]
+    pub last_query: Option<String>,
+
+    /// ピン留めしたプロジェクトのパス（選択UIで常に先頭に表示）
+    #[serde(default)]
+    pub pinned: BTreeSet<PathBuf>,
+
+    /// 初回セットアップを保存せずに終了したか（次回から自動では表示しない）
+    #[serde(default)]
+    pub setup_declined: bool,
+}
+
+impl State {
+    /// 状態ファイルを読み込む
+    ///
+    /// ファイルが存在しない場合は空の状態を返します。
+    pub fn load() -> Result<Self> {
+        let state_path = Self::state_path()?;
+
+        if !state_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&state_path)
+            .with_context(|| format!("Failed to read state file: {}", state_path.display()))?;
+
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse state file: {}", state_path.display()))
+    }
+
+    /// 状態ファイルに保存する
+    pub fn save(&self) -> Result<()> {
+        let state_path = Self::state_path()?;
+
+        if let Some(parent) = state_path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create data directory: {}", parent.display())
+            })?;
+        }
+
+        let content = toml::to_string_pretty(self).context("Failed to serialize state")?;
+
+        fs::write(&state_path, content)
+            .with_context(|| format!("Failed to write state file: {}", state_path.display()))?;
+
+        Ok(())
+    }
+
+    /// 状態ファイルのパスを取得
+    pub fn state_path() -> Result<PathBuf> {
+        data_path(STATE_FILE_NAME)
+    }
+
+    /// プロジェクトをピン留めする
+    ///
+    /// すでにピン留めしている場合は `false` を返します。
+    pub fn pin(&mut self, path: &Path) -> bool {
+        self.pinned.insert(path.to_path_buf())
+    }
+
+    /// プロジェクトのピン留めを解除する
+    ///
+    /// ピン留めしていない場合は `false` を返します。
+    pub fn unpin(&mut self, path: &Path) -> bool {
+        self.pinned.remove(path)
+    }
+
+    /// 移動・削除されて存在しなくなったピン留めのパス
+    pub fn stale_pins(&self) -> Vec<PathBuf> {
+        self.pinned
+            .iter()
+            .filter(|path| !path.is_dir())
+            .cloned()
+            .collect()
+    }
+
+    /// 存在しなくなったピン留めを解除し、解除した数を返す
+    pub fn prune_pins(&mut self) -> usize {
+        let before = self.pinned.len();
+        self.pinned.retain(|path| path.is_dir());
+        before - self.pinned.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_roundtrip() {
+        let state = State {
+            last_profile: Some("work".to_string()),
+            last_project: Some(Project {
+                path: PathBuf::from("/src/app"),
+                root: PathBuf::from("/src"),
+                name: "app".to_string(),
+                marker: "Cargo.toml".to_string(),
+                project_type: crate::scanner::ProjectType::Rust,
+                modified: None,
+                overrides: None,
+            }),
+            last_query: Some("api".to_string()),
+            pinned: BTreeSet::from([PathBuf::from("/src/app")]),
+            setup_declined: true,
+        };
+
+        let content = toml::to_string_pretty(&state).unwrap();
+        let parsed: State = toml::from_str(&content).unwrap();
+        assert_eq!(parsed, state);
+    }
+
+    #[test]
+    fn test_pin_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("moved-away");
+        let mut state = State::default();
+
+        assert!(state.pin(dir.path()));
+        assert!(!state.pin(dir.path()));
+        assert!(state.pin(&missing));
+        assert_eq!(state.stale_pins(), vec![missing.clone()]);
+
+        assert_eq!(state.prune_pins(), 1);
+        assert!(state.stale_pins().is_empty());
+        assert!(state.unpin(dir.path()));
+        assert!(!state.unpin(dir.path()));
+        assert!(state.pinned.is_empty());
+    }
+
+    #[test]
+    fn test_state_empty() {
+        let state: State = toml::from_str("").unwrap();
+        assert_eq!(state, State::default());
+    }
+}
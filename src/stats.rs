@@ -0,0 +1,169 @@
+//! 統計モジュール
+//!
+//! スキャン結果を種別やルートパスごとに集計します。
+
+use crate::scanner::{Project, ProjectType};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// プロジェクトの集計結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectStats {
+    /// プロジェクトの総数
+    pub total: usize,
+    /// 種別ごとの件数（件数の多い順）
+    pub by_type: Vec<(ProjectType, usize)>,
+    /// 検出マーカーごとの件数（件数の多い順）
+    pub by_marker: Vec<(String, usize)>,
+    /// ルートパスごとの件数（設定の順）
+    pub by_root: Vec<(PathBuf, usize)>,
+    /// ルートパスから見た最も深いプロジェクトの階層
+    pub deepest: usize,
+}
+
+impl ProjectStats {
+    /// スキャン結果を集計
+    pub fn compute(projects: &[Project], root_paths: &[PathBuf]) -> Self {
+        let mut by_type: Vec<(ProjectType, usize)> = ProjectType::ALL
+            .into_iter()
+            .map(|t| (t, projects.iter().filter(|p| p.project_type == t).count()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        by_type.sort_by_key(|(t, count)| (std::cmp::Reverse(*count), *t));
+
+        let mut marker_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for project in projects.iter().filter(|p| !p.marker.is_empty()) {
+            *marker_counts.entry(&project.marker).or_default() += 1;
+        }
+        let mut by_marker: Vec<(String, usize)> = marker_counts
+            .into_iter()
+            .map(|(marker, count)| (marker.to_string(), count))
+            .collect();
+        // 件数が同じ場合はマーカー名の順（BTreeMap の順）を保つ
+        by_marker.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let mut by_root: Vec<(PathBuf, usize)> =
+            root_paths.iter().map(|root| (root.clone(), 0)).collect();
+        let mut deepest = 0;
+
+        for project in projects {
+            if let Some(idx) = root_paths.iter().position(|root| *root == project.root) {
+                by_root[idx].1 += 1;
+            }
+
+            let depth = project
+                .path
+                .strip_prefix(&project.root)
+                .map(|rel| rel.components().count())
+                .unwrap_or(0);
+            deepest = deepest.max(depth);
+        }
+
+        Self {
+            total: projects.len(),
+            by_type,
+            by_marker,
+            by_root,
+            deepest,
+        }
+    }
+
+    /// プロジェクト数が最も多いルートパス（同数の場合は設定で先にあるもの）
+    pub fn largest_root(&self) -> Option<&(PathBuf, usize)> {
+        self.by_root
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .reduce(|largest, entry| if entry.1 > largest.1 { entry } else { largest })
+    }
+
+    /// 全体に対する割合（%）
+    pub fn percentage(&self, count: usize) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        count as f64 * 100.0 / self.total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_project(root: &str, path: &str, project_type: ProjectType) -> Project {
+        let path = PathBuf::from(path);
+        let marker = match project_type {
+            ProjectType::Rust => "Cargo.toml",
+            ProjectType::Node => "package.json",
+            ProjectType::Go => "go.mod",
+            _ => "",
+        };
+        Project {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            path,
+            root: PathBuf::from(root),
+            marker: marker.to_string(),
+            project_type,
+            modified: None,
+            overrides: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let projects = vec![
+            make_project("/src", "/src/a", ProjectType::Rust),
+            make_project("/src", "/src/group/b", ProjectType::Node),
+            make_project("/work", "/work/c", ProjectType::Rust),
+        ];
+        let roots = vec![PathBuf::from("/src"), PathBuf::from("/work")];
+
+        let stats = ProjectStats::compute(&projects, &roots);
+        assert_eq!(stats.total, 3);
+        assert_eq!(
+            stats.by_type,
+            vec![(ProjectType::Rust, 2), (ProjectType::Node, 1)]
+        );
+        assert_eq!(
+            stats.by_root,
+            vec![(PathBuf::from("/src"), 2), (PathBuf::from("/work"), 1)]
+        );
+        assert_eq!(
+            stats.by_marker,
+            vec![
+                ("Cargo.toml".to_string(), 2),
+                ("package.json".to_string(), 1)
+            ]
+        );
+        assert_eq!(stats.deepest, 2);
+        assert_eq!(stats.largest_root(), Some(&(PathBuf::from("/src"), 2)));
+    }
+
+    #[test]
+    fn test_largest_root() {
+        let roots = vec![PathBuf::from("/src"), PathBuf::from("/work")];
+        assert_eq!(ProjectStats::compute(&[], &roots).largest_root(), None);
+
+        // 同数の場合は設定で先にあるルートを選ぶ
+        let projects = vec![
+            make_project("/work", "/work/a", ProjectType::Rust),
+            make_project("/src", "/src/b", ProjectType::Go),
+        ];
+        let stats = ProjectStats::compute(&projects, &roots);
+        assert_eq!(stats.largest_root(), Some(&(PathBuf::from("/src"), 1)));
+    }
+
+    #[test]
+    fn test_percentage() {
+        let stats = ProjectStats::compute(&[], &[]);
+        assert_eq!(stats.percentage(1), 0.0);
+
+        let projects = vec![
+            make_project("/src", "/src/a", ProjectType::Rust),
+            make_project("/src", "/src/b", ProjectType::Go),
+            make_project("/src", "/src/c", ProjectType::Go),
+            make_project("/src", "/src/d", ProjectType::Go),
+        ];
+        let stats = ProjectStats::compute(&projects, &[]);
+        assert_eq!(stats.percentage(1), 25.0);
+    }
+}
@@ -0,0 +1,114 @@
+//! tmux連携モジュール
+//!
+//! プロジェクトごとにtmuxセッションを作成・切り替えます。
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// tmuxのセッション名に使用できない文字
+const INVALID_SESSION_CHARS: &[char] = &['.', ':'];
+
+/// プロジェクト名からセッション名を生成
+///
+/// tmuxが特別扱いする `.` と `:` は `_` に置き換えます。
+pub fn session_name(prefix: Option<&str>, project_name: &str) -> String {
+    let name = format!("{}{}", prefix.unwrap_or(""), project_name);
+    name.replace(INVALID_SESSION_CHARS, "_")
+}
+
+/// tmuxの中で実行されているかどうか
+pub fn is_inside_tmux() -> bool {
+    std::env::var_os("TMUX").is_some()
+}
+
+/// 指定した名前のセッションが存在するかチェック
+pub fn has_session(name: &str) -> bool {
+    Command::new("tmux")
+        .args(["has-session", "-t", &exact_target(name)])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// 作業ディレクトリを指定してデタッチ状態のセッションを作成
+pub fn new_session(name: &str, dir: &Path) -> Result<()> {
+    let status = Command::new("tmux")
+        .args(["new-session", "-d", "-s", name, "-c"])
+        .arg(dir)
+        .status()
+        .context("Failed to run tmux. Is it installed and in PATH?")?;
+
+    if !status.success() {
+        bail!("tmux new-session failed for session '{}'", name);
+    }
+
+    Ok(())
+}
+
+/// セッションでコマンドを実行
+pub fn send_command(name: &str, command_line: &str) -> Result<()> {
+    let status = Command::new("tmux")
+        .args([
+            "send-keys",
+            "-t",
+            &exact_target(name),
+            command_line,
+            "Enter",
+        ])
+        .status()
+        .context("Failed to run tmux send-keys")?;
+
+    if !status.success() {
+        bail!("tmux send-keys failed for session '{}'", name);
+    }
+
+    Ok(())
+}
+
+/// セッションに接続（tmux内では switch-client、外では attach）
+pub fn attach(name: &str) -> Result<()> {
+    let subcommand = if is_inside_tmux() {
+        "switch-client"
+    } else {
+        "attach"
+    };
+
+    let status = Command::new("tmux")
+        .args([subcommand, "-t", &exact_target(name)])
+        .status()
+        .with_context(|| format!("Failed to run tmux {}", subcommand))?;
+
+    if !status.success() {
+        bail!("tmux {} failed for session '{}'", subcommand, name);
+    }
+
+    Ok(())
+}
+
+/// 前方一致ではなく完全一致でセッションを指定する
+fn exact_target(name: &str) -> String {
+    format!("={}", name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_name() {
+        assert_eq!(session_name(None, "my-app"), "my-app");
+        assert_eq!(session_name(Some("work/"), "my-app"), "work/my-app");
+    }
+
+    #[test]
+    fn test_session_name_sanitizes() {
+        assert_eq!(session_name(None, "example.com"), "example_com");
+        assert_eq!(session_name(Some("a:"), "b.c"), "a_b_c");
+    }
+
+    #[test]
+    fn test_exact_target() {
+        assert_eq!(exact_target("api"), "=api");
+    }
+}
@@ -1,90 +1,2005 @@
 //! ユーザーインターフェースモジュール
 //!
-//! dialoguerを使用したインタラクティブな選択UIを提供します。
+//! crosstermで描画する組み込みの選択UIと、skim・fzf・番号入力による選択、
+//! dialoguerによる複数選択・確認のプロンプトを提供します。
 
-use crate::scanner::Project;
+use crate::clipboard;
+use crate::config::{Config, GroupMode, SelectorTheme};
+use crate::doctor::{Diagnostic, Severity};
+use crate::history::{History, ProjectHistory};
+use crate::launcher;
+use crate::preview;
+use crate::scanner::{
+    base_marker, CaseSensitivity, Project, ProjectType, ScanError, SortCriterion,
+};
+use crate::stats::ProjectStats;
+use crate::watch::ProjectDiff;
 use anyhow::{Context, Result};
-use colored::Colorize;
+use colored::{Color, ColoredString, Colorize};
 use console::Term;
-use dialoguer::{theme::ColorfulTheme, FuzzySelect};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue};
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+use dialoguer::{Confirm, MultiSelect};
+use fuzzy_matcher::FuzzyMatcher;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// プレビュー付きの選択UIを使う端末の最小幅
+const PREVIEW_MIN_WIDTH: u16 = 100;
+
+/// 選択UIでステータスメッセージを表示する時間
+const STATUS_DURATION: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// 選択UIで数字キーに対応するプロジェクトがないときに警告を表示する時間
+const FLASH_DURATION: std::time::Duration = std::time::Duration::from_millis(400);
+
+/// 再スキャン中のスピナーの表示
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// 再スキャン中にスピナーを進める間隔
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 選択UIの Ctrl+R で呼び出す再スキャン（選択UIに表示するプロジェクト一覧を返す）
+///
+/// 選択UIを操作できるよう別スレッドで実行し、選択UIを閉じるときは終了を待たずに結果を捨てます。
+/// そのため借用を含まず、スレッド間で共有できる必要があります。
+pub type Rescan = Arc<dyn Fn() -> Result<Vec<Project>> + Send + Sync>;
+
+/// skim・fzfのプレビューコマンド（READMEの先頭、なければファイル一覧を表示）
+///
+/// `{3}` は入力行の3番目のフィールド（プロジェクトのパス）に置換されます。
+/// Nix flake で `nix` が使える場合は、先頭に flake の説明などのメタデータを表示します。
+const PREVIEW_COMMAND: &str = "\
+    if [ -f {3}/flake.nix ] && command -v nix >/dev/null 2>&1; then \
+        nix flake metadata --no-write-lock-file {3} 2>/dev/null | head -n 4; echo; \
+    fi; \
+    head -n 40 {3}/README.md 2>/dev/null || ls -1A {3}";
+
+/// 色付き出力の設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    /// 標準出力が端末で、`NO_COLOR` が未設定の場合のみ色付け（デフォルト）
+    #[default]
+    Auto,
+    /// 常に色付け
+    Always,
+    /// 色付けしない
+    Never,
+}
+
+impl std::str::FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(format!(
+                "unknown color mode '{}' (expected: auto, always, never)",
+                s
+            )),
+        }
+    }
+}
+
+/// 一覧表示（scan）の出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListFormat {
+    /// 見出し付きの一覧（デフォルト）
+    #[default]
+    List,
+    /// 列をそろえたパイプ区切りの表（Markdownにそのまま貼り付け可能）
+    Table,
+    /// RFC 4180 形式のCSV（表計算ソフト向け）
+    Csv,
+}
+
+impl std::str::FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "list" => Ok(ListFormat::List),
+            "table" => Ok(ListFormat::Table),
+            "csv" => Ok(ListFormat::Csv),
+            _ => Err(format!(
+                "unknown list format '{}' (expected: list, table, csv)",
+                s
+            )),
+        }
+    }
+}
+
+/// 色付き出力の有効・無効をすべての出力に適用
+pub fn init_colors(mode: ColorMode) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let enabled = should_colorize(mode, no_color, Term::stdout().is_term());
+
+    colored::control::set_override(enabled);
+    console::set_colors_enabled(enabled);
+    // 選択UIは標準エラー出力に描画するため、端末の判定は標準エラー出力で行う
+    // （`--cd` で標準出力をパイプしていても選択UIは色付けする）
+    console::set_colors_enabled_stderr(should_colorize(mode, no_color, Term::stderr().is_term()));
+}
+
+/// 色付けするかどうかを判定
+fn should_colorize(mode: ColorMode, no_color: bool, is_tty: bool) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => !no_color && is_tty,
+    }
+}
+
+/// プロジェクト一覧の表示オプション
+#[derive(Debug, Clone, Default)]
+pub struct DisplayOptions {
+    /// プロジェクト種別のアイコンを表示するか
+    pub show_icons: bool,
+    /// 選択UIでのグループ化方法
+    pub group_by: Option<GroupMode>,
+    /// プロジェクトの並び順（選択UIのステータスバーに表示）
+    pub sort: SortCriterion,
+    /// グループ化に使用するルートパス（設定ファイルの順序）
+    pub root_paths: Vec<PathBuf>,
+    /// 表示テンプレート（未指定時は "名前 [タグ] (パス)" 形式）
+    pub display_format: Option<String>,
+    /// 選択UIでREADMEのプレビューを表示するか
+    pub show_preview: bool,
+    /// 選択UIの検索方式の初期値
+    pub match_mode: MatchMode,
+    /// 選択UIの検索で大文字と小文字を区別するかの初期値
+    pub case_sensitivity: CaseSensitivity,
+    /// プロジェクトのタグ（キーはプロジェクトの実体パス）
+    pub tags: HashMap<PathBuf, Vec<String>>,
+    /// 一覧表示で最終更新からの経過時間を表示するか
+    pub show_age: bool,
+    /// 選択UIのプロンプト（`{count}` はプロジェクト数に置き換える）
+    pub prompt: String,
+    /// 選択UIの配色
+    pub theme: SelectorTheme,
+    /// 表示テンプレートの `{open_count}` / `{last_opened}` に使う起動履歴（キーはプロジェクトのパス）
+    pub history: HashMap<PathBuf, ProjectHistory>,
+    /// ピン留めしたプロジェクトのパス（`★` を付けて表示）
+    pub pinned: BTreeSet<PathBuf>,
+    /// 選択UIの検索欄が空のときに数字キー（1〜9）を押すと、表示中のN番目のプロジェクトをすぐに開くか
+    pub quick_open: bool,
+    /// 選択UIの Ctrl+F で使うファイルマネージャー（`None` の場合はOS標準のコマンド）
+    pub file_manager: Option<String>,
+}
+
+/// 選択UIの検索方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchMode {
+    /// あいまい検索
+    #[default]
+    Fuzzy,
+    /// 完全一致（大文字小文字を区別しない部分文字列）検索
+    Exact,
+}
+
+impl MatchMode {
+    /// もう一方の検索方式を返す
+    fn toggle(self) -> Self {
+        match self {
+            MatchMode::Fuzzy => MatchMode::Exact,
+            MatchMode::Exact => MatchMode::Fuzzy,
+        }
+    }
+
+    /// プロンプトに表示する名前
+    fn label(self) -> &'static str {
+        match self {
+            MatchMode::Fuzzy => "fuzzy",
+            MatchMode::Exact => "exact",
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// 設定から表示オプションを作成
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            show_icons: config.show_icons,
+            group_by: config.group_by.filter(|mode| *mode != GroupMode::None),
+            sort: config.default_sort.parse().unwrap_or_default(),
+            root_paths: config.root_paths.clone(),
+            display_format: config.display_format.clone(),
+            show_preview: config.show_preview,
+            match_mode: if config.exact_match {
+                MatchMode::Exact
+            } else {
+                MatchMode::Fuzzy
+            },
+            case_sensitivity: CaseSensitivity::from_flag(config.case_sensitive),
+            tags: config.project_tags.clone(),
+            show_age: config.show_age,
+            prompt: config.prompt.clone(),
+            theme: config.theme,
+            history: HashMap::new(),
+            pinned: BTreeSet::new(),
+            quick_open: config.quick_open_digits,
+            file_manager: config.file_manager.clone(),
+        }
+    }
+
+    /// 表示テンプレートが起動履歴のプレースホルダーを使っているか
+    pub fn needs_history(&self) -> bool {
+        self.display_format.as_deref().is_some_and(|template| {
+            template.contains("{open_count}") || template.contains("{last_opened}")
+        })
+    }
+
+    /// 表示テンプレートに使う起動履歴を設定
+    pub fn with_history(mut self, history: History) -> Self {
+        self.history = history
+            .projects
+            .into_iter()
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+        self
+    }
+
+    /// 並び順を設定（`--sort` で設定ファイルの指定を上書きした場合など）
+    pub fn with_sort(mut self, sort: SortCriterion) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// ピン留めしたプロジェクトを設定
+    pub fn with_pinned(mut self, pinned: BTreeSet<PathBuf>) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// 移動・削除されて存在しなくなったピン留めプロジェクトか
+    pub fn is_stale(&self, project: &Project) -> bool {
+        self.pinned.contains(&project.path) && !project.path.is_dir()
+    }
+
+    /// プロンプトの `{count}` をプロジェクト数に置き換える
+    pub fn expand_prompt(&self, count: usize) -> String {
+        self.prompt.replace("{count}", &count.to_string())
+    }
+
+    /// dialoguer の配色
+    fn dialoguer_theme(&self) -> Box<dyn Theme> {
+        match self.theme {
+            SelectorTheme::Colorful => Box::new(ColorfulTheme::default()),
+            SelectorTheme::Simple => Box::new(SimpleTheme),
+        }
+    }
+}
 
 /// プロジェクト選択UIを表示
 ///
 /// あいまい検索で絞り込み、矢印キーで選択できるUIを表示します。
-pub fn select_project(projects: &[Project]) -> Result<Option<&Project>> {
+/// Ctrl+Eで完全一致（部分文字列）検索と、Ctrl+Sで大文字小文字の区別と切り替えられます。
+/// Ctrl+Yでカーソル位置のプロジェクトのパスをコピーし、何も開かずに閉じます。
+/// Ctrl+Fでカーソル位置のプロジェクトをファイルマネージャーで開き、エディタを開かずに閉じます。
+/// `quick_open` が有効な場合は、検索欄が空の間だけ数字キー（1〜9）で画面に表示中のN番目のプロジェクトをすぐに選択します。
+/// `rescan` を指定した場合は、Ctrl+Rで選択UIを閉じずに再スキャンし、検索欄の入力を保ったまま
+/// 一覧を更新します（再スキャン中もキー操作を受け付けます）。
+/// グループ化が有効な場合は、各グループの前に見出し行を挿入します。
+/// 標準エラー出力が端末でない場合は、番号付きの一覧から選ぶ形式になります。
+/// dialoguerの `FuzzySelect` は独自のキー操作や検索方式の切り替えを受け付けないため、
+/// crosstermで描画する独自の選択UIを使います。
+/// ピン留めしたプロジェクトには `★` を付け、存在しなくなったものは薄く表示します。
+/// 色のエスケープシーケンスが検索対象に含まれないよう、表示文字列は装飾なしで渡します。
+///
+/// `query` は検索欄の初期値で、選択UIを閉じたときの入力内容に更新されます。
+pub fn select_project(
+    projects: &[Project],
+    options: &DisplayOptions,
+    query: &mut String,
+    rescan: Option<Rescan>,
+) -> Result<Option<Project>> {
     if projects.is_empty() {
         return Ok(None);
     }
 
-    // 表示用の文字列リストを作成
-    let items: Vec<String> = projects.iter().map(format_project_item).collect();
+    if Term::stderr().is_term() {
+        return select_interactive(projects.to_vec(), options, query, rescan);
+    }
+
+    // 標準エラー出力が端末でない場合はTUIを表示できないため、番号入力で選ぶ
+    Ok(select_project_numbered(projects, options)?.cloned())
+}
+
+/// 選択UIの表示用の文字列リストと、各行に対応するプロジェクトのインデックス（見出しはNone）を作成
+fn select_items(
+    projects: &[Project],
+    options: &DisplayOptions,
+) -> (Vec<String>, Vec<Option<usize>>) {
+    let mut items: Vec<String> = Vec::with_capacity(projects.len());
+    let mut indices: Vec<Option<usize>> = Vec::with_capacity(projects.len());
+
+    match options.group_by {
+        Some(mode) => {
+            for (header, group) in group_projects(projects, mode, &options.root_paths) {
+                items.push(format!("── {} ──", header));
+                indices.push(None);
+
+                for idx in group {
+                    items.push(plain_project_item(&projects[idx], options));
+                    indices.push(Some(idx));
+                }
+            }
+        }
+        None => {
+            items.extend(projects.iter().map(|p| plain_project_item(p, options)));
+            indices.extend((0..projects.len()).map(Some));
+        }
+    }
+
+    (items, indices)
+}
+
+/// 検索方式を切り替えられる選択UIでプロジェクトを選択
+///
+/// `show_preview` が有効で端末の幅が十分な場合は、端末を左右に分割して
+/// 右側にカーソル位置のプロジェクトのREADMEを表示します。
+fn select_interactive(
+    projects: Vec<Project>,
+    options: &DisplayOptions,
+    query: &mut String,
+    rescan: Option<Rescan>,
+) -> Result<Option<Project>> {
+    let mut stderr = std::io::stderr();
+    let mut search = Search {
+        query: std::mem::take(query),
+        mode: options.match_mode,
+        case: options.case_sensitivity,
+    };
+
+    let guard = TerminalGuard::enter(&mut stderr)?;
+
+    // 再スキャン中でも終了を待たずに端末を元に戻す
+    let result = run_select(&mut stderr, projects, options, &mut search, rescan);
+    drop(guard);
+
+    *query = search.query;
+    result
+}
+
+/// 選択UIを表示している間の端末の状態
+///
+/// 破棄されるときに raw モードと代替画面を元に戻すため、
+/// エラーやパニックで選択UIを抜けた場合も端末が壊れたままになりません。
+struct TerminalGuard;
+
+impl TerminalGuard {
+    /// raw モードにして代替画面に切り替える
+    fn enter(out: &mut impl Write) -> Result<Self> {
+        terminal::enable_raw_mode().context("Failed to enable raw mode")?;
+        let guard = Self;
+        execute!(out, EnterAlternateScreen, cursor::Hide).context("Failed to show selection UI")?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(std::io::stderr(), cursor::Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// 選択UIの検索条件
+struct Search {
+    /// 入力中のクエリ
+    query: String,
+    /// 検索方式
+    mode: MatchMode,
+    /// 大文字と小文字を区別するか
+    case: CaseSensitivity,
+}
+
+/// 選択UIのイベントループ
+///
+/// Ctrl+Rの再スキャンは別スレッドで実行し、結果が届いたら一覧を作り直します。
+/// 再スキャン中はスピナーを表示し、キー入力を待つ間も一定間隔で描画し直します。
+fn run_select(
+    out: &mut impl Write,
+    mut projects: Vec<Project>,
+    options: &DisplayOptions,
+    search: &mut Search,
+    rescan: Option<Rescan>,
+) -> Result<Option<Project>> {
+    let mut previews: HashMap<usize, Vec<String>> = HashMap::new();
+    let (mut items, mut indices) = select_items(&projects, options);
+    let mut stale = stale_projects(&projects, options);
+    let mut visible = filter_items(&items, &indices, search);
+    let mut selected = next_selectable(&visible, &indices, 0, 1);
+    let mut offset = 0;
+    let mut screen = Screen::default();
+    let (sender, receiver) = mpsc::channel();
+    let mut rescanning = false;
+    let mut spinner = 0;
+    // プレビュー中のNix flakeの説明は別スレッドで取得し、届いたらプレビューの先頭に加える
+    let (flake_sender, flake_receiver) = mpsc::channel::<(PathBuf, Option<String>)>();
+    let mut flake_pending = 0;
+
+    loop {
+        while let Ok((path, description)) = flake_receiver.try_recv() {
+            flake_pending -= 1;
+            let lines = description.and_then(|description| {
+                let idx = projects.iter().position(|p| p.path == path)?;
+                Some((previews.get_mut(&idx)?, description))
+            });
+            if let Some((lines, description)) = lines {
+                prepend_flake_description(lines, &description);
+            }
+        }
+
+        // 再スキャンの結果が届いたら、カーソル位置のプロジェクトを保ったまま一覧を作り直す
+        if let Ok(result) = receiver.try_recv() {
+            rescanning = false;
+            match result {
+                Ok(rescanned) => {
+                    let current = visible
+                        .get(selected)
+                        .and_then(|&item| indices[item])
+                        .map(|idx| projects[idx].path.clone());
+
+                    projects = rescanned;
+                    (items, indices) = select_items(&projects, options);
+                    stale = stale_projects(&projects, options);
+                    previews.clear();
+                    visible = filter_items(&items, &indices, search);
+                    selected = position_of(&visible, &indices, &projects, current.as_deref());
+                    offset = 0;
+                }
+                Err(e) => {
+                    let (_, height) = terminal::size().context("Failed to get terminal size")?;
+                    screen.draw_status(out, height, &format!("{:#}", e).red().to_string())?;
+                    std::thread::sleep(STATUS_DURATION);
+                }
+            }
+        }
+
+        let prompt = options.expand_prompt(projects.len());
+        let status = format_status_bar(
+            visible
+                .iter()
+                .filter(|&&item| indices[item].is_some())
+                .count(),
+            projects.len(),
+            options,
+        );
+        let (width, height) = terminal::size().context("Failed to get terminal size")?;
+        // 1行目は検索欄、最下行はステータスバー
+        let list_height = usize::from(height.saturating_sub(2)).max(1);
+
+        offset = scroll_offset(selected, offset, list_height);
+
+        let preview = (options.show_preview && width >= PREVIEW_MIN_WIDTH)
+            .then(|| visible.get(selected))
+            .flatten()
+            .and_then(|&item| indices[item])
+            .map(|idx| {
+                previews.entry(idx).or_insert_with(|| {
+                    if projects[idx].project_type == ProjectType::Nix {
+                        let path = projects[idx].path.clone();
+                        let sender = flake_sender.clone();
+                        flake_pending += 1;
+                        std::thread::spawn(move || {
+                            let description = preview::flake_description(&path);
+                            let _ = sender.send((path, description));
+                        });
+                    }
+                    preview::read_readme(&projects[idx].path)
+                        .unwrap_or_else(|| "(no README)".dimmed().to_string())
+                        .lines()
+                        .map(String::from)
+                        .collect()
+                })
+            });
+
+        draw_select(
+            out,
+            &mut screen,
+            (width, list_height),
+            (&prompt, &status, options.theme),
+            search,
+            &items,
+            &indices,
+            &stale,
+            &visible[offset..],
+            selected - offset,
+            preview.map(|lines| lines.as_slice()),
+            options.quick_open && search.query.is_empty(),
+        )?;
+
+        // 再スキャン中はキー入力を待つ間もスピナーを進める
+        if rescanning {
+            let frame = SPINNER_FRAMES[spinner % SPINNER_FRAMES.len()];
+            screen.draw_status(out, height, &format!("{} Rescanning...", frame.cyan()))?;
+        }
+        // 再スキャンやflakeの説明の取得を待つ間は、一定間隔で描画し直す
+        if (rescanning || flake_pending > 0)
+            && !event::poll(SPINNER_INTERVAL).context("Failed to read key input")?
+        {
+            spinner += 1;
+            continue;
+        }
+
+        let Event::Key(key) = event::read().context("Failed to read key input")? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Ok(None),
+            KeyCode::Char('c') if ctrl => return Ok(None),
+            KeyCode::Enter => {
+                if let Some(idx) = visible.get(selected).and_then(|&item| indices[item]) {
+                    return Ok(Some(projects.swap_remove(idx)));
+                }
+            }
+            // 画面に表示中のN番目のプロジェクトをすぐに開く
+            // （検索欄が空で修飾キーなしの場合のみ。入力中の数字は検索語として扱う）
+            KeyCode::Char(c @ '1'..='9')
+                if options.quick_open && search.query.is_empty() && key.modifiers.is_empty() =>
+            {
+                let n = c as usize - '0' as usize;
+                let end = visible.len().min(offset + list_height);
+                match nth_visible_project(&visible[offset..end], &indices, n) {
+                    Some(idx) => return Ok(Some(projects.swap_remove(idx))),
+                    None => {
+                        let message = format!("No project #{} on screen", n);
+                        screen.draw_status(out, height, &message.yellow().bold().to_string())?;
+                        std::thread::sleep(FLASH_DURATION);
+                        continue;
+                    }
+                }
+            }
+            // 選択UIを閉じずに再スキャンする（実行中の場合は何もしない）
+            KeyCode::Char('r') if ctrl => {
+                if let Some(rescan) = rescan.as_ref().filter(|_| !rescanning) {
+                    let sender = sender.clone();
+                    let rescan = Arc::clone(rescan);
+                    std::thread::spawn(move || {
+                        let _ = sender.send(rescan());
+                    });
+                    rescanning = true;
+                    spinner = 0;
+                }
+                continue;
+            }
+            // パスをクリップボードにコピーし、エディタを起動せずに閉じる
+            KeyCode::Char('y') if ctrl => {
+                let Some(idx) = visible.get(selected).and_then(|&item| indices[item]) else {
+                    continue;
+                };
+                match clipboard::copy(&projects[idx].path.to_string_lossy()) {
+                    Ok(()) => {
+                        screen.draw_status(
+                            out,
+                            height,
+                            &"Path copied!".green().bold().to_string(),
+                        )?;
+                        std::thread::sleep(STATUS_DURATION);
+                        return Ok(None);
+                    }
+                    Err(e) => {
+                        screen.draw_status(out, height, &format!("{:#}", e).red().to_string())?;
+                        std::thread::sleep(STATUS_DURATION);
+                        continue;
+                    }
+                }
+            }
+            // ファイルマネージャーで開き、エディタを起動せずに閉じる
+            KeyCode::Char('f') if ctrl => {
+                let Some(idx) = visible.get(selected).and_then(|&item| indices[item]) else {
+                    continue;
+                };
+                screen.draw_status(
+                    out,
+                    height,
+                    &"Opening in file manager...".green().to_string(),
+                )?;
+                match launcher::open_file_manager(
+                    &projects[idx].path,
+                    options.file_manager.as_deref(),
+                ) {
+                    Ok(()) => {
+                        std::thread::sleep(STATUS_DURATION);
+                        return Ok(None);
+                    }
+                    Err(e) => {
+                        screen.draw_status(out, height, &format!("{:#}", e).red().to_string())?;
+                        std::thread::sleep(STATUS_DURATION);
+                        continue;
+                    }
+                }
+            }
+            KeyCode::Up | KeyCode::BackTab => {
+                selected = next_selectable(&visible, &indices, selected.saturating_sub(1), -1);
+            }
+            KeyCode::Char('p' | 'k') if ctrl => {
+                selected = next_selectable(&visible, &indices, selected.saturating_sub(1), -1);
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                selected = next_selectable(&visible, &indices, selected + 1, 1);
+            }
+            KeyCode::Char('n' | 'j') if ctrl => {
+                selected = next_selectable(&visible, &indices, selected + 1, 1);
+            }
+            KeyCode::PageUp => {
+                let target = selected.saturating_sub(list_height);
+                selected = next_selectable(&visible, &indices, target, -1);
+            }
+            KeyCode::PageDown => {
+                selected = next_selectable(&visible, &indices, selected + list_height, 1);
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+            }
+            KeyCode::Char('u') if ctrl => search.query.clear(),
+            KeyCode::Char('e') if ctrl => search.mode = search.mode.toggle(),
+            KeyCode::Char('s') if ctrl => search.case = search.case.toggle(),
+            KeyCode::Char(c) if !ctrl => search.query.push(c),
+            _ => continue,
+        }
+
+        // 検索条件が変わったら絞り込み直す
+        if matches!(key.code, KeyCode::Backspace | KeyCode::Char(_)) {
+            visible = filter_items(&items, &indices, search);
+            selected = next_selectable(&visible, &indices, 0, 1);
+            offset = 0;
+        }
+    }
+}
+
+/// 画面に表示中の行のうち、N番目（1始まり）のプロジェクトのインデックス
+///
+/// グループの見出し行は数えません。
+fn nth_visible_project(visible: &[usize], indices: &[Option<usize>], n: usize) -> Option<usize> {
+    visible
+        .iter()
+        .filter_map(|&item| indices[item])
+        .nth(n.checked_sub(1)?)
+}
+
+/// 画面に表示中の各行に付ける数字キーの番号（1〜9。見出しと10番目以降は `None`）
+///
+/// 番号は [`nth_visible_project`] で開くプロジェクトと対応します。
+fn quick_open_labels(visible: &[usize], indices: &[Option<usize>]) -> Vec<Option<usize>> {
+    let mut n = 0;
+    visible
+        .iter()
+        .map(|&item| {
+            indices[item]?;
+            n += 1;
+            (n <= 9).then_some(n)
+        })
+        .collect()
+}
+
+/// 存在しなくなったピン留めプロジェクトのインデックス
+fn stale_projects(projects: &[Project], options: &DisplayOptions) -> HashSet<usize> {
+    (0..projects.len())
+        .filter(|&idx| options.is_stale(&projects[idx]))
+        .collect()
+}
+
+/// カーソル位置が見えるようにスクロールした、一覧の表示開始位置
+fn scroll_offset(selected: usize, offset: usize, list_height: usize) -> usize {
+    if selected < offset {
+        selected
+    } else if selected >= offset + list_height {
+        selected + 1 - list_height
+    } else {
+        offset
+    }
+}
+
+/// 画面の1行（表示する桁と文字列の組）
+type Row = Vec<(u16, String)>;
+
+/// 選択UIの画面
+///
+/// 前回描画した行を覚えておき、内容が変わった行だけを消して描き直します。
+#[derive(Debug, Default)]
+struct Screen {
+    /// 前回描画したときの端末の幅
+    width: u16,
+    /// 前回描画した各行
+    rows: Vec<Row>,
+}
+
+impl Screen {
+    /// 画面を描画する（端末の大きさが変わった場合は全体を描き直す）
+    fn draw(&mut self, out: &mut impl Write, width: u16, rows: Vec<Row>) -> Result<()> {
+        if self.width != width || self.rows.len() != rows.len() {
+            queue!(out, terminal::Clear(ClearType::All))?;
+            self.width = width;
+            self.rows.clear();
+        }
+
+        for (y, row) in rows.iter().enumerate() {
+            if self.rows.get(y) == Some(row) {
+                continue;
+            }
+            let y = u16::try_from(y).unwrap_or(u16::MAX);
+            queue!(
+                out,
+                cursor::MoveTo(0, y),
+                terminal::Clear(ClearType::CurrentLine)
+            )?;
+            for (x, text) in row {
+                queue!(out, cursor::MoveTo(*x, y), Print(text))?;
+            }
+        }
+        self.rows = rows;
+
+        out.flush().context("Failed to draw selection UI")?;
+        Ok(())
+    }
+
+    /// 選択UIの最下行にステータスメッセージを表示
+    fn draw_status(&mut self, out: &mut impl Write, height: u16, message: &str) -> Result<()> {
+        let y = height.saturating_sub(1);
+        queue!(
+            out,
+            cursor::MoveTo(0, y),
+            terminal::Clear(ClearType::CurrentLine),
+            Print(message)
+        )?;
+        // 次に描画するときに、ステータスバーを描き直すようにする
+        if let Some(row) = self.rows.get_mut(usize::from(y)) {
+            *row = vec![(0, message.to_string())];
+        }
+        out.flush().context("Failed to draw selection UI")?;
+        Ok(())
+    }
+}
+
+/// 選択UIを描画
+///
+/// `preview` が `Some` の場合は右半分にプレビューを表示します。
+/// `stale` に含まれるプロジェクト（存在しなくなったピン留め）は薄く表示します。
+/// 一覧の下の行には `status` をステータスバーとして表示します。
+/// `quick_open` が `true` の場合は、数字キーで開けるプロジェクトの行に番号を表示します。
+#[allow(clippy::too_many_arguments)]
+fn draw_select(
+    out: &mut impl Write,
+    screen: &mut Screen,
+    (width, list_height): (u16, usize),
+    (prompt, status, theme): (&str, &str, SelectorTheme),
+    search: &Search,
+    items: &[String],
+    indices: &[Option<usize>],
+    stale: &HashSet<usize>,
+    visible: &[usize],
+    selected: usize,
+    preview: Option<&[String]>,
+    quick_open: bool,
+) -> Result<()> {
+    let labels = if quick_open {
+        quick_open_labels(&visible[..visible.len().min(list_height)], indices)
+    } else {
+        vec![]
+    };
+    let list_width = match preview {
+        Some(_) => usize::from(width / 2),
+        None => usize::from(width),
+    };
+    let preview_width = usize::from(width).saturating_sub(list_width + 2);
+
+    let header = format!(
+        "{} [{}] [{}]",
+        prompt,
+        search.mode.label(),
+        search.case.label()
+    );
+    let header = match theme {
+        SelectorTheme::Colorful => format!(
+            "{} {} {} {}",
+            "?".yellow().bold(),
+            header.bold(),
+            "›".bold(),
+            search.query
+        ),
+        SelectorTheme::Simple => format!("{} > {}", header, search.query),
+    };
+    let mut rows: Vec<Row> = vec![vec![(0, header)]];
+
+    for row in 0..list_height {
+        let mut segments = Row::new();
+
+        if let Some(&item) = visible.get(row) {
+            // 番号を表示する場合は、見出し以外の行に番号の列（2文字）を設ける
+            let label = match labels.get(row) {
+                Some(Some(n)) => format!("{} ", n),
+                Some(None) if indices[item].is_some() => "  ".to_string(),
+                _ => String::new(),
+            };
+            let width = list_width.saturating_sub(3 + label.len());
+            let text = console::truncate_str(&items[item], width, "…");
+            let line = match (indices[item], theme) {
+                (None, SelectorTheme::Colorful) => text.dimmed().to_string(),
+                (None, SelectorTheme::Simple) => text.to_string(),
+                // 検索語に一致した文字に下線を付ける（切り詰めは色を保ったまま行う）
+                (Some(idx), SelectorTheme::Colorful) => {
+                    let style: fn(&str) -> ColoredString = if stale.contains(&idx) {
+                        |s| s.dimmed()
+                    } else if row == selected {
+                        |s| s.cyan().bold()
+                    } else {
+                        |s| s.normal()
+                    };
+                    let text = highlight_matches(&items[item], search, style);
+                    let text = console::truncate_str(&text, width, "…");
+                    if row == selected {
+                        format!("{} {}{}", "❯".green(), label.dimmed(), text)
+                    } else {
+                        format!("  {}{}", label.dimmed(), text)
+                    }
+                }
+                (Some(_), SelectorTheme::Simple) if row == selected => {
+                    format!("> {}{}", label, text)
+                }
+                (Some(_), SelectorTheme::Simple) => format!("  {}{}", label, text),
+            };
+            segments.push((0, line));
+        }
+
+        if let Some(preview) = preview {
+            let preview_line = preview.get(row).map(String::as_str).unwrap_or_default();
+            segments.push((
+                u16::try_from(list_width).unwrap_or(u16::MAX),
+                format!(
+                    "{} {}",
+                    "│".dimmed(),
+                    console::truncate_str(preview_line, preview_width, "…")
+                ),
+            ));
+        }
+        rows.push(segments);
+    }
+
+    let status = console::truncate_str(status, usize::from(width), "…");
+    let status = match theme {
+        SelectorTheme::Colorful => status.dimmed().to_string(),
+        SelectorTheme::Simple => status.to_string(),
+    };
+    rows.push(vec![(0, status)]);
+
+    screen.draw(out, width, rows)
+}
+
+/// 選択UIのステータスバーの内容（絞り込み後の件数/全体の件数、並び順、グループ化方法）
+fn format_status_bar(visible: usize, total: usize, options: &DisplayOptions) -> String {
+    format!(
+        "{}/{} · sort: {} · group: {}",
+        visible,
+        total,
+        options.sort.as_str(),
+        options.group_by.unwrap_or(GroupMode::None).as_str()
+    )
+}
+
+/// 検索条件で一覧を絞り込み、表示する行のインデックスを返す
+///
+/// クエリが空の場合はすべての行（見出しを含む）を元の順序で返します。
+/// それ以外は見出しを除き、あいまい検索ではスコアの高い順、
+/// 完全一致検索では元の順序で返します。
+fn filter_items(items: &[String], indices: &[Option<usize>], search: &Search) -> Vec<usize> {
+    let query = search.query.as_str();
+    if query.is_empty() {
+        return (0..items.len()).collect();
+    }
+
+    if search.mode == MatchMode::Exact {
+        let query = search.case.normalize(query);
+        return (0..items.len())
+            .filter(|&i| indices[i].is_some() && search.case.normalize(&items[i]).contains(&query))
+            .collect();
+    }
+
+    let matcher = search.case.fuzzy_matcher();
+
+    let mut scored: Vec<(i64, usize)> = items
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| indices[*i].is_some())
+        .filter_map(|(i, item)| matcher.fuzzy_match(item, query).map(|score| (score, i)))
+        .collect();
+
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
+
+/// 検索条件に一致した文字の位置（文字単位のインデックス、昇順）
+///
+/// あいまい検索では絞り込みと同じアルゴリズムで一致した文字を、
+/// 完全一致検索では部分文字列として一致したすべての箇所の文字を返します。
+fn match_positions(item: &str, search: &Search) -> Vec<usize> {
+    let query = search.query.as_str();
+    if query.is_empty() {
+        return vec![];
+    }
+
+    if search.mode == MatchMode::Fuzzy {
+        return search
+            .case
+            .fuzzy_matcher()
+            .fuzzy_indices(item, query)
+            .map(|(_, positions)| positions)
+            .unwrap_or_default();
+    }
+
+    // 大文字小文字の変換で文字数が変わる場合は位置を対応付けられないため、強調しない
+    let normalized = search.case.normalize(item);
+    let query = search.case.normalize(query);
+    if normalized.chars().count() != item.chars().count() {
+        return vec![];
+    }
+
+    let query_len = query.chars().count();
+    normalized
+        .match_indices(&query)
+        .flat_map(|(byte, _)| {
+            let start = normalized[..byte].chars().count();
+            start..start + query_len
+        })
+        .collect()
+}
+
+/// 検索条件に一致した文字に下線を付け、すべての文字に `style` を適用する
+///
+/// 連続する文字はまとめて装飾します。行全体の色が下線の終わりで解除されないよう、
+/// 行の装飾も区間ごとに適用します。
+fn highlight_matches(item: &str, search: &Search, style: fn(&str) -> ColoredString) -> String {
+    let positions = match_positions(item, search);
+    let mut result = String::with_capacity(item.len());
+    let mut run = String::new();
+    let mut run_matched = false;
+
+    let mut flush = |run: &mut String, matched: bool| {
+        if run.is_empty() {
+            return;
+        }
+        let styled = style(run);
+        let styled = if matched { styled.underline() } else { styled };
+        result.push_str(&styled.to_string());
+        run.clear();
+    };
+
+    for (i, c) in item.chars().enumerate() {
+        let matched = positions.binary_search(&i).is_ok();
+        if matched != run_matched {
+            flush(&mut run, run_matched);
+            run_matched = matched;
+        }
+        run.push(c);
+    }
+    flush(&mut run, run_matched);
+
+    result
+}
+
+/// プレビューの先頭にNix flakeの説明を加える
+fn prepend_flake_description(lines: &mut Vec<String>, description: &str) {
+    let header = format!("{} {}", "❄".cyan(), description.bold());
+    lines.splice(0..0, [header, String::new()]);
+}
+
+/// 作り直した一覧での `path` のプロジェクトの表示位置
+///
+/// 見つからない場合は、先頭の選択可能な行の位置を返します。
+fn position_of(
+    visible: &[usize],
+    indices: &[Option<usize>],
+    projects: &[Project],
+    path: Option<&Path>,
+) -> usize {
+    path.and_then(|path| {
+        visible
+            .iter()
+            .position(|&item| indices[item].is_some_and(|idx| projects[idx].path == path))
+    })
+    .unwrap_or_else(|| next_selectable(visible, indices, 0, 1))
+}
+
+/// `start` から `step` の方向に、見出し以外の行を探す
+///
+/// 見つからない場合は逆方向に探し、それでもなければ範囲内に収めた `start` を返します。
+fn next_selectable(
+    visible: &[usize],
+    indices: &[Option<usize>],
+    start: usize,
+    step: isize,
+) -> usize {
+    if visible.is_empty() {
+        return 0;
+    }
+
+    let last = visible.len() - 1;
+    let start = start.min(last);
+    let selectable = |pos: &usize| indices[visible[*pos]].is_some();
+
+    let forward: Vec<usize> = if step > 0 {
+        (start..=last).collect()
+    } else {
+        (0..=start).rev().collect()
+    };
+    let backward: Vec<usize> = if step > 0 {
+        (0..start).rev().collect()
+    } else {
+        (start + 1..=last).collect()
+    };
+
+    forward
+        .into_iter()
+        .chain(backward)
+        .find(selectable)
+        .unwrap_or(start)
+}
+
+/// プロジェクトをグループ化し、(見出し, プロジェクトのインデックス一覧) を返す
+///
+/// ルートパスでのグループは `root_paths` の順序に従い、どのルートにも属さない
+/// プロジェクトは末尾の "other" グループにまとめます。
+fn group_projects(
+    projects: &[Project],
+    mode: GroupMode,
+    root_paths: &[PathBuf],
+) -> Vec<(String, Vec<usize>)> {
+    match mode {
+        GroupMode::Root => {
+            let mut groups: Vec<(String, Vec<usize>)> = root_paths
+                .iter()
+                .map(|root| (shorten_home_path(&root.to_string_lossy()), vec![]))
+                .collect();
+            let mut other = vec![];
+
+            for (idx, project) in projects.iter().enumerate() {
+                match root_paths.iter().position(|root| *root == project.root) {
+                    Some(i) => groups[i].1.push(idx),
+                    None => other.push(idx),
+                }
+            }
+
+            groups.push(("other".to_string(), other));
+            groups.retain(|(_, group)| !group.is_empty());
+            groups
+        }
+        GroupMode::Type => {
+            let mut types: Vec<_> = projects.iter().map(|p| p.project_type).collect();
+            types.sort();
+            types.dedup();
+
+            types
+                .into_iter()
+                .map(|project_type| {
+                    let group = projects
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, p)| p.project_type == project_type)
+                        .map(|(idx, _)| idx)
+                        .collect();
+                    (project_type.as_str().to_string(), group)
+                })
+                .collect()
+        }
+        GroupMode::Marker => {
+            let mut labels: Vec<&str> =
+                projects.iter().map(|p| marker_label(&p.marker).0).collect();
+            labels.sort();
+            labels.dedup();
+
+            labels
+                .into_iter()
+                .map(|label| {
+                    let group = projects
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, p)| marker_label(&p.marker).0 == label)
+                        .map(|(idx, _)| idx)
+                        .collect();
+                    (label.to_string(), group)
+                })
+                .collect()
+        }
+        GroupMode::None => vec![("all".to_string(), (0..projects.len()).collect())],
+    }
+}
+
+/// skim（`sk` コマンド）でプロジェクト選択UIを表示
+///
+/// カーソル位置のプロジェクトのREADMEまたはファイル一覧をプレビュー表示します。
+///
+/// `query` は検索欄の初期値です。
+pub fn select_project_skim<'a>(
+    projects: &'a [Project],
+    options: &DisplayOptions,
+    query: &str,
+) -> Result<Option<&'a Project>> {
+    let mut command = Command::new("sk");
+    command.args(["--preview", PREVIEW_COMMAND]);
+    if options.match_mode == MatchMode::Exact {
+        command.arg("--exact");
+    }
+    if options.case_sensitivity == CaseSensitivity::Sensitive {
+        command.args(["--case", "respect"]);
+    }
+
+    select_project_external(command, "skim (sk)", projects, options, query)
+}
+
+/// fzf でプロジェクト選択UIを表示
+///
+/// `FZF_DEFAULT_OPTS` で設定した配色やキー割り当てはそのまま使われます。
+/// `show_preview` が有効な場合は、skimと同じくREADMEまたはファイル一覧をプレビュー表示します。
+///
+/// `query` は検索欄の初期値です。
+pub fn select_project_fzf<'a>(
+    projects: &'a [Project],
+    options: &DisplayOptions,
+    query: &str,
+) -> Result<Option<&'a Project>> {
+    let mut command = Command::new("fzf");
+    if options.show_preview {
+        command.args(["--preview", PREVIEW_COMMAND]);
+    }
+    if options.match_mode == MatchMode::Exact {
+        command.arg("--exact");
+    }
+    if options.case_sensitivity == CaseSensitivity::Sensitive {
+        command.arg("+i");
+    }
+
+    select_project_external(command, "fzf", projects, options, query)
+}
+
+/// 外部のあいまい検索コマンド（skim・fzf）でプロジェクトを選択
+///
+/// 両者に共通するオプション（ANSIカラー、区切り文字、プロンプト、初期クエリ）はここで指定します。
+fn select_project_external<'a>(
+    mut command: Command,
+    name: &str,
+    projects: &'a [Project],
+    options: &DisplayOptions,
+    query: &str,
+) -> Result<Option<&'a Project>> {
+    if projects.is_empty() {
+        return Ok(None);
+    }
+
+    // "インデックス<TAB>表示文字列<TAB>パス" の形式で渡し、表示文字列のみ検索対象にする
+    let input: String = projects
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            format!(
+                "{}\t{}\t{}\n",
+                i,
+                format_project_item(p, options),
+                p.path.display()
+            )
+        })
+        .collect();
+
+    command
+        .args(["--ansi", "--delimiter", "\t", "--with-nth", "2"])
+        .arg("--prompt")
+        .arg(format!("{}> ", options.expand_prompt(projects.len())));
+    if !query.is_empty() {
+        command.args(["--query", query]);
+    }
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to launch {}", name))?;
+
+    child
+        .stdin
+        .take()
+        .with_context(|| format!("Failed to open {} stdin", name))?
+        .write_all(input.as_bytes())
+        .with_context(|| format!("Failed to write to {}", name))?;
+
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("Failed to read {} output", name))?;
+
+    // キャンセル（ESC）や未選択の場合は非ゼロで終了する
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let selected = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_selected_index(&selected).and_then(|idx| projects.get(idx)))
+}
+
+/// skim・fzfの出力行からプロジェクトのインデックスを取り出す
+fn parse_selected_index(line: &str) -> Option<usize> {
+    line.split('\t').next()?.trim().parse().ok()
+}
+
+/// 複数プロジェクトの選択UIを表示
+///
+/// スペースキーで複数のプロジェクトにチェックを付け、Enterで確定します。
+/// 何も選択せずに確定した場合はキャンセルとして空のリストを返します。
+pub fn select_projects<'a>(
+    projects: &'a [Project],
+    options: &DisplayOptions,
+) -> Result<Vec<&'a Project>> {
+    if projects.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let items: Vec<String> = projects
+        .iter()
+        .map(|p| format_project_item(p, options))
+        .collect();
+
+    let selection = MultiSelect::with_theme(options.dialoguer_theme().as_ref())
+        .with_prompt("Select projects (space to toggle, enter to confirm)")
+        .items(&items)
+        .interact_on_opt(&Term::stderr())
+        .context("Failed to show selection UI")?;
+
+    Ok(selection
+        .unwrap_or_default()
+        .into_iter()
+        .map(|idx| &projects[idx])
+        .collect())
+}
+
+/// 番号付きの一覧からプロジェクトを選択（TUIを使わない）
+///
+/// 一覧を標準エラー出力に表示し、標準入力から番号またはプロジェクト名を読み込みます。
+/// 空行・`q`・入力の終わりでキャンセルし、該当しない入力の場合は入力し直します。
+/// シリアル接続やダム端末など、カーソル移動ができない環境でも使えます。
+pub fn select_project_numbered<'a>(
+    projects: &'a [Project],
+    options: &DisplayOptions,
+) -> Result<Option<&'a Project>> {
+    let selected = select_numbered(
+        projects,
+        options,
+        &mut std::io::stdin().lock(),
+        &mut std::io::stderr(),
+    )?;
+    Ok(selected.map(|idx| &projects[idx]))
+}
+
+/// 番号付きの一覧を `out` に表示し、`input` から選択を読み込む
+fn select_numbered(
+    projects: &[Project],
+    options: &DisplayOptions,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+) -> Result<Option<usize>> {
+    if projects.is_empty() {
+        return Ok(None);
+    }
+
+    let width = projects.len().to_string().len();
+    for (i, project) in projects.iter().enumerate() {
+        writeln!(
+            out,
+            "{:>width$}) {}",
+            i + 1,
+            plain_project_item(project, options)
+        )?;
+    }
+
+    loop {
+        write!(
+            out,
+            "{} [1-{}, name, q to cancel]: ",
+            options.expand_prompt(projects.len()),
+            projects.len()
+        )?;
+        out.flush()?;
+
+        let mut line = String::new();
+        if input
+            .read_line(&mut line)
+            .context("Failed to read selection")?
+            == 0
+        {
+            return Ok(None);
+        }
+
+        match parse_numbered_choice(projects, line.trim()) {
+            Ok(choice) => return Ok(choice),
+            Err(message) => writeln!(out, "{}", message)?,
+        }
+    }
+}
+
+/// 番号付き一覧への入力を解釈
+///
+/// 空の入力と `q` はキャンセル（`Ok(None)`）です。数字は1から始まる番号として、
+/// それ以外はプロジェクト名との完全一致（大文字と小文字を区別しない）として扱います。
+/// 該当しない場合や、同名のプロジェクトが複数ある場合はエラーメッセージを返します。
+fn parse_numbered_choice(projects: &[Project], input: &str) -> Result<Option<usize>, String> {
+    if input.is_empty() || input.eq_ignore_ascii_case("q") {
+        return Ok(None);
+    }
+
+    if let Ok(number) = input.parse::<usize>() {
+        if (1..=projects.len()).contains(&number) {
+            return Ok(Some(number - 1));
+        }
+        return Err(format!(
+            "No project numbered {} (expected 1-{}).",
+            number,
+            projects.len()
+        ));
+    }
+
+    let matched: Vec<usize> = projects
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.name.eq_ignore_ascii_case(input))
+        .map(|(i, _)| i)
+        .collect();
+
+    match matched.as_slice() {
+        [idx] => Ok(Some(*idx)),
+        [] => Err(format!("No project named '{}'.", input)),
+        _ => Err(format!(
+            "{} are named '{}'. Enter a number instead.",
+            plural(matched.len(), "project"),
+            input
+        )),
+    }
+}
+
+/// プロジェクト項目のフォーマット
+///
+/// 表示テンプレートが設定されている場合はそれを展開します。
+/// タグが付いたプロジェクトには `[work, active]` のように控えめな色でタグを添えます。
+/// ピン留めしたプロジェクトには `★` を付け、存在しなくなったものは `(missing: パス)` として薄く表示します。
+fn format_project_item(project: &Project, options: &DisplayOptions) -> String {
+    if options.is_stale(project) {
+        return format!(
+            "★ {} (missing: {})",
+            project.name,
+            shorten_home_path(&project.path.to_string_lossy())
+        )
+        .dimmed()
+        .to_string();
+    }
+
+    let tags = project.tags(&options.tags);
+    let tags = if tags.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", format!("[{}]", tags.join(", ")).bright_black())
+    };
+
+    let item = match &options.display_format {
+        Some(template) => format!(
+            "{}{}",
+            expand_display_format(
+                template,
+                project,
+                options.history.get(&project.path),
+                SystemTime::now()
+            ),
+            tags
+        ),
+        None => format!(
+            "{} {}{} {}",
+            project.name.bold(),
+            format_marker_tag(&project.marker),
+            tags,
+            format!("({})", shorten_home_path(&project.path.to_string_lossy())).dimmed()
+        ),
+    };
+
+    let item = if options.show_icons {
+        format!("{} {}", project.project_type.icon(), item)
+    } else {
+        item
+    };
+
+    if options.pinned.contains(&project.path) {
+        format!("{} {}", "★".yellow(), item)
+    } else {
+        item
+    }
+}
+
+/// 色のエスケープシーケンスを含まないプロジェクト項目（あいまい検索用）
+fn plain_project_item(project: &Project, options: &DisplayOptions) -> String {
+    console::strip_ansi_codes(&format_project_item(project, options)).into_owned()
+}
+
+/// 表示テンプレートを展開
+///
+/// `{name}`（プロジェクト名）、`{parent}`（親ディレクトリ名）、`{path}` と `{short_path}`（~ 短縮済みのパス）、
+/// `{full_path}`（フルパス）、`{marker}`（検出マーカー）、`{type}`（種別）、
+/// `{open_count}`（開いた回数）、`{last_opened}`（最後に開いてからの経過時間。未使用なら "never"）を
+/// 置換します。未知のプレースホルダーはそのまま残します。
+fn expand_display_format(
+    template: &str,
+    project: &Project,
+    history: Option<&ProjectHistory>,
+    now: SystemTime,
+) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start..];
+
+        let Some(end) = after.find('}') else {
+            rest = after;
+            break;
+        };
+
+        let key = &after[1..end];
+        match key {
+            "name" => result.push_str(&project.name),
+            "parent" => result.push_str(
+                &project
+                    .path
+                    .parent()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            ),
+            "path" | "short_path" => {
+                result.push_str(&shorten_home_path(&project.path.to_string_lossy()))
+            }
+            "full_path" => result.push_str(&project.path.to_string_lossy()),
+            "marker" => result.push_str(&project.marker),
+            "description" => result.push_str(
+                project
+                    .overrides
+                    .as_ref()
+                    .and_then(|o| o.description.as_deref())
+                    .unwrap_or_default(),
+            ),
+            "type" => result.push_str(project.project_type.as_str()),
+            "open_count" => {
+                result.push_str(&history.map_or(0, |entry| entry.open_count).to_string())
+            }
+            "last_opened" => result.push_str(&history.map_or_else(
+                || "never".to_string(),
+                |entry| format_age(unix_time(entry.last_opened), now),
+            )),
+            _ => result.push_str(&after[..=end]),
+        }
+
+        rest = &after[end + 1..];
+    }
+
+    // 閉じ括弧のない `{` 以降はそのまま出力
+    result.push_str(rest);
+    result
+}
+
+/// マーカーを短い言語ラベルと表示色に変換
+///
+/// 言語固有のマーカーがなく `.git` のみで検出された場合は `git` になります。
+fn marker_label(marker: &str) -> (&str, Color) {
+    match base_marker(marker) {
+        "Cargo.toml" => ("rust", Color::Red),
+        "package.json" => ("node", Color::Green),
+        "deno.json" => ("deno", Color::Green),
+        "go.mod" => ("go", Color::Cyan),
+        "pyproject.toml" | "setup.py" => ("python", Color::Yellow),
+        "pom.xml" | "build.gradle" => ("java", Color::Red),
+        "Gemfile" => ("ruby", Color::Red),
+        "mix.exs" => ("elixir", Color::Magenta),
+        "composer.json" => ("php", Color::Blue),
+        "CMakeLists.txt" => ("cmake", Color::Blue),
+        "pubspec.yaml" => ("dart", Color::Cyan),
+        "*.sln" | "*.csproj" | "*.fsproj" => ("dotnet", Color::Magenta),
+        "Package.swift" => ("swift", Color::Red),
+        "build.zig" => ("zig", Color::Yellow),
+        "flake.nix" | "flake.lock" | "default.nix" | "shell.nix" => ("nix", Color::Blue),
+        "main.tf" | "*.tf" => ("terraform", Color::Magenta),
+        "Pulumi.yaml" => ("pulumi", Color::Magenta),
+        "playbook.yml" | "ansible.cfg" => ("ansible", Color::Red),
+        ".github/workflows" | ".gitlab-ci.yml" | ".circleci/config.yml" | "Jenkinsfile" => {
+            ("ci", Color::Magenta)
+        }
+        "Makefile" => ("make", Color::White),
+        ".git" => ("git", Color::BrightBlack),
+        other => (other, Color::White),
+    }
+}
+
+/// マーカーを色付きのタグ（例: `[rust]`）として整形
+fn format_marker_tag(marker: &str) -> String {
+    let (label, color) = marker_label(marker);
+    format!("[{}]", label).color(color).to_string()
+}
+
+/// ホームディレクトリを ~ に短縮
+pub fn shorten_home_path(path: &str) -> String {
+    if let Ok(home) = std::env::var("HOME") {
+        if path.starts_with(&home) {
+            return path.replacen(&home, "~", 1);
+        }
+    }
+    path.to_string()
+}
+
+/// 確認プロンプトを表示（デフォルトは No）
+pub fn confirm(prompt: &str) -> Result<bool> {
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(false)
+        .interact_on_opt(&Term::stderr())
+        .context("Failed to show confirmation prompt")?
+        .unwrap_or(false))
+}
+
+/// パス追加結果のサマリーを作成（例: "Added 3, skipped 1 duplicate, 1 error"）
+pub fn format_add_summary(added: usize, duplicates: usize, errors: usize) -> String {
+    let mut summary = format!("Added {}", added);
+    if duplicates > 0 {
+        summary.push_str(&format!(", skipped {}", plural(duplicates, "duplicate")));
+    }
+    if errors > 0 {
+        summary.push_str(&format!(", {}", plural(errors, "error")));
+    }
+    summary
+}
+
+/// 複数パスの削除結果のサマリーを整形
+pub fn format_remove_summary(removed: usize, not_found: usize, errors: usize) -> String {
+    let mut summary = format!("Removed {}", removed);
+    if not_found > 0 {
+        summary.push_str(&format!(", {} not found", not_found));
+    }
+    if errors > 0 {
+        summary.push_str(&format!(", {}", plural(errors, "error")));
+    }
+    summary
+}
+
+/// 件数と単語を英語の単数形・複数形で整形（例: "1 error", "2 errors"）
+pub fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+/// スキャン結果のサマリーを表示
+pub fn print_scan_summary(projects: &[Project], elapsed_ms: u128) {
+    println!();
+    println!(
+        "{} {} projects found in {}ms",
+        "✓".green().bold(),
+        projects.len().to_string().cyan(),
+        elapsed_ms
+    );
+}
+
+/// スキャン中に読み込めなかったパスを表示
+///
+/// 通常は権限エラーとそれ以外のエラーの件数だけを表示し、
+/// `verbose` が `true` の場合は各パスとエラーの内容も表示します。
+pub fn print_scan_errors(errors: &[ScanError], verbose: bool) {
+    if errors.is_empty() {
+        return;
+    }
+
+    let denied = errors.iter().filter(|e| e.permission_denied).count();
+    let others = errors.len() - denied;
+    if denied > 0 {
+        print_warning(&format!(
+            "{} directories skipped due to permission errors",
+            denied
+        ));
+    }
+    if others > 0 {
+        print_warning(&format!("{} paths skipped due to scan errors", others));
+    }
+
+    if !verbose {
+        println!(
+            "  {}",
+            "Run with --verbose to see the skipped paths.".dimmed()
+        );
+        return;
+    }
+
+    for error in errors {
+        match &error.path {
+            Some(path) => println!("  {} {}", path.display(), error.message.dimmed()),
+            None => println!("  {}", error.message.dimmed()),
+        }
+    }
+}
+
+/// プロジェクト一覧を表示
+///
+/// グループ化が有効な場合は見出しごとに字下げして表示し、
+/// 各グループ内はプロジェクト名の順に並べます。
+pub fn print_project_list(projects: &[Project], options: &DisplayOptions) {
+    if projects.is_empty() {
+        println!("{}", "No projects found.".yellow());
+        return;
+    }
+
+    println!();
+    println!("{}", "Projects:".bold());
+    println!();
+
+    match options.group_by {
+        Some(mode) => {
+            for (header, mut group) in group_projects(projects, mode, &options.root_paths) {
+                group.sort_by_cached_key(|&idx| projects[idx].name.to_lowercase());
+
+                println!("  {}", header.bold());
+                for idx in group {
+                    print_project_line(&projects[idx], 4, options.show_age);
+                }
+                println!();
+            }
+        }
+        None => {
+            for project in projects {
+                print_project_line(project, 2, options.show_age);
+            }
+            println!();
+        }
+    }
+
+    println!("Total: {} projects", projects.len().to_string().cyan());
+}
+
+/// プロジェクト一覧を列をそろえた表で表示
+///
+/// 見出し行は色付けが有効な場合のみ太字にします（`NO_COLOR` や `--color never` では装飾なし）。
+pub fn print_project_table(projects: &[Project]) {
+    let table = format_project_table(projects);
+    let mut lines = table.lines();
+
+    if let Some(header) = lines.next() {
+        println!("{}", header.bold());
+    }
+    for line in lines {
+        println!("{}", line);
+    }
+}
+
+/// プロジェクト一覧を `| NAME | TYPE | MARKER | PATH |` 形式の表に整形
+///
+/// 各列は最も幅の広い値に合わせて空白で埋め、見出し行の次に区切り行を入れます。
+/// 値に含まれる `|` は Markdown の表を崩さないよう `\|` にエスケープします。
+fn format_project_table(projects: &[Project]) -> String {
+    const HEADERS: [&str; 4] = ["NAME", "TYPE", "MARKER", "PATH"];
+
+    let rows: Vec<[String; 4]> = projects
+        .iter()
+        .map(|p| {
+            [
+                p.name.clone(),
+                p.project_type.as_str().to_string(),
+                p.marker.clone(),
+                p.path.to_string_lossy().to_string(),
+            ]
+            .map(|cell| cell.replace('|', "\\|"))
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..HEADERS.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| console::measure_text_width(&row[col]))
+                .chain(std::iter::once(HEADERS[col].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |cells: &[&str]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| console::pad_str(cell, width, console::Alignment::Left, None))
+            .map(|cell| cell.into_owned())
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+
+    let mut lines = vec![format_row(&HEADERS)];
+    lines.push(format!(
+        "|{}|",
+        widths
+            .iter()
+            .map(|&width| "-".repeat(width + 2))
+            .collect::<Vec<_>>()
+            .join("|")
+    ));
+    lines.extend(
+        rows.iter()
+            .map(|row| format_row(&row.each_ref().map(String::as_str))),
+    );
+
+    lines.join("\n")
+}
+
+/// プロジェクト一覧をCSVで出力
+pub fn print_project_csv(projects: &[Project]) {
+    print!("{}", format_project_csv(projects));
+}
+
+/// プロジェクト一覧を `name,path,marker,type` の見出し付きCSVに整形
+///
+/// RFC 4180 に従い、行末は CRLF とし、カンマ・ダブルクォート・改行を含む値はダブルクォートで囲みます。
+fn format_project_csv(projects: &[Project]) -> String {
+    let mut csv = String::from("name,path,marker,type\r\n");
+
+    for project in projects {
+        let fields = [
+            project.name.as_str(),
+            &project.path.to_string_lossy(),
+            project.marker.as_str(),
+            project.project_type.as_str(),
+        ]
+        .map(csv_field);
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// CSVのフィールドを必要に応じてダブルクォートで囲む（中のダブルクォートは2つ重ねる）
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 一覧表示の1行を表示
+///
+/// `show_age` が有効な場合は、末尾に最終更新からの経過時間を添えます。
+fn print_project_line(project: &Project, indent: usize, show_age: bool) {
+    let path_display = shorten_home_path(&project.path.to_string_lossy());
+    let age = match project.modified {
+        Some(modified) if show_age => format!(
+            " {}",
+            format_age(modified, SystemTime::now()).yellow().dimmed()
+        ),
+        _ => String::new(),
+    };
+    println!(
+        "{}{} {} {} {}{}",
+        " ".repeat(indent),
+        "•".cyan(),
+        project.name.bold(),
+        format_marker_tag(&project.marker),
+        format!("({})", path_display).dimmed(),
+        age
+    );
+}
+
+/// Unix時間の秒を `SystemTime` に変換（負の値は 1970-01-01 とみなす）
+fn unix_time(secs: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).unwrap_or(0))
+}
+
+/// 経過時間を "3d ago" のような短い表記に変換
+fn format_age(modified: SystemTime, now: SystemTime) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    // 未来の日時（時計のずれなど）は "just now" とする
+    let secs = now
+        .duration_since(modified)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    match secs {
+        s if s < MINUTE => "just now".to_string(),
+        s if s < HOUR => format!("{}m ago", s / MINUTE),
+        s if s < DAY => format!("{}h ago", s / HOUR),
+        s if s < 30 * DAY => format!("{}d ago", s / DAY),
+        s if s < 365 * DAY => format!("{}mo ago", s / (30 * DAY)),
+        s => format!("{}y ago", s / (365 * DAY)),
+    }
+}
+
+/// 監視中に検出したプロジェクトの追加・削除を表示
+pub fn print_project_diff(diff: &ProjectDiff) {
+    for project in &diff.added {
+        let path_display = shorten_home_path(&project.path.to_string_lossy());
+        println!(
+            "  {} {} {}",
+            "+".green().bold(),
+            project.name.bold(),
+            format!("({})", path_display).dimmed()
+        );
+    }
+
+    for project in &diff.removed {
+        let path_display = shorten_home_path(&project.path.to_string_lossy());
+        println!(
+            "  {} {} {}",
+            "-".red().bold(),
+            project.name.bold(),
+            format!("({})", path_display).dimmed()
+        );
+    }
+}
+
+/// セットアップで入力した設定の確認用の要約を表示
+pub fn print_setup_summary(config: &Config) {
+    println!();
+    println!("{}", "Summary:".bold());
+    println!();
+    println!("  Root paths:");
+    for root in &config.root_paths {
+        println!("    {}", shorten_home_path(&root.to_string_lossy()).cyan());
+    }
+    println!(
+        "  Editor:      {}",
+        config
+            .editor
+            .as_ref()
+            .map(|e| e.to_string())
+            .unwrap_or_else(|| "(not set, using $EDITOR or 'code')".to_string())
+            .cyan()
+    );
+    println!("  Max depth:   {}", config.max_depth.to_string().cyan());
+    println!();
+}
+
+/// プロジェクトの統計を表形式で表示
+///
+/// `from_cache` が `true` の場合は、スキャン時間にキャッシュを使ったことを併記します。
+pub fn print_stats(stats: &ProjectStats, elapsed_ms: u128, from_cache: bool) {
+    println!();
+    println!("{}", "Project statistics:".bold());
+    println!();
+    println!("  Total projects:  {}", stats.total.to_string().cyan());
+    println!(
+        "  Deepest project: {} levels",
+        stats.deepest.to_string().cyan()
+    );
+    if let Some((root, count)) = stats.largest_root() {
+        println!(
+            "  Largest root:    {} ({} projects)",
+            shorten_home_path(&root.to_string_lossy()).cyan(),
+            count
+        );
+    }
+    println!(
+        "  Scan time:       {}ms{}",
+        elapsed_ms.to_string().cyan(),
+        if from_cache {
+            " (served from cache)".dimmed().to_string()
+        } else {
+            String::new()
+        }
+    );
+
+    // 種別ごとの内訳
+    let type_rows: Vec<(String, usize)> = stats
+        .by_type
+        .iter()
+        .map(|(t, count)| (format!("{} {}", t.icon(), t.as_str()), *count))
+        .collect();
+    print_stats_table("By type", &type_rows, stats);
 
-    let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
-        .with_prompt("Select a project")
-        .items(&items)
-        .default(0)
-        .interact_on_opt(&Term::stderr())
-        .context("Failed to show selection UI")?;
+    // 検出マーカーごとの内訳
+    print_stats_table("By marker", &stats.by_marker, stats);
 
-    Ok(selection.map(|idx| &projects[idx]))
+    // ルートパスごとの内訳
+    let root_rows: Vec<(String, usize)> = stats
+        .by_root
+        .iter()
+        .map(|(root, count)| (shorten_home_path(&root.to_string_lossy()), *count))
+        .collect();
+    print_stats_table("By root", &root_rows, stats);
+
+    println!();
 }
 
-/// プロジェクト項目のフォーマット
-fn format_project_item(project: &Project) -> String {
-    // パスからホームディレクトリを短縮
-    let path_display = shorten_home_path(&project.path.to_string_lossy());
+/// 統計の内訳を「ラベル / 件数 / 割合」の列で揃えて表示
+fn print_stats_table(title: &str, rows: &[(String, usize)], stats: &ProjectStats) {
+    println!();
+    println!("  {}", title.bold());
 
-    format!(
-        "{} {}",
-        project.name.bold(),
-        format!("({})", path_display).dimmed()
-    )
-}
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| console::measure_text_width(label))
+        .max()
+        .unwrap_or(0);
+    let count_width = rows
+        .iter()
+        .map(|(_, count)| count.to_string().len())
+        .max()
+        .unwrap_or(0);
 
-/// ホームディレクトリを ~ に短縮
-fn shorten_home_path(path: &str) -> String {
-    if let Ok(home) = std::env::var("HOME") {
-        if path.starts_with(&home) {
-            return path.replacen(&home, "~", 1);
-        }
+    for (label, count) in rows {
+        println!(
+            "    {}  {:>count_width$}  {:>5.1}%",
+            console::pad_str(label, label_width, console::Alignment::Left, None),
+            count,
+            stats.percentage(*count),
+        );
     }
-    path.to_string()
 }
 
-/// スキャン結果のサマリーを表示
-pub fn print_scan_summary(projects: &[Project], elapsed_ms: u128) {
+/// `open-all` で開くプロジェクトの一覧を表示（`dry_run` では "Would open" と表示）
+pub fn print_open_all(projects: &[&Project], dry_run: bool) {
+    let verb = if dry_run { "Would open" } else { "Opening" };
+
     println!();
     println!(
-        "{} {} projects found in {}ms",
-        "✓".green().bold(),
-        projects.len().to_string().cyan(),
-        elapsed_ms
+        "{}",
+        format!("{} {}:", verb, plural(projects.len(), "project")).bold()
     );
+    println!();
+
+    for project in projects {
+        print_project_line(project, 2, false);
+    }
 }
 
-/// プロジェクト一覧を表示
-pub fn print_project_list(projects: &[Project]) {
-    if projects.is_empty() {
-        println!("{}", "No projects found.".yellow());
+/// よく開くプロジェクトの一覧を表示（開いた回数と最後に開いてからの経過時間つき）
+pub fn print_history(entries: &[&ProjectHistory], now: SystemTime) {
+    if entries.is_empty() {
+        println!("{}", "No project has been opened yet.".yellow());
         return;
     }
 
     println!();
-    println!("{}", "Projects:".bold());
+    println!("{}", "Most opened projects:".bold());
     println!();
 
-    for project in projects {
-        let path_display = shorten_home_path(&project.path.to_string_lossy());
+    let count_width = entries
+        .iter()
+        .map(|entry| entry.open_count.to_string().len())
+        .max()
+        .unwrap_or(1);
+
+    for entry in entries {
+        let name = entry
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| entry.path.display().to_string());
+        let last_opened = unix_time(entry.last_opened);
+
         println!(
-            "  {} {} {}",
-            "•".cyan(),
-            project.name.bold(),
-            format!("({})", path_display).dimmed()
+            "  {:>width$}× {} {} {}",
+            entry.open_count.to_string().green(),
+            name.bold(),
+            format!("({})", shorten_home_path(&entry.path.to_string_lossy())).dimmed(),
+            format_age(last_opened, now).dimmed(),
+            width = count_width
         );
     }
 
     println!();
-    println!("Total: {} projects", projects.len().to_string().cyan());
 }
 
 /// 登録済みパスの一覧を表示
@@ -112,6 +2027,23 @@ pub fn print_root_paths(paths: &[std::path::PathBuf]) {
     println!();
 }
 
+/// プロファイルの一覧を表示（使用中のものに印を付ける）
+pub fn print_profiles(profiles: &[String], active: &str) {
+    println!();
+    println!("{}", "Profiles:".bold());
+    println!();
+
+    for profile in profiles {
+        if profile == active {
+            println!("  {} {}", "*".green().bold(), profile.green().bold());
+        } else {
+            println!("    {}", profile);
+        }
+    }
+
+    println!();
+}
+
 /// 設定ファイルのパスを表示
 pub fn print_config_path(path: &std::path::Path) {
     println!();
@@ -129,6 +2061,40 @@ pub fn print_config_path(path: &std::path::Path) {
     println!();
 }
 
+/// 診断結果を表示
+pub fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    println!();
+    println!("{}", "Diagnostics:".bold());
+    println!();
+
+    for diagnostic in diagnostics {
+        let status = match diagnostic.severity {
+            Severity::Ok => "✓".green(),
+            Severity::Warning => "⚠".yellow(),
+            Severity::Error => "✗".red(),
+        };
+        println!("  {} {}", status, diagnostic.message);
+
+        if let Some(ref suggestion) = diagnostic.suggestion {
+            println!("    {}", suggestion.dimmed());
+        }
+    }
+
+    let count = |severity| {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    };
+
+    println!();
+    println!(
+        "{} errors, {} warnings",
+        count(Severity::Error).to_string().red(),
+        count(Severity::Warning).to_string().yellow()
+    );
+}
+
 /// エラーメッセージを表示
 pub fn print_error(message: &str) {
     eprintln!("{} {}", "Error:".red().bold(), message);
@@ -141,11 +2107,10 @@ pub fn print_success(message: &str) {
 
 /// 警告メッセージを表示
 pub fn print_warning(message: &str) {
-    println!("{} {}", "⚠".yellow().bold(), message);
+    eprintln!("{} {}", "⚠".yellow().bold(), message);
 }
 
 /// 情報メッセージを表示
-#[allow(dead_code)]
 pub fn print_info(message: &str) {
     println!("{} {}", "ℹ".blue().bold(), message);
 }
@@ -170,6 +2135,7 @@ pub fn print_banner() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scanner::ProjectType;
 
     #[test]
     fn test_shorten_home_path() {
@@ -182,15 +2148,645 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_selected_index() {
+        assert_eq!(
+            parse_selected_index("3\tapi (~/src/api)\t/home/u/src/api\n"),
+            Some(3)
+        );
+        assert_eq!(parse_selected_index(""), None);
+        assert_eq!(parse_selected_index("x\tfoo"), None);
+    }
+
     #[test]
     fn test_format_project_item() {
         let project = Project {
             path: std::path::PathBuf::from("/tmp/test-project"),
+            root: std::path::PathBuf::from("/tmp"),
             name: "test-project".to_string(),
-            marker: ".git".to_string(),
+            marker: "Cargo.toml".to_string(),
+            project_type: ProjectType::Rust,
+            modified: None,
+            overrides: None,
         };
 
-        let formatted = format_project_item(&project);
+        let mut options = DisplayOptions::default();
+        let formatted = format_project_item(&project, &options);
         assert!(formatted.contains("test-project"));
+        assert!(!formatted.contains("🦀"));
+
+        options.show_icons = true;
+        let with_icon = format_project_item(&project, &options);
+        assert!(with_icon.starts_with("🦀 "));
+    }
+
+    #[test]
+    fn test_parse_numbered_choice() {
+        let projects = vec![
+            make_project("/src", "/src/api", ProjectType::Rust),
+            make_project("/work", "/work/api", ProjectType::Go),
+            make_project("/src", "/src/Web", ProjectType::Node),
+        ];
+
+        assert_eq!(parse_numbered_choice(&projects, ""), Ok(None));
+        assert_eq!(parse_numbered_choice(&projects, "Q"), Ok(None));
+        assert_eq!(parse_numbered_choice(&projects, "1"), Ok(Some(0)));
+        assert_eq!(parse_numbered_choice(&projects, "3"), Ok(Some(2)));
+        assert!(parse_numbered_choice(&projects, "0").is_err());
+        assert!(parse_numbered_choice(&projects, "4").is_err());
+
+        // 名前は大文字小文字を区別せず完全一致。同名が複数あれば番号で選ばせる
+        assert_eq!(parse_numbered_choice(&projects, "web"), Ok(Some(2)));
+        assert!(parse_numbered_choice(&projects, "we").is_err());
+        let err = parse_numbered_choice(&projects, "api").unwrap_err();
+        assert!(err.contains("2 projects"));
+    }
+
+    #[test]
+    fn test_select_numbered_retries_until_valid() {
+        let projects = vec![
+            make_project("/src", "/src/api", ProjectType::Rust),
+            make_project("/src", "/src/web", ProjectType::Node),
+        ];
+        let options = DisplayOptions::default();
+        let mut out = Vec::new();
+
+        let mut input = std::io::Cursor::new("9\nweb\n");
+        let selected = select_numbered(&projects, &options, &mut input, &mut out).unwrap();
+        assert_eq!(selected, Some(1));
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("1) api"));
+        assert!(out.contains("2) web"));
+        assert!(out.contains("No project numbered 9"));
+
+        // 入力の終わりはキャンセル
+        let mut input = std::io::Cursor::new("");
+        let selected = select_numbered(&projects, &options, &mut input, &mut Vec::new()).unwrap();
+        assert_eq!(selected, None);
+    }
+
+    #[test]
+    fn test_plain_project_item_pinned() {
+        let dir = tempfile::tempdir().unwrap();
+        let pinned = Project::from_path(dir.path());
+        let stale = Project::from_path(&dir.path().join("moved-away"));
+        let options = DisplayOptions::default()
+            .with_pinned(BTreeSet::from([pinned.path.clone(), stale.path.clone()]));
+
+        let item = plain_project_item(&pinned, &options);
+        assert!(item.starts_with("★ "));
+        assert!(!options.is_stale(&pinned));
+
+        // 存在しなくなったピン留めも、名前で検索できるよう残す
+        let item = plain_project_item(&stale, &options);
+        assert!(item.starts_with("★ moved-away (missing: "));
+        assert!(options.is_stale(&stale));
+
+        let other = Project::from_path(&dir.path().join("other"));
+        assert!(!plain_project_item(&other, &options).contains('★'));
+        assert!(!options.is_stale(&other));
+    }
+
+    #[test]
+    fn test_expand_display_format() {
+        let project = Project {
+            path: std::path::PathBuf::from("/srv/work/api"),
+            root: std::path::PathBuf::from("/srv"),
+            name: "api".to_string(),
+            marker: "go.mod".to_string(),
+            project_type: ProjectType::Go,
+            modified: None,
+            overrides: None,
+        };
+
+        let now = UNIX_EPOCH + Duration::from_secs(10 * 86_400);
+        let expand = |template| expand_display_format(template, &project, None, now);
+
+        assert_eq!(expand("{parent}/{name}"), "work/api");
+        assert_eq!(
+            expand("{name} ({marker}, {type}) {full_path}"),
+            "api (go.mod, go) /srv/work/api"
+        );
+        // {path} は従来どおり ~ 短縮済みのパス
+        assert_eq!(expand("{path}"), expand("{short_path}"));
+        assert_eq!(expand("{unknown} {name"), "{unknown} {name");
+        assert_eq!(expand("{open_count}x {last_opened}"), "0x never");
+        assert_eq!(expand("{name}: {description}"), "api: ");
+
+        let entry = ProjectHistory {
+            path: project.path.clone(),
+            open_count: 7,
+            last_opened: 8 * 86_400,
+        };
+        assert_eq!(
+            expand_display_format(
+                "{name} {open_count}x {last_opened}",
+                &project,
+                Some(&entry),
+                now
+            ),
+            "api 7x 2d ago"
+        );
+    }
+
+    #[test]
+    fn test_plain_project_item_has_no_ansi() {
+        let project = Project {
+            path: std::path::PathBuf::from("/tmp/test-project"),
+            root: std::path::PathBuf::from("/tmp"),
+            name: "test-project".to_string(),
+            marker: "Cargo.toml".to_string(),
+            project_type: ProjectType::Rust,
+            modified: None,
+            overrides: None,
+        };
+
+        let item = plain_project_item(&project, &DisplayOptions::default());
+        assert!(!item.contains('\x1b'));
+        assert!(item.starts_with("test-project [rust]"));
+
+        let options = DisplayOptions {
+            tags: HashMap::from([(
+                project.path.clone(),
+                vec!["active".to_string(), "work".to_string()],
+            )]),
+            ..DisplayOptions::default()
+        };
+        let item = plain_project_item(&project, &options);
+        assert!(item.starts_with("test-project [rust] [active, work] ("));
+    }
+
+    #[test]
+    fn test_expand_prompt() {
+        let options = DisplayOptions::from_config(&Config {
+            prompt: "Select a project ({count} found)".to_string(),
+            ..Config::default()
+        });
+        assert_eq!(options.expand_prompt(123), "Select a project (123 found)");
+
+        let options = DisplayOptions::from_config(&Config::default());
+        assert_eq!(options.expand_prompt(5), "Select a project");
+    }
+
+    #[test]
+    fn test_format_project_table() {
+        let project = |name: &str, marker: &str, project_type, path: &str| Project {
+            path: PathBuf::from(path),
+            root: PathBuf::from("/src"),
+            name: name.to_string(),
+            marker: marker.to_string(),
+            project_type,
+            modified: None,
+            overrides: None,
+        };
+        let projects = vec![
+            project("api", "Cargo.toml", ProjectType::Rust, "/src/api"),
+            project("web|ui", "package.json", ProjectType::Node, "/src/web"),
+        ];
+
+        assert_eq!(
+            format_project_table(&projects),
+            "| NAME    | TYPE | MARKER       | PATH     |\n\
+             |---------|------|--------------|----------|\n\
+             | api     | rust | Cargo.toml   | /src/api |\n\
+             | web\\|ui | node | package.json | /src/web |"
+        );
+        assert_eq!(format_project_table(&[]).lines().count(), 2);
+    }
+
+    #[test]
+    fn test_format_project_csv() {
+        let project = |name: &str, path: &str| Project {
+            path: PathBuf::from(path),
+            root: PathBuf::from("/src"),
+            name: name.to_string(),
+            marker: "Cargo.toml".to_string(),
+            project_type: ProjectType::Rust,
+            modified: None,
+            overrides: None,
+        };
+        let projects = vec![
+            project("api", "/src/api"),
+            project("say \"hi\", world", "/src/a,b"),
+        ];
+
+        assert_eq!(
+            format_project_csv(&projects),
+            "name,path,marker,type\r\n\
+             api,/src/api,Cargo.toml,rust\r\n\
+             \"say \"\"hi\"\", world\",\"/src/a,b\",Cargo.toml,rust\r\n"
+        );
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(400 * 86_400);
+        let ago = |secs: u64| format_age(now - std::time::Duration::from_secs(secs), now);
+
+        assert_eq!(ago(5), "just now");
+        assert_eq!(ago(5 * 60), "5m ago");
+        assert_eq!(ago(3 * 3600), "3h ago");
+        assert_eq!(ago(3 * 86_400), "3d ago");
+        assert_eq!(ago(65 * 86_400), "2mo ago");
+        assert_eq!(ago(370 * 86_400), "1y ago");
+        assert_eq!(
+            format_age(now + std::time::Duration::from_secs(60), now),
+            "just now"
+        );
+    }
+
+    #[test]
+    fn test_format_add_summary() {
+        assert_eq!(
+            format_add_summary(3, 1, 1),
+            "Added 3, skipped 1 duplicate, 1 error"
+        );
+        assert_eq!(format_add_summary(2, 0, 0), "Added 2");
+        assert_eq!(
+            format_add_summary(0, 2, 3),
+            "Added 0, skipped 2 duplicates, 3 errors"
+        );
+    }
+
+    #[test]
+    fn test_format_remove_summary() {
+        assert_eq!(format_remove_summary(2, 0, 0), "Removed 2");
+        assert_eq!(
+            format_remove_summary(1, 2, 1),
+            "Removed 1, 2 not found, 1 error"
+        );
+    }
+
+    #[test]
+    fn test_color_mode() {
+        assert_eq!("always".parse::<ColorMode>(), Ok(ColorMode::Always));
+        assert_eq!("Never".parse::<ColorMode>(), Ok(ColorMode::Never));
+        assert!("sometimes".parse::<ColorMode>().is_err());
+
+        assert!(should_colorize(ColorMode::Auto, false, true));
+        assert!(!should_colorize(ColorMode::Auto, true, true));
+        assert!(!should_colorize(ColorMode::Auto, false, false));
+        assert!(should_colorize(ColorMode::Always, true, false));
+        assert!(!should_colorize(ColorMode::Never, false, true));
+    }
+
+    fn search(query: &str, mode: MatchMode, case: CaseSensitivity) -> Search {
+        Search {
+            query: query.to_string(),
+            mode,
+            case,
+        }
+    }
+
+    #[test]
+    fn test_filter_items_match_mode() {
+        let items: Vec<String> = ["── rust ──", "quick-proj", "quill", "Pick"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let indices = vec![None, Some(0), Some(1), Some(2)];
+        let insensitive = CaseSensitivity::Insensitive;
+
+        // クエリが空なら見出しを含めてすべて表示
+        let all = filter_items(&items, &indices, &search("", MatchMode::Exact, insensitive));
+        assert_eq!(all, vec![0, 1, 2, 3]);
+
+        // あいまい検索は飛び飛びの文字にも一致する
+        let fuzzy = filter_items(
+            &items,
+            &indices,
+            &search("qp", MatchMode::Fuzzy, insensitive),
+        );
+        assert_eq!(fuzzy, vec![1]);
+        let exact = filter_items(
+            &items,
+            &indices,
+            &search("qp", MatchMode::Exact, insensitive),
+        );
+        assert!(exact.is_empty());
+
+        // 完全一致検索は部分文字列で、元の順序を保つ
+        let exact = filter_items(
+            &items,
+            &indices,
+            &search("ick", MatchMode::Exact, insensitive),
+        );
+        assert_eq!(exact, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_filter_items_case_sensitivity() {
+        let items: Vec<String> = ["quick-proj", "Pick"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let indices = vec![Some(0), Some(1)];
+
+        for mode in [MatchMode::Fuzzy, MatchMode::Exact] {
+            let insensitive = search("PICK", mode, CaseSensitivity::Insensitive);
+            assert_eq!(filter_items(&items, &indices, &insensitive), vec![1]);
+
+            let sensitive = search("PICK", mode, CaseSensitivity::Sensitive);
+            assert!(filter_items(&items, &indices, &sensitive).is_empty());
+
+            let sensitive = search("Pi", mode, CaseSensitivity::Sensitive);
+            assert_eq!(filter_items(&items, &indices, &sensitive), vec![1]);
+        }
+    }
+
+    #[test]
+    fn test_nth_visible_project() {
+        // 見出し行（None）は数えない
+        let indices = vec![None, Some(2), Some(0), None, Some(1)];
+        let visible = vec![0, 1, 2, 3, 4];
+        assert_eq!(nth_visible_project(&visible, &indices, 1), Some(2));
+        assert_eq!(nth_visible_project(&visible, &indices, 3), Some(1));
+        assert_eq!(nth_visible_project(&visible, &indices, 4), None);
+        assert_eq!(nth_visible_project(&visible, &indices, 0), None);
+        assert_eq!(nth_visible_project(&visible[2..], &indices, 1), Some(0));
+    }
+
+    #[test]
+    fn test_next_selectable() {
+        // 0: 見出し, 1-2: プロジェクト, 3: 見出し, 4: プロジェクト
+        let indices = vec![None, Some(0), Some(1), None, Some(2)];
+        let visible: Vec<usize> = (0..indices.len()).collect();
+
+        assert_eq!(next_selectable(&visible, &indices, 0, 1), 1);
+        assert_eq!(next_selectable(&visible, &indices, 3, 1), 4);
+        assert_eq!(next_selectable(&visible, &indices, 3, -1), 2);
+        // 範囲外は末尾に収め、その方向に見つからなければ逆方向に探す
+        assert_eq!(next_selectable(&visible, &indices, 10, 1), 4);
+        assert_eq!(next_selectable(&visible, &indices, 0, -1), 1);
+
+        // 絞り込み後の表示行を対象にする
+        assert_eq!(next_selectable(&[0, 3, 4], &indices, 0, 1), 2);
+        // 選択できる行がなければ範囲内に収めた位置のまま
+        assert_eq!(next_selectable(&[0, 3], &indices, 5, 1), 1);
+        assert_eq!(next_selectable(&[], &indices, 3, 1), 0);
+    }
+
+    #[test]
+    fn test_scroll_offset() {
+        // 表示範囲内なら動かさない
+        assert_eq!(scroll_offset(3, 0, 5), 0);
+        assert_eq!(scroll_offset(4, 2, 5), 2);
+        // 下にはみ出したらカーソルが最下行になるまで進める
+        assert_eq!(scroll_offset(5, 0, 5), 1);
+        assert_eq!(scroll_offset(20, 0, 5), 16);
+        // 上にはみ出したらカーソルが最上行になるまで戻す
+        assert_eq!(scroll_offset(2, 6, 5), 2);
+    }
+
+    #[test]
+    fn test_screen_redraws_only_changed_rows() {
+        let frame = |second: &str| -> Vec<Row> {
+            vec![
+                vec![(0, "header".to_string())],
+                vec![(0, second.to_string()), (10, "preview".to_string())],
+                vec![(0, "status".to_string())],
+            ]
+        };
+        let mut screen = Screen::default();
+
+        let mut out = Vec::new();
+        screen.draw(&mut out, 80, frame("first")).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("header") && output.contains("first"));
+
+        // 同じ内容なら何も出力しない
+        let mut out = Vec::new();
+        screen.draw(&mut out, 80, frame("first")).unwrap();
+        assert!(out.is_empty());
+
+        // 変わった行だけを描き直す
+        let mut out = Vec::new();
+        screen.draw(&mut out, 80, frame("second")).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("second") && output.contains("preview"));
+        assert!(!output.contains("header") && !output.contains("status"));
+
+        // ステータスを上書きした行は次の描画で元に戻す
+        let mut out = Vec::new();
+        screen.draw_status(&mut out, 3, "Rescanning").unwrap();
+        screen.draw(&mut out, 80, frame("second")).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("status") && !output.contains("header"));
+
+        // 端末の幅が変わったらすべて描き直す
+        let mut out = Vec::new();
+        screen.draw(&mut out, 100, frame("second")).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("header") && output.contains("status"));
+    }
+
+    #[test]
+    fn test_prepend_flake_description() {
+        let mut lines = vec!["Title".to_string(), "body".to_string()];
+        prepend_flake_description(&mut lines, "A dev shell");
+        assert!(lines[0].contains("A dev shell"));
+        assert_eq!(lines[1..], ["", "Title", "body"]);
+    }
+
+    #[test]
+    fn test_position_of_keeps_cursor_after_rescan() {
+        let options = DisplayOptions {
+            group_by: Some(GroupMode::Type),
+            ..DisplayOptions::default()
+        };
+        let insensitive = CaseSensitivity::Insensitive;
+
+        // 再スキャンで順序が変わり、プロジェクトが増えても同じパスの行を選ぶ
+        let rescanned = vec![
+            make_project("/src", "/src/new", ProjectType::Go),
+            make_project("/src", "/src/b", ProjectType::Node),
+            make_project("/src", "/src/a", ProjectType::Rust),
+        ];
+        let (items, indices) = select_items(&rescanned, &options);
+        let visible = filter_items(&items, &indices, &search("", MatchMode::Fuzzy, insensitive));
+        let selected = position_of(&visible, &indices, &rescanned, Some(Path::new("/src/b")));
+        assert_eq!(indices[visible[selected]], Some(1));
+
+        // 検索で絞り込んだ一覧でも位置を引き継ぐ
+        let visible = filter_items(
+            &items,
+            &indices,
+            &search("a", MatchMode::Exact, insensitive),
+        );
+        let selected = position_of(&visible, &indices, &rescanned, Some(Path::new("/src/a")));
+        assert_eq!(indices[visible[selected]], Some(2));
+
+        // 消えたプロジェクトの場合は先頭の選択可能な行（見出しは飛ばす）
+        let visible = filter_items(&items, &indices, &search("", MatchMode::Fuzzy, insensitive));
+        let selected = position_of(&visible, &indices, &rescanned, Some(Path::new("/src/gone")));
+        assert!(indices[visible[selected]].is_some());
+        assert_eq!(selected, next_selectable(&visible, &indices, 0, 1));
+    }
+
+    #[test]
+    fn test_quick_open_labels() {
+        let indices: Vec<Option<usize>> = std::iter::once(None).chain((0..10).map(Some)).collect();
+        let visible: Vec<usize> = (0..indices.len()).collect();
+
+        let labels = quick_open_labels(&visible, &indices);
+        assert_eq!(labels[0], None);
+        assert_eq!(labels[1], Some(1));
+        assert_eq!(labels[9], Some(9));
+        assert_eq!(labels[10], None);
+
+        // 番号と数字キーで開くプロジェクトが一致する
+        for (row, label) in labels.iter().enumerate() {
+            if let Some(n) = label {
+                assert_eq!(
+                    nth_visible_project(&visible, &indices, *n),
+                    indices[visible[row]]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_status_bar() {
+        let mut options = DisplayOptions::default();
+        assert_eq!(
+            format_status_bar(3, 40, &options),
+            "3/40 · sort: name · group: none"
+        );
+
+        options = options.with_sort(SortCriterion::Frecency);
+        options.group_by = Some(GroupMode::Root);
+        assert_eq!(
+            format_status_bar(40, 40, &options),
+            "40/40 · sort: frecency · group: root"
+        );
+    }
+
+    #[test]
+    fn test_match_positions() {
+        let fuzzy = search("qp", MatchMode::Fuzzy, CaseSensitivity::Insensitive);
+        assert_eq!(match_positions("quick-proj", &fuzzy), vec![0, 6]);
+        assert!(match_positions("web", &fuzzy).is_empty());
+
+        let exact = search("Ap", MatchMode::Exact, CaseSensitivity::Insensitive);
+        assert_eq!(match_positions("app-api", &exact), vec![0, 1, 4, 5]);
+
+        let exact = search("Ap", MatchMode::Exact, CaseSensitivity::Sensitive);
+        assert!(match_positions("app-api", &exact).is_empty());
+
+        // 複数バイトの文字を含んでも文字単位の位置を返す
+        let exact = search("api", MatchMode::Exact, CaseSensitivity::Insensitive);
+        assert_eq!(match_positions("🦀 api", &exact), vec![2, 3, 4]);
+
+        let empty = search("", MatchMode::Fuzzy, CaseSensitivity::Insensitive);
+        assert!(match_positions("api", &empty).is_empty());
+    }
+
+    #[test]
+    fn test_highlight_matches_keeps_text() {
+        let fuzzy = search("ap", MatchMode::Fuzzy, CaseSensitivity::Insensitive);
+        let highlighted = highlight_matches("🦀 my-app (~/src)", &fuzzy, |s| s.cyan());
+        assert_eq!(console::strip_ansi_codes(&highlighted), "🦀 my-app (~/src)");
+    }
+
+    #[test]
+    fn test_match_mode_toggle() {
+        assert_eq!(MatchMode::default(), MatchMode::Fuzzy);
+        assert_eq!(MatchMode::Fuzzy.toggle(), MatchMode::Exact);
+        assert_eq!(MatchMode::Exact.toggle().label(), "fuzzy");
+    }
+
+    #[test]
+    fn test_marker_label() {
+        assert_eq!(marker_label("Cargo.toml").0, "rust");
+        assert_eq!(marker_label("package.json").0, "node");
+        assert_eq!(marker_label("go.mod").0, "go");
+        assert_eq!(marker_label(".git").0, "git");
+        assert_eq!(marker_label("*.sln").0, "dotnet");
+        assert_eq!(marker_label("pubspec.yaml").0, "dart");
+        assert_eq!(marker_label("custom.marker").0, "custom.marker");
+    }
+
+    #[test]
+    fn test_format_marker_tag() {
+        assert!(format_marker_tag("Cargo.toml").contains("[rust]"));
+        assert!(format_marker_tag(".git").contains("[git]"));
+    }
+
+    fn make_project(root: &str, path: &str, project_type: ProjectType) -> Project {
+        let path = std::path::PathBuf::from(path);
+        Project {
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            path,
+            root: PathBuf::from(root),
+            marker: String::new(),
+            project_type,
+            modified: None,
+            overrides: None,
+        }
+    }
+
+    #[test]
+    fn test_group_projects_by_root() {
+        let projects = vec![
+            make_project("/src/rust", "/src/rust/a", ProjectType::Rust),
+            make_project("/src", "/src/b", ProjectType::Node),
+            make_project("/work", "/work/c", ProjectType::Go),
+            make_project("/elsewhere", "/elsewhere/d", ProjectType::Generic),
+        ];
+        let roots = vec![
+            PathBuf::from("/work"),
+            PathBuf::from("/src"),
+            PathBuf::from("/src/rust"),
+        ];
+
+        let groups = group_projects(&projects, GroupMode::Root, &roots);
+        let indices: Vec<&Vec<usize>> = groups.iter().map(|(_, g)| g).collect();
+
+        // 設定の順序に従い、検出元のルートに所属する
+        assert_eq!(indices, [&vec![2], &vec![1], &vec![0], &vec![3]]);
+        assert_eq!(groups[0].0, "/work");
+        assert_eq!(groups[3].0, "other");
+    }
+
+    #[test]
+    fn test_group_projects_by_type() {
+        let projects = vec![
+            make_project("/src", "/src/a", ProjectType::Node),
+            make_project("/src", "/src/b", ProjectType::Rust),
+            make_project("/src", "/src/c", ProjectType::Node),
+        ];
+
+        let groups = group_projects(&projects, GroupMode::Type, &[]);
+        assert_eq!(
+            groups,
+            vec![
+                ("rust".to_string(), vec![1]),
+                ("node".to_string(), vec![0, 2]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_projects_by_marker() {
+        let mut projects = vec![
+            make_project("/src", "/src/a", ProjectType::Node),
+            make_project("/src", "/src/b", ProjectType::Node),
+            make_project("/src", "/src/c", ProjectType::Rust),
+        ];
+        projects[0].marker = "package.json".to_string();
+        projects[1].marker = "deno.json".to_string();
+        projects[2].marker = "Cargo.toml".to_string();
+
+        let groups = group_projects(&projects, GroupMode::Marker, &[]);
+        assert_eq!(
+            groups,
+            vec![
+                ("deno".to_string(), vec![1]),
+                ("node".to_string(), vec![0]),
+                ("rust".to_string(), vec![2]),
+            ]
+        );
     }
 }
@@ -0,0 +1,147 @@
+//! 監視モジュール
+//!
+//! ルートパスの変更を監視し、プロジェクトの追加・削除を検出します。
+
+use crate::config::Config;
+use crate::launcher;
+use crate::scanner::{Project, Scanner};
+use anyhow::{Context, Result};
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// 変更イベントをまとめる待ち時間
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// スキャン結果の差分
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ProjectDiff {
+    /// 新しく検出されたプロジェクト
+    pub added: Vec<Project>,
+    /// 検出されなくなったプロジェクト
+    pub removed: Vec<Project>,
+}
+
+impl ProjectDiff {
+    /// 差分がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// 2つのスキャン結果の差分をパスで比較して求める
+pub fn diff_projects(old: &[Project], new: &[Project]) -> ProjectDiff {
+    let old_paths: HashSet<&PathBuf> = old.iter().map(|p| &p.path).collect();
+    let new_paths: HashSet<&PathBuf> = new.iter().map(|p| &p.path).collect();
+
+    ProjectDiff {
+        added: new
+            .iter()
+            .filter(|p| !old_paths.contains(&p.path))
+            .cloned()
+            .collect(),
+        removed: old
+            .iter()
+            .filter(|p| !new_paths.contains(&p.path))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// ルートパスを監視し、変更があるたびに `on_change` を呼び出す
+///
+/// イベントは [`DEBOUNCE`] の間まとめてから再スキャンします。
+/// `exec` が指定されている場合は、変更ごとにシェルコマンドとして実行します。
+pub fn run(config: &Config, exec: Option<&str>, on_change: impl Fn(&ProjectDiff)) -> Result<()> {
+    let scanner = Scanner::from_config(config);
+    let mut projects = scanner.scan(&config.root_paths)?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).context("Failed to create file system watcher")?;
+
+    for root in config.root_paths.iter().filter(|p| p.exists()) {
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch: {}", root.display()))?;
+    }
+
+    while rx.recv().is_ok() {
+        // 連続するイベントが落ち着くまで待つ
+        while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+        let rescanned = scanner.scan(&config.root_paths)?;
+        let diff = diff_projects(&projects, &rescanned);
+        projects = rescanned;
+
+        if diff.is_empty() {
+            continue;
+        }
+
+        on_change(&diff);
+
+        if let Some(command) = exec {
+            run_exec(command, &diff)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 変更時のコマンドを実行
+///
+/// 追加・削除されたプロジェクトのパスを改行区切りで
+/// `QUICK_PROJ_ADDED` と `QUICK_PROJ_REMOVED` に設定します。
+fn run_exec(command: &str, diff: &ProjectDiff) -> Result<()> {
+    let join = |projects: &[Project]| {
+        projects
+            .iter()
+            .map(|p| p.path.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    launcher::shell_command(command)
+        .env("QUICK_PROJ_ADDED", join(&diff.added))
+        .env("QUICK_PROJ_REMOVED", join(&diff.removed))
+        .status()
+        .with_context(|| format!("Failed to run command: {}", command))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::ProjectType;
+
+    fn make_project(path: &str) -> Project {
+        Project {
+            path: PathBuf::from(path),
+            root: PathBuf::from("/"),
+            name: path.rsplit('/').next().unwrap().to_string(),
+            marker: ".git".to_string(),
+            project_type: ProjectType::Generic,
+            modified: None,
+            overrides: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_projects() {
+        let old = vec![make_project("/src/a"), make_project("/src/b")];
+        let new = vec![make_project("/src/b"), make_project("/src/c")];
+
+        let diff = diff_projects(&old, &new);
+        assert_eq!(diff.added, vec![make_project("/src/c")]);
+        assert_eq!(diff.removed, vec![make_project("/src/a")]);
+    }
+
+    #[test]
+    fn test_diff_projects_unchanged() {
+        let projects = vec![make_project("/src/a")];
+        assert!(diff_projects(&projects, &projects).is_empty());
+    }
+}
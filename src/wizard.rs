@@ -0,0 +1,228 @@
+//! 初回セットアップモジュール
+//!
+//! 対話形式でルートパス・エディタ・スキャンの最大深度を設定し、確認してから保存します。
+//! 設定ファイルがない状態で選択UIを起動したときと、`quick-proj setup` で実行します。
+
+use crate::config::{expand_path, Config, EditorSetting, MAX_DEPTH_RANGE};
+use crate::launcher::get_available_editors;
+use crate::ui;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use console::Term;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, MultiSelect};
+use std::path::{Path, PathBuf};
+
+/// ルートパスの候補として提示するホームディレクトリ直下のディレクトリ
+const ROOT_CANDIDATES: &[&str] = &[
+    "~/src",
+    "~/projects",
+    "~/dev",
+    "~/code",
+    "~/workspace",
+    "~/repos",
+    "~/git",
+];
+
+/// 対話形式で設定を行う
+///
+/// 現在の設定（設定ファイルがない場合はデフォルト）を初期値として使います。
+/// 保存した場合はその設定を返し、保存を確認して拒否された場合は何も保存せずに `None` を返します。
+pub fn run() -> Result<Option<Config>> {
+    let mut config = Config::load_raw()?;
+    let theme = ColorfulTheme::default();
+    let term = Term::stderr();
+
+    println!();
+    println!(
+        "{}",
+        "Welcome to quick-proj! Let's set up your config.".bold()
+    );
+    println!();
+
+    choose_root_paths(&mut config, &theme, &term)?;
+    choose_editor(&mut config, &theme, &term)?;
+    config.max_depth = Input::with_theme(&theme)
+        .with_prompt("Max scan depth")
+        .default(config.max_depth)
+        .validate_with(|depth: &usize| {
+            if MAX_DEPTH_RANGE.contains(depth) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Enter a value between {} and {}",
+                    MAX_DEPTH_RANGE.start(),
+                    MAX_DEPTH_RANGE.end()
+                ))
+            }
+        })
+        .interact_text_on(&term)
+        .context("Failed to read max depth")?;
+
+    ui::print_setup_summary(&config);
+
+    let save = Confirm::with_theme(&theme)
+        .with_prompt("Save this configuration?")
+        .default(true)
+        .interact_on(&term)
+        .context("Failed to show confirmation prompt")?;
+    if !save {
+        ui::print_warning("Setup cancelled. Nothing was saved.");
+        return Ok(None);
+    }
+
+    config.save()?;
+    ui::print_success(&format!(
+        "Saved config to {}",
+        Config::config_path()?.display()
+    ));
+
+    Ok(Some(config))
+}
+
+/// ルートパスを選ぶ（少なくとも1つ）
+///
+/// 登録済みのルートパスと、存在する候補ディレクトリから複数選択し、
+/// さらに任意のパスを入力で追加できます。
+fn choose_root_paths(config: &mut Config, theme: &ColorfulTheme, term: &Term) -> Result<()> {
+    let current = std::mem::take(&mut config.root_paths);
+    let suggested: Vec<PathBuf> = ROOT_CANDIDATES
+        .iter()
+        .filter_map(|candidate| expand_path(Path::new(candidate)).ok())
+        .collect();
+    let candidates = root_candidates(&current, &suggested);
+
+    if !candidates.is_empty() {
+        // 再実行時は登録済みのものを、初回はすべての候補を選択済みにする
+        let defaults: Vec<bool> = candidates
+            .iter()
+            .map(|path| current.is_empty() || current.contains(path))
+            .collect();
+        let labels: Vec<String> = candidates
+            .iter()
+            .map(|path| ui::shorten_home_path(&path.to_string_lossy()))
+            .collect();
+
+        let selected = MultiSelect::with_theme(theme)
+            .with_prompt("Root paths to scan for projects (Space to toggle, Enter to confirm)")
+            .items(&labels)
+            .defaults(&defaults)
+            .interact_on(term)
+            .context("Failed to select root paths")?;
+        for idx in selected {
+            add_root_path(config, &candidates[idx]);
+        }
+    }
+
+    loop {
+        let prompt = if config.root_paths.is_empty() {
+            "Add a root path"
+        } else {
+            "Add another root path (leave empty to finish)"
+        };
+        let input: String = Input::with_theme(theme)
+            .with_prompt(prompt)
+            .allow_empty(true)
+            .interact_text_on(term)
+            .context("Failed to read root path")?;
+
+        let input = input.trim();
+        if input.is_empty() {
+            if !config.root_paths.is_empty() {
+                return Ok(());
+            }
+            ui::print_warning("Add at least one root path.");
+            continue;
+        }
+        add_root_path(config, Path::new(input));
+    }
+}
+
+/// 登録済みのルートパスと、`suggested` のうち存在するディレクトリ（重複なし）
+fn root_candidates(current: &[PathBuf], suggested: &[PathBuf]) -> Vec<PathBuf> {
+    let mut candidates = current.to_vec();
+    for path in suggested {
+        if path.is_dir() && !candidates.contains(path) {
+            candidates.push(path.clone());
+        }
+    }
+    candidates
+}
+
+/// ルートパスを追加し、追加できなかった場合は警告する
+fn add_root_path(config: &mut Config, path: &Path) {
+    if let Err(e) = config.add_root_path(path) {
+        ui::print_warning(&format!("{:#}", e));
+    }
+}
+
+/// デフォルトのエディタを選ぶ
+///
+/// PATH上にあるエディタから複数選択でき、複数選んだ場合は一覧の順にフォールバックします。
+/// 見つからない場合や何も選ばなかった場合は、コマンドを入力できます（空の場合は変更しない）。
+fn choose_editor(config: &mut Config, theme: &ColorfulTheme, term: &Term) -> Result<()> {
+    let available = get_available_editors();
+    let current = config
+        .editor
+        .as_ref()
+        .map(EditorSetting::to_vec)
+        .unwrap_or_default();
+
+    let mut editors = Vec::new();
+    if !available.is_empty() {
+        let defaults: Vec<bool> = available
+            .iter()
+            .map(|editor| current.contains(editor))
+            .collect();
+        let selected = MultiSelect::with_theme(theme)
+            .with_prompt("Default editor (select several to try them in order)")
+            .items(&available)
+            .defaults(&defaults)
+            .interact_on(term)
+            .context("Failed to select editor")?;
+        editors = selected
+            .into_iter()
+            .map(|idx| available[idx].clone())
+            .collect();
+    }
+
+    if editors.is_empty() {
+        let input: String = Input::with_theme(theme)
+            .with_prompt("Editor command (leave empty to use $EDITOR)")
+            .allow_empty(true)
+            .interact_text_on(term)
+            .context("Failed to read editor")?;
+        let input = input.trim();
+        if !input.is_empty() {
+            editors.push(input.to_string());
+        }
+    }
+
+    match editors.len() {
+        0 => {}
+        1 => config.editor = editors.pop().map(EditorSetting::Single),
+        _ => config.editor = Some(EditorSetting::Chain(editors)),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_candidates_keep_current_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let (src, code) = (dir.path().join("src"), dir.path().join("code"));
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::create_dir_all(&code).unwrap();
+
+        let current = vec![PathBuf::from("/srv/work"), src.clone()];
+        let suggested = vec![src.clone(), dir.path().join("missing"), code.clone()];
+        let candidates = root_candidates(&current, &suggested);
+
+        // 登録済みのものを先頭に、存在する候補だけを重複なく続ける
+        assert_eq!(candidates, vec![PathBuf::from("/srv/work"), src, code]);
+    }
+}
@@ -0,0 +1,38 @@
+//! ライブラリとしての利用のテスト
+
+use quick_proj::{filter_projects, CaseSensitivity, Config, ProjectType, Scanner};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_scan_and_filter_as_library() {
+    let root = tempdir().unwrap();
+    for (dir, marker) in [
+        ("work/api", "Cargo.toml"),
+        ("work/web", "package.json"),
+        ("notes", "README.md"),
+    ] {
+        let path = root.path().join(dir);
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join(marker), "").unwrap();
+    }
+
+    let config = Config {
+        root_paths: vec![root.path().to_path_buf()],
+        ..Config::default()
+    };
+    let scanner = Scanner::from_config(&config);
+    let projects = scanner.scan(&config.root_paths).unwrap();
+
+    let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["api", "web"]);
+    assert_eq!(projects[0].project_type, ProjectType::Rust);
+    assert!(scanner.take_errors().is_empty());
+
+    let filtered = filter_projects(&projects, "WORK web", CaseSensitivity::Insensitive);
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(
+        filtered[0].display_string(),
+        format!("web ({})", root.path().join("work/web").display())
+    );
+}